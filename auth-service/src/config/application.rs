@@ -1,12 +1,17 @@
+use common_config::ConfigLoader;
 use common_core::{AppError, application::Snowflake};
+use common_grpc::application::GrpcClient;
 use common_redis::application::Redis;
+use common_registry::application::Registry;
 use common_tracing::application::Logs;
-use common_web::application::Server;
+use common_web::application::{InternalIdentity, Server};
 use serde::Deserialize;
-use std::fs;
+
+/// 默认配置文件路径（相对工作区根目录）
+pub const DEFAULT_CONFIG_PATH: &str = "auth-service/application.yaml";
 
 #[derive(Debug, Clone, Deserialize)]
-pub struct JWT {
+pub struct Jwt {
     pub secret: String,
     pub expiration_hours: u64,
     pub refresh_expiration_hours: u64,
@@ -14,46 +19,28 @@ pub struct JWT {
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Services {
-    pub user_service_grpc: String,
+    /// 用户服务 gRPC 客户端
+    pub user_service: GrpcClient,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct AppConfig {
     pub redis: Redis,
     pub snowflake: Snowflake,
-    pub jwt: JWT,
+    pub jwt: Jwt,
     pub server: Server,
     pub services: Services,
     pub logs: Logs,
+    /// 内部身份密钥，用于签发调用用户服务的服务间凭证
+    pub identity: InternalIdentity,
+    /// 服务注册与发现，不配置时不注册，用户服务使用固定地址
+    #[serde(default)]
+    pub registry: Option<Registry>,
 }
 
 impl AppConfig {
-    /// 从 YAML 文件加载配置
-    pub fn from_yaml(path: &str) -> Result<Self, AppError> {
-        let content = fs::read_to_string(path).map_err(|e| {
-            AppError::internal(format!("Failed to read config file {}: {}", path, e))
-        })?;
-
-        let config: AppConfig = serde_yml::from_str(&content)
-            .map_err(|e| AppError::internal(format!("Failed to parse config file: {}", e)))?;
-
-        Ok(config)
-    }
-
-    /// 从默认路径加载配置
-    pub fn from_default_yaml() -> Result<Self, AppError> {
-        let possible_paths = [
-            "src/application.yaml",
-            "application.yaml",
-            "auth-service/application.yaml",
-        ];
-        for path in &possible_paths {
-            if let Ok(config) = Self::from_yaml(path) {
-                return Ok(config);
-            }
-        }
-        Err(AppError::internal(
-            "Failed to load config from any default path. Tried: src/application.yaml, application.yaml",
-        ))
+    /// 加载配置（分层规则见 `common_config`）
+    pub fn load() -> Result<Self, AppError> {
+        ConfigLoader::new(DEFAULT_CONFIG_PATH).load()
     }
 }
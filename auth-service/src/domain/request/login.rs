@@ -1,13 +1,17 @@
 use serde::Deserialize;
+use validator::Validate;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Validate)]
 pub struct LoginWeb3Request {
+    #[validate(length(min = 1, max = 256, message = "signature must be 1-256 characters"))]
     pub signature: String,
+    #[validate(length(min = 1, max = 1024, message = "message must be 1-1024 characters"))]
     pub message: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Validate)]
 pub struct LoginWeb3NonceQuery {
     pub chain_id: i64,
+    #[validate(length(min = 1, max = 128, message = "address must be 1-128 characters"))]
     pub address: String,
 }
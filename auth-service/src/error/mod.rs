@@ -1,2 +0,0 @@
-pub mod error;
-pub use error::ApiError;
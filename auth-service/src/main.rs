@@ -1,14 +1,13 @@
 mod config;
 mod domain;
-mod error;
-mod grpc;
 mod routes;
 mod services;
 mod startup;
 
 use common_core::AppError;
 use common_tracing::TracingService;
-use startup::{init_app_config, init_app_state, start_http_server};
+use common_web::runner::ServiceRunner;
+use startup::{health_checks, init_app_config, init_app_state, start_http_server};
 
 pub use startup::AppState;
 
@@ -17,11 +16,13 @@ async fn main() -> Result<(), AppError> {
     // 1. 加载配置
     let app_config = init_app_config()?;
     // 初始化日志
-    let _guard = TracingService::init(&app_config.logs);
+    let _guard = TracingService::init(&app_config.server.name, &app_config.logs);
 
     tracing::info!("🚀 {} Service starting...", app_config.server.name);
 
     let bind_addr = app_config.server.bind_addr.clone();
+    let service_name = app_config.server.name.clone();
+    let mut runner = ServiceRunner::new(&service_name, &app_config.server.shutdown);
 
     // 2. 初始化应用（基础设施 + 业务服务）
     let app_state = match init_app_state(app_config).await {
@@ -32,6 +33,21 @@ async fn main() -> Result<(), AppError> {
         }
     };
 
-    // 3. 启动 HTTP 服务器
-    start_http_server(app_state, bind_addr).await
+    // 3. 注册到注册中心，停机时注销
+    let lifecycle = runner.lifecycle();
+    if let Some(registry) = &app_state.registry {
+        let instance = registry.config().instance(&bind_addr, None)?;
+        if let Some(registration) = registry.register(&service_name, instance, lifecycle.shutdown())
+        {
+            runner.add_background("Service registration", registration);
+        }
+    }
+
+    // 4. 启动 HTTP 服务器，运行到收到 SIGTERM/SIGINT，优雅停机后返回（日志守卫随之刷新）
+    let health = health_checks(&app_state, lifecycle.clone());
+    runner.spawn_server(
+        "HTTP",
+        start_http_server(app_state, bind_addr, health, lifecycle),
+    );
+    runner.run().await
 }
@@ -1,13 +1,17 @@
 use axum::{
     Json, Router,
-    extract::{Query, State},
+    extract::State,
     routing::{get, post},
 };
-use common_core::{AppError, utils::jwt_utils::JwtUtils};
-use common_web::domain::r::R;
+use common_core::utils::jwt_utils::JwtUtils;
+use common_web::{
+    domain::r::R,
+    error::ApiError,
+    validation::{ValidatedJson, ValidatedQuery},
+};
 use common_web3::chain::Chain;
 
-use crate::{domain::request::login::LoginWeb3NonceQuery, error::ApiError};
+use crate::domain::request::login::LoginWeb3NonceQuery;
 
 use crate::{
     AppState,
@@ -21,38 +25,35 @@ pub fn router() -> Router<AppState> {
 }
 
 async fn get_login_web3_nonce(
-    Query(params): Query<LoginWeb3NonceQuery>,
+    ValidatedQuery(params): ValidatedQuery<LoginWeb3NonceQuery>,
     State(state): State<AppState>,
 ) -> Result<Json<R<String>>, ApiError> {
     let chain_id = params.chain_id;
 
-    Chain::try_from(chain_id)
-        .map_err(|e| AppError::Internal(format!("Invalid chain id: {}", e)))?;
+    Chain::try_from(chain_id)?;
 
     let nonce = state
         .login_service
         .get_login_web3_nonce(chain_id, params.address)
-        .await
-        .map_err(|e| AppError::Internal(e.to_string()))?;
+        .await?;
 
     Ok(Json(R::ok(nonce)))
 }
 
 async fn login_web3_wallet(
     State(state): State<AppState>,
-    Json(body): Json<LoginWeb3Request>,
+    ValidatedJson(body): ValidatedJson<LoginWeb3Request>,
 ) -> Result<Json<R<LoginResponse>>, ApiError> {
     // 钱包登录签名等验证
     let (chain_id, recovered_addr) = state
         .login_service
         .login_web3_wallet(body.signature, body.message)
-        .await
-        .map_err(|e| AppError::Internal(e.to_string()))?;
+        .await?;
 
     // 获取或注册用户
     let user_id = state
         .login_service
-        .register_or_get_web3_user(&state.user_grpc_client, chain_id, recovered_addr)
+        .register_or_get_web3_user(&state.user_client, chain_id, recovered_addr)
         .await?;
 
     let jwt_config = &state.app_config.jwt;
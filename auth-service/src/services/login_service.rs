@@ -1,12 +1,11 @@
 use async_trait::async_trait;
 use common_core::AppError;
-use common_proto::user::{RegisterType, RegisterUserReq, user_service_client::UserServiceClient};
+use common_grpc::UserClient;
 use common_redis::RedisClient;
 use common_web3::{Web3Recover, chain::Chain};
 use snowflake::SnowflakeIdGenerator;
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use tonic::transport::Channel;
 
 const LOGIN_WEB3_NONCE_CACHE: &str = "blog:auth:login:web3:nonce";
 const NONCE_EXPIRATION_SECONDS: u64 = 300;
@@ -27,7 +26,7 @@ pub trait LoginService: Send + Sync {
     /// Web3 用户注册或获取用户ID
     async fn register_or_get_web3_user(
         &self,
-        user_grpc_client: &UserServiceClient<Channel>,
+        user_client: &UserClient,
         chain_id: i64,
         address: String,
     ) -> Result<i64, AppError>;
@@ -68,7 +67,7 @@ impl LoginService for LoginServiceImpl {
         let nonce = message
             .split(": ")
             .last()
-            .ok_or_else(|| AppError::Internal("Invalid message format".into()))?;
+            .ok_or_else(|| AppError::bad_request("Invalid message format"))?;
 
         let redis_key = format!("{}:{}", LOGIN_WEB3_NONCE_CACHE, nonce);
 
@@ -77,19 +76,19 @@ impl LoginService for LoginServiceImpl {
             .redis_client
             .get_str(&redis_key)
             .await?
-            .ok_or_else(|| AppError::Internal("Nonce expired or invalid".into()))?;
+            .ok_or_else(|| AppError::unauthorized("Nonce expired or invalid"))?;
 
         self.redis_client.del(&redis_key).await?;
 
         // 3. 解析缓存的数据 "chain_id:address"
         let parts: Vec<&str> = cached_data.splitn(2, ':').collect();
         if parts.len() != 2 {
-            return Err(AppError::Internal("Corrupted session data".into()));
+            return Err(AppError::internal("Corrupted session data"));
         }
 
         let chain_id = parts[0]
             .parse::<i64>()
-            .map_err(|_| AppError::Internal("Invalid chain ID".into()))?;
+            .map_err(|_| AppError::internal("Invalid chain ID"))?;
         let expected_address = parts[1].to_lowercase();
 
         // 4. Web3 恢复地址
@@ -98,7 +97,7 @@ impl LoginService for LoginServiceImpl {
 
         // 5. 恢复出的地址必须等于申请 Nonce 时填写的地址
         if recovered_addr.to_lowercase() != expected_address {
-            return Err(AppError::Internal("Signature address mismatch".into()));
+            return Err(AppError::unauthorized("Signature address mismatch"));
         }
 
         Ok((chain_id, recovered_addr))
@@ -106,60 +105,39 @@ impl LoginService for LoginServiceImpl {
 
     async fn register_or_get_web3_user(
         &self,
-        user_grpc_client: &UserServiceClient<Channel>,
+        user_client: &UserClient,
         chain_id: i64,
         address: String,
     ) -> Result<i64, AppError> {
-        use common_proto::user::Web3InfoReq;
-
-        // 1. 尝试获取用户信息
-        let web3_info_req = tonic::Request::new(Web3InfoReq {
-            chain_id,
-            address: address.clone(),
-        });
-
-        let user_info_result = user_grpc_client
-            .clone()
-            .get_user_info_by_web3(web3_info_req)
-            .await;
-
-        // 2. 如果用户存在，返回 user_id；否则自动注册
-        match user_info_result {
-            Ok(response) => {
-                let user_info = response.into_inner();
-                tracing::debug!("Web3 user found: user_id={}", user_info.id);
-                Ok(user_info.id)
+        // 1. 用户存在时直接返回 user_id；仅在 NotFound 时自动注册，其它错误直接返回
+        match user_client.get_user_by_web3(chain_id, &address).await {
+            Ok(user) => {
+                tracing::debug!("Web3 user found: user_id={}", user.id);
+                return Ok(user.id);
             }
-            Err(_) => {
-                // 用户不存在，自动注册
-                tracing::info!(
-                    "Web3 user not found, auto-registering: chain_id={}, address={}",
-                    chain_id,
-                    &address
-                );
-
-                let register_req = tonic::Request::new(RegisterUserReq {
-                    register_type: RegisterType::Web3 as i32,
-                    username: None,
-                    password: None,
-                    web3_address: Some(address.clone()),
-                    web3_chain_id: Some(chain_id),
-                    email: None,
-                });
-
-                let register_res = user_grpc_client
-                    .clone()
-                    .register_user(register_req)
-                    .await
-                    .map_err(|e| AppError::Internal(format!("Failed to register user: {}", e)))?
-                    .into_inner();
+            Err(AppError::NotFound(_)) => {}
+            Err(e) => return Err(e),
+        }
 
+        // 2. 自动注册
+        tracing::info!(
+            "Web3 user not found, auto-registering: chain_id={}, address={}",
+            chain_id,
+            &address
+        );
+        match user_client.register_web3_user(chain_id, &address).await {
+            Ok(user_id) => {
                 tracing::info!(
                     "Web3 user auto-registered successfully: user_id={}",
-                    register_res.user_id
+                    user_id
                 );
-                Ok(register_res.user_id)
+                Ok(user_id)
+            }
+            // 并发登录时另一请求已完成注册，重新查询即可
+            Err(AppError::Conflict(_)) => {
+                Ok(user_client.get_user_by_web3(chain_id, &address).await?.id)
             }
+            Err(e) => Err(e),
         }
     }
 }
@@ -1,9 +1,9 @@
-use common_proto::user::user_service_client::UserServiceClient;
+use common_grpc::UserClient;
 use common_redis::RedisClient;
+use common_registry::RegistryClient;
 use snowflake::SnowflakeIdGenerator;
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use tonic::transport::Channel;
 
 use crate::{config::application::AppConfig, services::login_service::LoginService};
 
@@ -18,9 +18,10 @@ pub struct AppState {
     // 基础设施组件
     pub redis_client: RedisClient,
     pub id_generator: Arc<RwLock<SnowflakeIdGenerator>>,
+    pub registry: Option<RegistryClient>,
 
     // gRPC 客户端
-    pub user_grpc_client: UserServiceClient<Channel>,
+    pub user_client: UserClient,
 
     // 配置
     pub app_config: Arc<AppConfig>,
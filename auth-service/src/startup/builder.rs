@@ -1,11 +1,12 @@
 use common_core::AppError;
+use common_grpc::{ServiceToken, UserClient};
 use common_redis::RedisClient;
+use common_registry::RegistryClient;
 use snowflake::SnowflakeIdGenerator;
 use std::sync::Arc;
 
 use crate::{
     config::application::AppConfig,
-    grpc::user_client::UserServiceGrpcClient,
     services::login_service::{LoginService, LoginServiceImpl},
 };
 
@@ -13,25 +14,37 @@ use super::AppState;
 
 /// 加载应用配置
 pub fn init_app_config() -> Result<AppConfig, AppError> {
-    AppConfig::from_default_yaml()
+    AppConfig::load()
 }
 
 /// 初始化应用（基础设施 + 业务服务）
 pub async fn init_app_state(app_config: AppConfig) -> Result<AppState, AppError> {
     // 1. 初始化 Redis 客户端
     let redis_client = RedisClient::new(app_config.redis.clone()).await?;
-
-    // 2. 初始化 UserService gRPC 客户端 (tonic 客户端本身可克隆)
-    let user_grpc_client =
-        UserServiceGrpcClient::new(app_config.services.user_service_grpc.clone()).await?;
-
-    // 3. 初始化 ID 生成器
+    redis_client.register_pool_metrics("redis");
+
+    // 2. 初始化注册中心客户端（启动后在 main 中注册本实例）
+    let registry = app_config
+        .registry
+        .as_ref()
+        .map(RegistryClient::new)
+        .transpose()?;
+
+    // 3. 初始化 UserService gRPC 客户端（可克隆，共享底层连接）
+    let user_client = UserClient::connect(
+        &app_config.services.user_service,
+        registry.as_ref(),
+        ServiceToken::new(&app_config.server.name, &app_config.identity),
+    )
+    .await?;
+
+    // 4. 初始化 ID 生成器
     let id_generator = Arc::new(tokio::sync::RwLock::new(SnowflakeIdGenerator::new(
         app_config.snowflake.machine_id,
         app_config.snowflake.node_id,
     )));
 
-    // 4. 初始化业务服务
+    // 5. 初始化业务服务
     let login_service = Arc::new(LoginServiceImpl {
         redis_client: redis_client.clone(),
         id_generator: id_generator.clone(),
@@ -41,7 +54,8 @@ pub async fn init_app_state(app_config: AppConfig) -> Result<AppState, AppError>
         login_service,
         redis_client,
         id_generator,
-        user_grpc_client,
+        registry,
+        user_client,
         app_config: Arc::new(app_config),
     })
 }
@@ -1,20 +1,61 @@
-use axum::{Router, routing::get};
+use axum::{Router, middleware, routing::get};
 use common_core::AppError;
+use common_metrics::{http_metrics, metrics_handler};
+use common_tracing::{http_trace, log_level_router};
+use common_web::{
+    deadline::deadline, health::HealthChecks, request_id::request_id, runner::Lifecycle,
+};
 
 use crate::routes::login_router;
 
 use super::AppState;
 
-/// 启动 HTTP 服务器
-pub async fn start_http_server(app_state: AppState, bind_addr: String) -> Result<(), AppError> {
+/// 就绪检查：Redis 为关键依赖；用户服务不可用时只影响登录，报告为降级
+pub fn health_checks(app_state: &AppState, lifecycle: Lifecycle) -> HealthChecks {
+    let redis_client = app_state.redis_client.clone();
+    let user_client = app_state.user_client.clone();
+    HealthChecks::new(lifecycle, &app_state.app_config.server.health)
+        .critical("redis", move || {
+            let redis_client = redis_client.clone();
+            async move { redis_client.ping().await }
+        })
+        .optional("user-service", move || {
+            let user_client = user_client.clone();
+            async move { user_client.check_health().await }
+        })
+}
+
+/// 启动 HTTP 服务器，收到停机通知后处理完进行中的请求再返回
+pub async fn start_http_server(
+    app_state: AppState,
+    bind_addr: String,
+    health: HealthChecks,
+    lifecycle: Lifecycle,
+) -> Result<(), AppError> {
     let app = Router::new()
-        .route("/health", get(|| async { "ok" }))
+        // 存活 / 就绪探针
+        .merge(health.router())
+        .route("/metrics", get(metrics_handler))
         .merge(login_router::router())
+        // 运行时调整日志级别
+        .merge(log_level_router(
+            app_state.app_config.logs.admin_token.as_deref(),
+        ))
+        // 截止时间（网关按路由超时传入，约束下游 gRPC 调用）
+        .layer(middleware::from_fn(deadline))
+        // 请求指标
+        .layer(middleware::from_fn(http_metrics))
+        // 请求 ID（沿用网关传入的 X-Request-Id，记录到追踪 span）
+        .layer(middleware::from_fn(request_id))
+        // 链路追踪（最外层，接续网关传入的 traceparent）
+        .layer(middleware::from_fn(http_trace))
         .with_state(app_state);
 
     let listener = tokio::net::TcpListener::bind(&bind_addr).await?;
-    println!("listening on {}", listener.local_addr()?);
+    tracing::info!("HTTP server listening on {}", listener.local_addr()?);
 
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(lifecycle.shutdown())
+        .await?;
     Ok(())
 }
@@ -0,0 +1,238 @@
+//! 分层配置加载
+//!
+//! 优先级从低到高：
+//! 1. 基础配置文件：`--config <path>` > 环境变量 `BLOG_CONFIG` > 服务默认路径
+//! 2. profile 配置文件：基础文件同目录下的 `application-{profile}.yaml`，
+//!    profile 来自 `--profile <name>` 或环境变量 `BLOG_PROFILE`（dev / test / prod）
+//! 3. 环境变量覆盖：`BLOG__REDIS__PASSWORD=xxx` 对应 `redis.password`
+//!
+//! 配置文件中的字符串支持 `${VAR}` / `${VAR:-default}` 引用环境变量，用于注入密钥。
+
+use common_core::AppError;
+use serde::de::DeserializeOwned;
+use serde_yml::{Mapping, Value};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+mod value;
+
+/// 环境变量前缀
+pub const ENV_PREFIX: &str = "BLOG";
+
+/// 分层配置加载器
+#[derive(Debug, Clone)]
+pub struct ConfigLoader {
+    default_path: PathBuf,
+    config_path: Option<PathBuf>,
+    profile: Option<String>,
+    env: Vec<(String, String)>,
+}
+
+impl ConfigLoader {
+    /// `default_path` 为未指定 `--config` / `BLOG_CONFIG` 时使用的配置文件（相对工作区根目录）
+    pub fn new(default_path: impl Into<PathBuf>) -> Self {
+        Self::from_parts(
+            default_path.into(),
+            std::env::args().skip(1).collect(),
+            std::env::vars().collect(),
+        )
+    }
+
+    fn from_parts(default_path: PathBuf, args: Vec<String>, env: Vec<(String, String)>) -> Self {
+        let lookup = |name: &str| {
+            env.iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.clone())
+        };
+
+        let config_path = cli_arg(&args, "--config")
+            .or_else(|| lookup(&format!("{}_CONFIG", ENV_PREFIX)))
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from);
+        let profile = cli_arg(&args, "--profile")
+            .or_else(|| lookup(&format!("{}_PROFILE", ENV_PREFIX)))
+            .filter(|profile| !profile.is_empty());
+
+        Self {
+            default_path,
+            config_path,
+            profile,
+            env,
+        }
+    }
+
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    /// 参与合并的配置文件：基础文件，以及存在时的 profile 文件
+    pub fn files(&self) -> Result<Vec<PathBuf>, AppError> {
+        let base = self
+            .config_path
+            .clone()
+            .unwrap_or_else(|| self.default_path.clone());
+        if !base.is_file() {
+            return Err(AppError::internal(format!(
+                "Config file not found: {} (use --config <path> or {}_CONFIG to specify one)",
+                base.display(),
+                ENV_PREFIX
+            )));
+        }
+
+        let mut files = vec![base.clone()];
+        if let Some(profile) = &self.profile {
+            let profile_file = profile_path(&base, profile);
+            if profile_file.is_file() {
+                files.push(profile_file);
+            } else {
+                tracing::warn!(
+                    "Profile `{}` has no config file at {}, using base config only",
+                    profile,
+                    profile_file.display()
+                );
+            }
+        }
+        Ok(files)
+    }
+
+    /// 按优先级合并所有来源并反序列化，出错时给出配置项路径
+    pub fn load<T: DeserializeOwned>(&self) -> Result<T, AppError> {
+        let mut merged = Value::Mapping(Mapping::new());
+        for file in self.files()? {
+            let content = fs::read_to_string(&file).map_err(|e| {
+                AppError::internal(format!(
+                    "Failed to read config file {}: {}",
+                    file.display(),
+                    e
+                ))
+            })?;
+            let layer: Value = serde_yml::from_str(&content).map_err(|e| {
+                AppError::internal(format!(
+                    "Failed to parse config file {}: {}",
+                    file.display(),
+                    e
+                ))
+            })?;
+            value::merge(&mut merged, layer);
+        }
+
+        value::interpolate(&mut merged, &self.env)?;
+        value::apply_env_overrides(&mut merged, &self.env)?;
+
+        serde_path_to_error::deserialize(merged).map_err(|e| {
+            AppError::internal(format!("Invalid config at `{}`: {}", e.path(), e.inner()))
+        })
+    }
+}
+
+/// 读取 `--name value` 或 `--name=value`
+fn cli_arg(args: &[String], name: &str) -> Option<String> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == name {
+            return iter.next().cloned();
+        }
+        if let Some(value) = arg.strip_prefix(name).and_then(|v| v.strip_prefix('=')) {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+/// `dir/application.yaml` -> `dir/application-{profile}.yaml`
+fn profile_path(base: &Path, profile: &str) -> PathBuf {
+    let stem = base
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("application");
+    let name = match base.extension().and_then(|e| e.to_str()) {
+        Some(ext) => format!("{}-{}.{}", stem, profile, ext),
+        None => format!("{}-{}", stem, profile),
+    };
+    base.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use std::{fs, path::PathBuf};
+
+    use super::ConfigLoader;
+
+    #[derive(Debug, Deserialize)]
+    struct Config {
+        redis: Redis,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Redis {
+        host: String,
+        port: u16,
+        password: Option<String>,
+    }
+
+    fn write_files(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("common-config-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for (file, content) in files {
+            fs::write(dir.join(file), content).unwrap();
+        }
+        dir.join("application.yaml")
+    }
+
+    fn env(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn layers_profile_file_and_env_overrides() {
+        let base = write_files(
+            "layers",
+            &[
+                (
+                    "application.yaml",
+                    "redis:\n  host: localhost\n  port: 6379\n  password: ${REDIS_SECRET}\n",
+                ),
+                ("application-prod.yaml", "redis:\n  host: redis.prod\n"),
+            ],
+        );
+        let loader = ConfigLoader::from_parts(
+            base,
+            vec!["--profile".into(), "prod".into()],
+            env(&[("REDIS_SECRET", "123456"), ("BLOG__REDIS__PORT", "6380")]),
+        );
+
+        let config: Config = loader.load().unwrap();
+        assert_eq!(config.redis.host, "redis.prod");
+        assert_eq!(config.redis.port, 6380);
+        // 插值结果始终是字符串，纯数字密码也不会被解析成数字
+        assert_eq!(config.redis.password.as_deref(), Some("123456"));
+    }
+
+    #[test]
+    fn reports_key_path_and_missing_variables() {
+        let base = write_files(
+            "errors",
+            &[(
+                "application.yaml",
+                "redis:\n  host: localhost\n  port: abc\n",
+            )],
+        );
+        let err = ConfigLoader::from_parts(base.clone(), vec![], vec![])
+            .load::<Config>()
+            .unwrap_err();
+        assert!(err.to_string().contains("redis.port"), "{}", err);
+
+        fs::write(&base, "redis:\n  host: ${REDIS_HOST}\n  port: 1\n").unwrap();
+        let err = ConfigLoader::from_parts(base, vec![], vec![])
+            .load::<Config>()
+            .unwrap_err();
+        assert!(err.to_string().contains("REDIS_HOST"), "{}", err);
+        assert!(err.to_string().contains("redis.host"), "{}", err);
+    }
+}
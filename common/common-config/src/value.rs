@@ -0,0 +1,169 @@
+//! 配置树的合并、插值与环境变量覆盖
+
+use common_core::AppError;
+use serde_yml::{Mapping, Value};
+
+use crate::ENV_PREFIX;
+
+/// 深度合并：映射逐键合并，其余类型（包括列表）整体替换
+pub(crate) fn merge(base: &mut Value, layer: Value) {
+    match (base, layer) {
+        (Value::Mapping(base), Value::Mapping(layer)) => {
+            for (key, value) in layer {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        // 空文件解析为 Null，不覆盖任何配置
+        (_, Value::Null) => {}
+        (base, layer) => *base = layer,
+    }
+}
+
+/// 替换字符串中的 `${VAR}` / `${VAR:-default}`，结果始终是字符串
+///
+/// 需要数字、布尔等类型的配置项请使用 `BLOG__...` 环境变量覆盖
+pub(crate) fn interpolate(value: &mut Value, env: &[(String, String)]) -> Result<(), AppError> {
+    interpolate_at(value, &mut Vec::new(), env)
+}
+
+fn interpolate_at(
+    value: &mut Value,
+    path: &mut Vec<String>,
+    env: &[(String, String)],
+) -> Result<(), AppError> {
+    match value {
+        Value::String(s) if s.contains("${") => {
+            *s = substitute(s, env).map_err(|e| {
+                AppError::internal(format!("Invalid config at `{}`: {}", path.join("."), e))
+            })?;
+        }
+        Value::Mapping(map) => {
+            for (key, child) in map.iter_mut() {
+                path.push(key_name(key));
+                interpolate_at(child, path, env)?;
+                path.pop();
+            }
+        }
+        Value::Sequence(items) => {
+            for (i, child) in items.iter_mut().enumerate() {
+                path.push(i.to_string());
+                interpolate_at(child, path, env)?;
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn substitute(input: &str, env: &[(String, String)]) -> Result<String, String> {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find("${") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| format!("unterminated `${{` in `{}`", input))?;
+        let expr = &after[..end];
+        let (name, default) = match expr.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (expr, None),
+        };
+
+        let value = env
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+            .or(default)
+            .ok_or_else(|| format!("environment variable `{}` is not set", name))?;
+        output.push_str(value);
+        rest = &after[end + 1..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// 应用 `BLOG__A__B=value` 形式的环境变量覆盖（键名不区分大小写，列表可用下标）
+///
+/// 原值为字符串时保持字符串，否则按 YAML 标量解析（数字、布尔、`[a, b]` 列表等）
+pub(crate) fn apply_env_overrides(
+    root: &mut Value,
+    env: &[(String, String)],
+) -> Result<(), AppError> {
+    let prefix = format!("{}__", ENV_PREFIX);
+    let mut overrides: Vec<(&String, &String)> = env
+        .iter()
+        .filter(|(key, _)| key.starts_with(&prefix))
+        .map(|(key, value)| (key, value))
+        .collect();
+    // 按变量名排序，结果与环境变量顺序无关
+    overrides.sort();
+
+    for (key, raw) in overrides {
+        let path: Vec<String> = key[prefix.len()..]
+            .split("__")
+            .map(str::to_ascii_lowercase)
+            .collect();
+        if path.iter().any(String::is_empty) {
+            return Err(AppError::internal(format!(
+                "Invalid config override `{}`: empty key segment",
+                key
+            )));
+        }
+
+        let slot = lookup_or_insert(root, &path)
+            .map_err(|e| AppError::internal(format!("Invalid config override `{}`: {}", key, e)))?;
+        *slot = match slot {
+            Value::String(_) => Value::String(raw.clone()),
+            _ => serde_yml::from_str(raw).unwrap_or_else(|_| Value::String(raw.clone())),
+        };
+    }
+    Ok(())
+}
+
+fn lookup_or_insert<'a>(root: &'a mut Value, path: &[String]) -> Result<&'a mut Value, String> {
+    let mut current = root;
+    for (depth, segment) in path.iter().enumerate() {
+        if current.is_null() {
+            *current = Value::Mapping(Mapping::new());
+        }
+        current = match current {
+            Value::Mapping(map) => map
+                .entry(Value::String(segment.clone()))
+                .or_insert(Value::Null),
+            Value::Sequence(items) => {
+                let index = segment
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&i| i < items.len())
+                    .ok_or_else(|| {
+                        format!(
+                            "`{}` is a list, `{}` is not a valid index",
+                            path[..depth].join("."),
+                            segment
+                        )
+                    })?;
+                &mut items[index]
+            }
+            _ => {
+                return Err(format!("`{}` is not a mapping", path[..depth].join(".")));
+            }
+        };
+    }
+    Ok(current)
+}
+
+fn key_name(key: &Value) -> String {
+    match key {
+        Value::String(s) => s.clone(),
+        other => serde_yml::to_string(other)
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default(),
+    }
+}
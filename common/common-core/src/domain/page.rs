@@ -1,8 +1,22 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use validator::Validate;
 
-#[derive(Debug, Deserialize)]
+use crate::AppError;
+
+/// 单页最大条数
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+#[derive(Debug, Deserialize, Validate)]
 pub struct Page {
+    #[validate(range(min = 1, message = "page_num must be >= 1"))]
     pub page_num: i64,
+    #[validate(range(
+        min = 1,
+        max = MAX_PAGE_SIZE,
+        message = "page_size must be between 1 and 100"
+    ))]
     pub page_size: i64,
 }
 
@@ -13,3 +27,121 @@ pub struct PageResult<T> {
     pub page_num: i64,
     pub page_size: i64,
 }
+
+/// 游标（keyset）分页位置：按 (created_at, id) 倒序排列时最后一条记录的键
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub created_at: DateTime<Utc>,
+    pub id: i64,
+}
+
+impl Cursor {
+    /// 编码为对客户端不透明的字符串
+    pub fn encode(&self) -> String {
+        let raw = format!("{}:{}", self.created_at.timestamp_micros(), self.id);
+        URL_SAFE_NO_PAD.encode(raw)
+    }
+
+    pub fn decode(value: &str) -> Result<Self, AppError> {
+        let invalid = || AppError::bad_request("Invalid cursor");
+
+        let raw = URL_SAFE_NO_PAD.decode(value).map_err(|_| invalid())?;
+        let raw = String::from_utf8(raw).map_err(|_| invalid())?;
+        let (micros, id) = raw.split_once(':').ok_or_else(invalid)?;
+
+        let micros = micros.parse::<i64>().map_err(|_| invalid())?;
+        let created_at = DateTime::from_timestamp_micros(micros).ok_or_else(invalid)?;
+        let id = id.parse::<i64>().map_err(|_| invalid())?;
+
+        Ok(Self { created_at, id })
+    }
+}
+
+/// 游标分页请求，首页不传 `cursor`
+#[derive(Debug, Deserialize, Validate)]
+pub struct CursorPage {
+    #[validate(length(max = 64, message = "cursor is too long"))]
+    pub cursor: Option<String>,
+    #[validate(range(
+        min = 1,
+        max = MAX_PAGE_SIZE,
+        message = "page_size must be between 1 and 100"
+    ))]
+    pub page_size: i64,
+}
+
+impl CursorPage {
+    /// 解析游标，首页返回 `None`
+    pub fn decode_cursor(&self) -> Result<Option<Cursor>, AppError> {
+        self.cursor.as_deref().map(Cursor::decode).transpose()
+    }
+}
+
+/// 游标分页结果，`next_cursor` 为空表示没有更多数据
+#[derive(Serialize)]
+pub struct CursorResult<T> {
+    pub list: Vec<T>,
+    pub next_cursor: Option<String>,
+    pub page_size: i64,
+}
+
+impl<T> CursorResult<T> {
+    /// 由多查一条（`page_size + 1`）的结果构造分页结果，多出的一条只用于判断是否还有下一页
+    pub fn from_overfetch(
+        mut list: Vec<T>,
+        page_size: i64,
+        cursor_of: impl Fn(&T) -> Cursor,
+    ) -> Self {
+        let has_more = list.len() as i64 > page_size;
+        list.truncate(page_size.max(0) as usize);
+
+        let next_cursor = if has_more {
+            list.last().map(|item| cursor_of(item).encode())
+        } else {
+            None
+        };
+
+        Self {
+            list,
+            next_cursor,
+            page_size,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::{Cursor, CursorResult};
+
+    fn cursor(id: i64) -> Cursor {
+        Cursor {
+            created_at: Utc.timestamp_micros(1_700_000_000_123_456).unwrap(),
+            id,
+        }
+    }
+
+    #[test]
+    fn cursor_round_trips() {
+        let c = cursor(7414486567504449536);
+        assert_eq!(Cursor::decode(&c.encode()).unwrap(), c);
+    }
+
+    #[test]
+    fn rejects_garbage_cursor() {
+        assert!(Cursor::decode("not-a-cursor").is_err());
+        assert!(Cursor::decode("").is_err());
+    }
+
+    #[test]
+    fn next_cursor_only_when_more_rows() {
+        let full = CursorResult::from_overfetch(vec![1, 2, 3], 2, |id| cursor(*id));
+        assert_eq!(full.list, vec![1, 2]);
+        assert_eq!(full.next_cursor, Some(cursor(2).encode()));
+
+        let last = CursorResult::from_overfetch(vec![1, 2], 2, |id| cursor(*id));
+        assert_eq!(last.list, vec![1, 2]);
+        assert_eq!(last.next_cursor, None);
+    }
+}
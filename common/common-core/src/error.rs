@@ -2,11 +2,68 @@ use thiserror::Error;
 
 pub type AppResult<T> = Result<T, AppError>;
 
+/// 稳定的业务错误码（写入 `R.code`，客户端据此判断错误类型）
+pub mod code {
+    pub const OK: u16 = 0;
+
+    pub const BAD_REQUEST: u16 = 40000;
+    pub const VALIDATION: u16 = 40001;
+    pub const UNAUTHORIZED: u16 = 40100;
+    pub const FORBIDDEN: u16 = 40300;
+    pub const NOT_FOUND: u16 = 40400;
+    pub const METHOD_NOT_ALLOWED: u16 = 40500;
+    pub const CONFLICT: u16 = 40900;
+    pub const PAYLOAD_TOO_LARGE: u16 = 41300;
+    pub const RATE_LIMITED: u16 = 42900;
+
+    pub const INTERNAL: u16 = 50000;
+    pub const DB: u16 = 50001;
+    pub const REDIS: u16 = 50002;
+    pub const IO: u16 = 50003;
+    pub const BAD_GATEWAY: u16 = 50200;
+    pub const SERVICE_UNAVAILABLE: u16 = 50300;
+}
+
 #[derive(Error, Debug)]
 pub enum AppError {
+    #[error("Bad Request: {0}")]
+    BadRequest(String),
+
+    #[error("Validation Failed: {0}")]
+    Validation(String),
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    #[error("Not Found: {0}")]
+    NotFound(String),
+
+    #[error("Method Not Allowed: {0}")]
+    MethodNotAllowed(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Payload Too Large: {0}")]
+    PayloadTooLarge(String),
+
+    #[error("Rate Limited: {0}")]
+    RateLimited(String),
+
     #[error("Internal Server Error: {0}")]
     Internal(String),
 
+    /// 上游服务出错或无响应（网关使用）
+    #[error("Bad Gateway: {0}")]
+    BadGateway(String),
+
+    /// 没有可用的上游实例或熔断打开（网关使用）
+    #[error("Service Unavailable: {0}")]
+    ServiceUnavailable(String),
+
     #[error("Redis Error: {0}")]
     Redis(String),
 
@@ -21,10 +78,54 @@ pub enum AppError {
 }
 
 impl AppError {
+    pub fn bad_request(msg: impl Into<String>) -> Self {
+        Self::BadRequest(msg.into())
+    }
+
+    pub fn validation(msg: impl Into<String>) -> Self {
+        Self::Validation(msg.into())
+    }
+
+    pub fn unauthorized(msg: impl Into<String>) -> Self {
+        Self::Unauthorized(msg.into())
+    }
+
+    pub fn forbidden(msg: impl Into<String>) -> Self {
+        Self::Forbidden(msg.into())
+    }
+
+    pub fn not_found(msg: impl Into<String>) -> Self {
+        Self::NotFound(msg.into())
+    }
+
+    pub fn method_not_allowed(msg: impl Into<String>) -> Self {
+        Self::MethodNotAllowed(msg.into())
+    }
+
+    pub fn conflict(msg: impl Into<String>) -> Self {
+        Self::Conflict(msg.into())
+    }
+
+    pub fn payload_too_large(msg: impl Into<String>) -> Self {
+        Self::PayloadTooLarge(msg.into())
+    }
+
+    pub fn rate_limited(msg: impl Into<String>) -> Self {
+        Self::RateLimited(msg.into())
+    }
+
     pub fn internal(msg: impl Into<String>) -> Self {
         Self::Internal(msg.into())
     }
 
+    pub fn bad_gateway(msg: impl Into<String>) -> Self {
+        Self::BadGateway(msg.into())
+    }
+
+    pub fn service_unavailable(msg: impl Into<String>) -> Self {
+        Self::ServiceUnavailable(msg.into())
+    }
+
     pub fn redis(msg: impl Into<String>) -> Self {
         Self::Redis(msg.into())
     }
@@ -32,4 +133,292 @@ impl AppError {
     pub fn db(msg: impl Into<String>) -> Self {
         Self::Db(msg.into())
     }
+
+    /// 业务错误码，见 [`code`]
+    pub fn code(&self) -> u16 {
+        match self {
+            Self::BadRequest(_) => code::BAD_REQUEST,
+            Self::Validation(_) => code::VALIDATION,
+            Self::Unauthorized(_) => code::UNAUTHORIZED,
+            Self::Forbidden(_) => code::FORBIDDEN,
+            Self::NotFound(_) => code::NOT_FOUND,
+            Self::MethodNotAllowed(_) => code::METHOD_NOT_ALLOWED,
+            Self::Conflict(_) => code::CONFLICT,
+            Self::PayloadTooLarge(_) => code::PAYLOAD_TOO_LARGE,
+            Self::RateLimited(_) => code::RATE_LIMITED,
+            Self::Internal(_) | Self::Other(_) => code::INTERNAL,
+            Self::Db(_) => code::DB,
+            Self::Redis(_) => code::REDIS,
+            Self::Io(_) => code::IO,
+            Self::BadGateway(_) => code::BAD_GATEWAY,
+            Self::ServiceUnavailable(_) => code::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    /// 是否为服务端内部错误（细节只记录日志，不返回给客户端）
+    pub fn is_internal(&self) -> bool {
+        matches!(
+            self,
+            Self::Internal(_) | Self::Redis(_) | Self::Db(_) | Self::Io(_) | Self::Other(_)
+        )
+    }
+
+    /// 可以安全返回给客户端的错误信息
+    pub fn public_message(&self) -> String {
+        match self {
+            Self::BadRequest(s)
+            | Self::Validation(s)
+            | Self::Unauthorized(s)
+            | Self::Forbidden(s)
+            | Self::NotFound(s)
+            | Self::MethodNotAllowed(s)
+            | Self::Conflict(s)
+            | Self::PayloadTooLarge(s)
+            | Self::RateLimited(s)
+            | Self::BadGateway(s)
+            | Self::ServiceUnavailable(s) => s.clone(),
+            _ => "Internal Server Error".into(),
+        }
+    }
+}
+
+#[cfg(feature = "sqlx")]
+impl From<sqlx::Error> for AppError {
+    /// 根据约束类型把数据库错误映射为领域错误（唯一约束冲突 -> Conflict 等）
+    ///
+    /// 返回给客户端的信息不含表名、约束名等库表细节，约束名只记录在服务端日志中
+    fn from(err: sqlx::Error) -> Self {
+        Self::from_sqlx(err, None)
+    }
+}
+
+#[cfg(feature = "sqlx")]
+impl AppError {
+    fn from_sqlx(err: sqlx::Error, context: Option<&str>) -> Self {
+        use sqlx::error::ErrorKind;
+
+        let db_err = match &err {
+            sqlx::Error::RowNotFound => return Self::not_found("Record not found"),
+            sqlx::Error::Database(db_err) => db_err,
+            _ => return Self::db_with_context(err, context),
+        };
+        let mapped = match db_err.kind() {
+            ErrorKind::UniqueViolation => Self::conflict("Record already exists"),
+            ErrorKind::ForeignKeyViolation => {
+                Self::conflict("Related record does not exist or is still referenced")
+            }
+            ErrorKind::NotNullViolation | ErrorKind::CheckViolation => {
+                Self::bad_request("Invalid field value")
+            }
+            _ => return Self::db_with_context(err, context),
+        };
+        tracing::warn!(
+            constraint = db_err.constraint(),
+            context,
+            "Database constraint violated: {}",
+            db_err.message()
+        );
+        mapped
+    }
+
+    fn db_with_context(err: sqlx::Error, context: Option<&str>) -> Self {
+        match context {
+            Some(context) => Self::db(format!("{}: {}", context, err)),
+            None => Self::db(err.to_string()),
+        }
+    }
+}
+
+/// 为数据库操作附加上下文（如 "Failed to fetch user by id"），
+/// 内部错误的上下文随错误信息记录，约束冲突的上下文写入告警日志
+#[cfg(feature = "sqlx")]
+pub trait DbResultExt<T> {
+    fn db_context(self, context: &str) -> AppResult<T>;
+}
+
+#[cfg(feature = "sqlx")]
+impl<T> DbResultExt<T> for Result<T, sqlx::Error> {
+    fn db_context(self, context: &str) -> AppResult<T> {
+        self.map_err(|err| AppError::from_sqlx(err, Some(context)))
+    }
+}
+
+#[cfg(feature = "tonic")]
+impl From<tonic::Status> for AppError {
+    fn from(status: tonic::Status) -> Self {
+        use tonic::Code;
+
+        let msg = status.message().to_string();
+        match status.code() {
+            Code::InvalidArgument | Code::OutOfRange | Code::FailedPrecondition => {
+                Self::BadRequest(msg)
+            }
+            Code::Unauthenticated => Self::Unauthorized(msg),
+            Code::PermissionDenied => Self::Forbidden(msg),
+            Code::NotFound => Self::NotFound(msg),
+            Code::AlreadyExists | Code::Aborted => Self::Conflict(msg),
+            Code::ResourceExhausted => Self::RateLimited(msg),
+            code => Self::internal(format!("gRPC call failed ({code:?}): {msg}")),
+        }
+    }
+}
+
+#[cfg(feature = "tonic")]
+impl From<AppError> for tonic::Status {
+    fn from(err: AppError) -> Self {
+        use tonic::Status;
+
+        let msg = err.to_string();
+        match err {
+            AppError::BadRequest(s) | AppError::Validation(s) => Status::invalid_argument(s),
+            AppError::Unauthorized(s) => Status::unauthenticated(s),
+            AppError::Forbidden(s) => Status::permission_denied(s),
+            AppError::NotFound(s) => Status::not_found(s),
+            AppError::Conflict(s) => Status::already_exists(s),
+            AppError::MethodNotAllowed(s) => Status::unimplemented(s),
+            AppError::PayloadTooLarge(s) => Status::out_of_range(s),
+            AppError::RateLimited(s) => Status::resource_exhausted(s),
+            AppError::BadGateway(s) | AppError::ServiceUnavailable(s) => Status::unavailable(s),
+            _ => Status::internal(msg),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "tonic"))]
+mod tests {
+    use tonic::{Code, Status};
+
+    use super::AppError;
+
+    #[test]
+    fn maps_status_to_app_error() {
+        assert!(matches!(
+            AppError::from(Status::not_found("User not found")),
+            AppError::NotFound(ref s) if s == "User not found"
+        ));
+        assert!(matches!(
+            AppError::from(Status::already_exists("dup")),
+            AppError::Conflict(_)
+        ));
+        assert!(AppError::from(Status::unavailable("down")).is_internal());
+    }
+
+    #[test]
+    fn maps_app_error_to_status() {
+        assert_eq!(
+            Status::from(AppError::not_found("x")).code(),
+            Code::NotFound
+        );
+        assert_eq!(
+            Status::from(AppError::conflict("x")).code(),
+            Code::AlreadyExists
+        );
+        assert_eq!(
+            Status::from(AppError::validation("x")).code(),
+            Code::InvalidArgument
+        );
+        assert_eq!(Status::from(AppError::db("boom")).code(), Code::Internal);
+    }
+}
+
+#[cfg(all(test, feature = "sqlx"))]
+mod sqlx_tests {
+    use sqlx::error::{DatabaseError, ErrorKind};
+    use std::{borrow::Cow, error::Error as StdError, fmt};
+
+    use super::{AppError, DbResultExt, code};
+
+    /// 模拟数据库返回的约束错误
+    #[derive(Debug)]
+    struct ConstraintError(ErrorKind);
+
+    impl fmt::Display for ConstraintError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.message())
+        }
+    }
+
+    impl StdError for ConstraintError {}
+
+    impl DatabaseError for ConstraintError {
+        fn message(&self) -> &str {
+            "violates constraint \"users_email_key\" on table \"users\""
+        }
+
+        fn code(&self) -> Option<Cow<'_, str>> {
+            None
+        }
+
+        fn as_error(&self) -> &(dyn StdError + Send + Sync + 'static) {
+            self
+        }
+
+        fn as_error_mut(&mut self) -> &mut (dyn StdError + Send + Sync + 'static) {
+            self
+        }
+
+        fn into_error(self: Box<Self>) -> Box<dyn StdError + Send + Sync + 'static> {
+            self
+        }
+
+        fn constraint(&self) -> Option<&str> {
+            Some("users_email_key")
+        }
+
+        fn kind(&self) -> ErrorKind {
+            match self.0 {
+                ErrorKind::UniqueViolation => ErrorKind::UniqueViolation,
+                ErrorKind::ForeignKeyViolation => ErrorKind::ForeignKeyViolation,
+                ErrorKind::NotNullViolation => ErrorKind::NotNullViolation,
+                ErrorKind::CheckViolation => ErrorKind::CheckViolation,
+                _ => ErrorKind::Other,
+            }
+        }
+    }
+
+    fn db_error(kind: ErrorKind) -> sqlx::Error {
+        sqlx::Error::Database(Box::new(ConstraintError(kind)))
+    }
+
+    #[test]
+    fn maps_constraint_violations_to_neutral_errors() {
+        let cases = [
+            (ErrorKind::UniqueViolation, code::CONFLICT),
+            (ErrorKind::ForeignKeyViolation, code::CONFLICT),
+            (ErrorKind::NotNullViolation, code::BAD_REQUEST),
+            (ErrorKind::CheckViolation, code::BAD_REQUEST),
+        ];
+
+        for (kind, biz_code) in cases {
+            let err = AppError::from(db_error(kind));
+            assert_eq!(err.code(), biz_code);
+            assert!(!err.public_message().contains("users"));
+        }
+    }
+
+    #[test]
+    fn maps_other_errors_to_db() {
+        let err = AppError::from(sqlx::Error::RowNotFound);
+        assert_eq!(err.code(), code::NOT_FOUND);
+
+        let err = AppError::from(db_error(ErrorKind::Other));
+        assert_eq!(err.code(), code::DB);
+        assert_eq!(err.public_message(), "Internal Server Error");
+
+        let err = AppError::from(sqlx::Error::PoolTimedOut);
+        assert_eq!(err.code(), code::DB);
+    }
+
+    #[test]
+    fn keeps_context_for_internal_errors() {
+        let result: Result<(), _> = Err(sqlx::Error::PoolTimedOut);
+        let err = result.db_context("Failed to fetch user by id").unwrap_err();
+        assert!(
+            matches!(err, AppError::Db(ref s) if s.starts_with("Failed to fetch user by id: "))
+        );
+
+        let result: Result<(), _> = Err(db_error(ErrorKind::UniqueViolation));
+        let err = result.db_context("Failed to create user").unwrap_err();
+        assert!(matches!(err, AppError::Conflict(_)));
+    }
 }
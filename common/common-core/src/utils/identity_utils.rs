@@ -0,0 +1,154 @@
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::AppError;
+
+/// 网关注入的用户 ID
+pub const USER_ID_HEADER: &str = "x-user-id";
+/// 签名时间戳（Unix 秒）
+pub const IDENTITY_TIMESTAMP_HEADER: &str = "x-identity-timestamp";
+/// HMAC-SHA256(secret, "user:{user_id}.{timestamp}") 的十六进制
+pub const IDENTITY_SIGNATURE_HEADER: &str = "x-identity-signature";
+/// 服务间 gRPC 调用凭证：`{调用方服务名}.{时间戳}.{HMAC-SHA256(secret, "svc:{服务名}.{时间戳}")}`
+pub const SERVICE_TOKEN_HEADER: &str = "x-service-token";
+
+/// 网关与后端之间的内部身份签名
+///
+/// 网关验证 JWT 后签发，后端据此确认 `x-user-id` 确实来自网关而不是客户端伪造；
+/// 服务之间的 gRPC 调用也用同一密钥签发调用凭证，两种签名内容分别带 `user:` / `svc:` 前缀，
+/// 用户身份签名不能当作服务调用凭证使用（反之亦然）
+pub struct IdentityUtils;
+
+impl IdentityUtils {
+    /// 为用户 ID 签名，返回 (时间戳, 签名)
+    pub fn sign(secret: &str, user_id: i64) -> (i64, String) {
+        let timestamp = Utc::now().timestamp();
+        (timestamp, Self::signature(secret, user_id, timestamp))
+    }
+
+    /// 校验签名，并要求时间戳与当前时间相差不超过 `max_skew_seconds`
+    pub fn verify(
+        secret: &str,
+        user_id: i64,
+        timestamp: i64,
+        signature: &str,
+        max_skew_seconds: i64,
+    ) -> Result<(), AppError> {
+        if (Utc::now().timestamp() - timestamp).abs() > max_skew_seconds {
+            return Err(AppError::unauthorized("Identity signature expired"));
+        }
+
+        if !Self::matches(secret, &Self::user_payload(user_id, timestamp), signature) {
+            return Err(AppError::unauthorized("Invalid identity signature"));
+        }
+        Ok(())
+    }
+
+    /// 为调用方服务签发调用凭证（每次调用重新签发）
+    pub fn sign_service(secret: &str, service: &str) -> String {
+        let payload = format!("{}.{}", service, Utc::now().timestamp());
+        let signature = hex::encode(
+            Self::mac(secret, &Self::service_payload(&payload))
+                .finalize()
+                .into_bytes(),
+        );
+        format!("{}.{}", payload, signature)
+    }
+
+    /// 校验服务间调用凭证，返回调用方服务名
+    pub fn verify_service<'a>(
+        secret: &str,
+        token: &'a str,
+        max_skew_seconds: i64,
+    ) -> Result<&'a str, AppError> {
+        let invalid = || AppError::unauthorized("Invalid service token");
+        let (payload, signature) = token.rsplit_once('.').ok_or_else(invalid)?;
+        let (service, timestamp) = payload.rsplit_once('.').ok_or_else(invalid)?;
+        let timestamp: i64 = timestamp.parse().map_err(|_| invalid())?;
+        if service.is_empty() {
+            return Err(invalid());
+        }
+        if (Utc::now().timestamp() - timestamp).abs() > max_skew_seconds {
+            return Err(AppError::unauthorized("Service token expired"));
+        }
+
+        if !Self::matches(secret, &Self::service_payload(payload), signature) {
+            return Err(invalid());
+        }
+        Ok(service)
+    }
+
+    fn signature(secret: &str, user_id: i64, timestamp: i64) -> String {
+        let payload = Self::user_payload(user_id, timestamp);
+        hex::encode(Self::mac(secret, &payload).finalize().into_bytes())
+    }
+
+    fn user_payload(user_id: i64, timestamp: i64) -> String {
+        format!("user:{}.{}", user_id, timestamp)
+    }
+
+    /// `payload` 为凭证中的 `{服务名}.{时间戳}`
+    fn service_payload(payload: &str) -> String {
+        format!("svc:{}", payload)
+    }
+
+    /// 比较十六进制签名（常量时间）
+    fn matches(secret: &str, payload: &str, signature: &str) -> bool {
+        hex::decode(signature)
+            .is_ok_and(|signature| Self::mac(secret, payload).verify_slice(&signature).is_ok())
+    }
+
+    fn mac(secret: &str, payload: &str) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(payload.as_bytes());
+        mac
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IdentityUtils;
+
+    #[test]
+    fn verifies_own_signature_only() {
+        let (timestamp, signature) = IdentityUtils::sign("secret", 42);
+
+        assert!(IdentityUtils::verify("secret", 42, timestamp, &signature, 60).is_ok());
+        assert!(IdentityUtils::verify("secret", 43, timestamp, &signature, 60).is_err());
+        assert!(IdentityUtils::verify("other", 42, timestamp, &signature, 60).is_err());
+        assert!(IdentityUtils::verify("secret", 42, timestamp - 120, &signature, 60).is_err());
+    }
+
+    #[test]
+    fn verifies_service_tokens() {
+        let token = IdentityUtils::sign_service("secret", "auth-service");
+        assert_eq!(
+            IdentityUtils::verify_service("secret", &token, 60).unwrap(),
+            "auth-service"
+        );
+        assert!(IdentityUtils::verify_service("other", &token, 60).is_err());
+
+        // 篡改调用方服务名后签名不再匹配
+        let forged = token.replacen("auth-service", "article-service", 1);
+        assert!(IdentityUtils::verify_service("secret", &forged, 60).is_err());
+        for malformed in ["", "auth-service", "auth-service.abc.00", ".1.00"] {
+            assert!(IdentityUtils::verify_service("secret", malformed, 60).is_err());
+        }
+    }
+
+    #[test]
+    fn user_signature_is_not_a_service_token() {
+        // 网关为用户 42 签发的身份签名拼成 `42.{时间戳}.{签名}` 后不能通过服务凭证校验
+        let (timestamp, signature) = IdentityUtils::sign("secret", 42);
+        let token = format!("42.{}.{}", timestamp, signature);
+        assert!(IdentityUtils::verify_service("secret", &token, 60).is_err());
+
+        // 反过来服务凭证的签名也不能作为用户身份签名
+        let token = IdentityUtils::sign_service("secret", "42");
+        let (payload, signature) = token.rsplit_once('.').unwrap();
+        let timestamp: i64 = payload.rsplit_once('.').unwrap().1.parse().unwrap();
+        assert!(IdentityUtils::verify("secret", 42, timestamp, signature, 60).is_err());
+    }
+}
@@ -32,7 +32,7 @@ impl JwtUtils {
             &claims,
             &EncodingKey::from_secret(secret.as_bytes()),
         )
-        .map_err(|e| AppError::Internal(format!("JWT encode error: {}", e)))
+        .map_err(|e| AppError::internal(format!("JWT encode error: {}", e)))
     }
 
     /// 验证并解析 Token
@@ -43,6 +43,6 @@ impl JwtUtils {
             &Validation::default(),
         )
         .map(|data| data.claims)
-        .map_err(|_| AppError::Internal("Invalid or expired token".into()))
+        .map_err(|_| AppError::unauthorized("Invalid or expired token"))
     }
 }
@@ -1 +1,2 @@
+pub mod identity_utils;
 pub mod jwt_utils;
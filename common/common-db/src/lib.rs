@@ -0,0 +1,61 @@
+//! PostgreSQL 公共组件：连接池、事务、数据库迁移
+
+use common_core::{AppError, AppResult};
+use common_metrics::PoolStats;
+use sqlx::postgres::PgPoolOptions;
+use std::time::Duration;
+
+pub mod application;
+pub mod migrate;
+mod transaction;
+
+pub use sqlx::PgPool;
+
+#[doc(hidden)]
+pub use common_core::AppError as __AppError;
+
+/// 创建连接池，并在启动时执行一次健康检查
+pub async fn build_pool(config: &application::Database) -> AppResult<PgPool> {
+    let url_safe = config.url_safe();
+    let pool = PgPoolOptions::new()
+        .max_connections(config.max_connections)
+        .min_connections(config.min_connections)
+        .acquire_timeout(Duration::from_secs(config.acquire_timeout_seconds))
+        .idle_timeout(Duration::from_secs(config.idle_timeout_seconds))
+        .connect(&config.connection_url())
+        .await
+        .map_err(|e| {
+            AppError::db(format!(
+                "Failed to connect to database (url={}): {}",
+                url_safe, e
+            ))
+        })?;
+
+    ping(&pool).await.map_err(|e| {
+        AppError::db(format!(
+            "Initial health check failed (url={}): {}. Hint: ensure PostgreSQL is running and reachable.",
+            url_safe, e
+        ))
+    })?;
+
+    Ok(pool)
+}
+
+/// 健康检查：执行 `SELECT 1`
+pub async fn ping(pool: &PgPool) -> AppResult<()> {
+    sqlx::query("SELECT 1")
+        .execute(pool)
+        .await
+        .map(|_| ())
+        .map_err(|e| AppError::db(format!("SELECT 1 failed: {}", e)))
+}
+
+/// 登记连接池指标（`pool_connections{pool="postgres"}` 等）
+pub fn register_pool_metrics(pool: &PgPool) {
+    let pool = pool.clone();
+    common_metrics::register_pool("postgres", move || PoolStats {
+        max: pool.options().get_max_connections(),
+        open: pool.size(),
+        idle: pool.num_idle() as u32,
+    });
+}
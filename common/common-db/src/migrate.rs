@@ -0,0 +1,37 @@
+//! 数据库迁移
+//!
+//! 每个服务在自己的 `migrations/` 目录维护迁移脚本，通过 `sqlx::migrate!()` 嵌入二进制。
+//! 各服务共用同一个数据库和 `_sqlx_migrations` 表，因此版本号按服务分段
+//! （如 user-service 使用 `20260101xxxxxx`，article-service 使用 `20260102xxxxxx`），
+//! 执行时忽略其他服务已应用的版本。
+
+use common_core::{AppError, AppResult};
+use sqlx::PgPool;
+
+pub use sqlx::migrate::Migrator;
+
+/// 只执行迁移然后退出的子命令：`<service> migrate [--config <path>]`
+pub const MIGRATE_COMMAND: &str = "migrate";
+
+/// 命令行第一个参数是否为 `migrate`
+pub fn is_migrate_command() -> bool {
+    std::env::args().nth(1).as_deref() == Some(MIGRATE_COMMAND)
+}
+
+/// 执行尚未应用的迁移（多实例同时启动时由 sqlx 的 advisory lock 串行化）
+pub async fn run_migrations(pool: &PgPool, mut migrator: Migrator, service: &str) -> AppResult<()> {
+    migrator.set_ignore_missing(true);
+
+    let total = migrator.iter().count();
+    migrator
+        .run(pool)
+        .await
+        .map_err(|e| AppError::db(format!("Database migration failed for {}: {}", service, e)))?;
+
+    tracing::info!(
+        "Database migrations for {} are up to date ({} total)",
+        service,
+        total
+    );
+    Ok(())
+}
@@ -0,0 +1,28 @@
+/// 事务宏，自动处理事务的开始、提交和回滚
+///
+/// ```ignore
+/// common_db::with_transaction!(&pool, |tx| async {
+///     ARTICLE_REPO.insert(tx, &article).await?;
+///     Ok(article.id)
+/// })
+/// ```
+#[macro_export]
+macro_rules! with_transaction {
+    ($pool:expr, |$conn:ident| async $body:block) => {{
+        let mut tx = $pool.begin().await.map_err($crate::__AppError::from)?;
+        let $conn = &mut *tx;
+
+        let result: Result<_, $crate::__AppError> = async $body.await;
+
+        match result {
+            Ok(value) => {
+                tx.commit().await.map_err($crate::__AppError::from)?;
+                Ok(value)
+            }
+            Err(e) => {
+                let _ = tx.rollback().await;
+                Err(e)
+            }
+        }
+    }};
+}
@@ -0,0 +1,56 @@
+use serde::Deserialize;
+use std::time::Duration;
+
+fn default_connect_timeout_ms() -> u64 {
+    5000
+}
+
+fn default_timeout_ms() -> u64 {
+    3000
+}
+
+fn default_max_retries() -> u32 {
+    2
+}
+
+fn default_retry_backoff_ms() -> u64 {
+    100
+}
+
+/// 下游 gRPC 服务的客户端配置
+#[derive(Debug, Clone, Deserialize)]
+pub struct GrpcClient {
+    /// 注册中心中的服务名，配置了 `registry` 时按它发现实例
+    pub service: String,
+    /// 固定地址（如 `http://127.0.0.1:50051`），未配置 `registry` 时使用
+    #[serde(default)]
+    pub addr: Option<String>,
+    /// 建立连接的超时（毫秒）
+    #[serde(default = "default_connect_timeout_ms")]
+    pub connect_timeout_ms: u64,
+    /// 单次调用的超时（毫秒），入站请求的剩余时间更短时以剩余时间为准
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// 下游不可用（`Unavailable`）时的最大重试次数，0 表示不重试
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// 首次重试前的等待（毫秒），之后每次翻倍
+    #[serde(default = "default_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+}
+
+impl GrpcClient {
+    pub fn connect_timeout(&self) -> Duration {
+        Duration::from_millis(self.connect_timeout_ms)
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms.max(1))
+    }
+
+    /// 第 `attempt` 次重试前的等待（从 1 开始）
+    pub fn retry_backoff(&self, attempt: u32) -> Duration {
+        let factor = 1u64 << attempt.saturating_sub(1).min(10);
+        Duration::from_millis(self.retry_backoff_ms.saturating_mul(factor))
+    }
+}
@@ -0,0 +1,34 @@
+use common_core::{AppError, AppResult};
+use common_registry::RegistryClient;
+use std::time::Duration;
+use tonic::transport::{Channel, Endpoint};
+
+use crate::application::GrpcClient;
+
+/// 创建到下游服务的通道（首次调用时才建立连接）
+///
+/// 配置了注册中心时按实例列表动态负载均衡，否则连接固定地址 `addr`
+pub async fn connect(config: &GrpcClient, registry: Option<&RegistryClient>) -> AppResult<Channel> {
+    let connect_timeout = config.connect_timeout();
+    if let Some(registry) = registry {
+        return Ok(registry
+            .grpc_channel(&config.service, move |addr| endpoint(addr, connect_timeout))
+            .await);
+    }
+    let addr = config.addr.clone().ok_or_else(|| {
+        AppError::internal(format!(
+            "gRPC client for {} requires `addr` without a registry",
+            config.service
+        ))
+    })?;
+    Ok(endpoint(addr, connect_timeout)?.connect_lazy())
+}
+
+/// 单个实例的连接配置；调用超时按每次调用设置（见 [`crate::CallPolicy`]）
+fn endpoint(addr: String, connect_timeout: Duration) -> AppResult<Endpoint> {
+    Ok(Channel::from_shared(addr)
+        .map_err(|e| AppError::internal(format!("Invalid gRPC address: {}", e)))?
+        .connect_timeout(connect_timeout)
+        .http2_keep_alive_interval(Duration::from_secs(30))
+        .keep_alive_timeout(Duration::from_secs(10)))
+}
@@ -0,0 +1,14 @@
+//! 服务间 gRPC 客户端
+//!
+//! 统一的连接方式（固定地址或注册中心发现）、可配置的超时与重试、入站请求截止时间的传播，
+//! 以及请求 ID 和服务间调用凭证的注入；各下游服务的客户端返回业务类型而不是 protobuf 消息。
+
+pub mod application;
+pub mod auth;
+pub mod channel;
+mod policy;
+pub mod user;
+
+pub use auth::{Caller, ServiceToken, VerifyServiceToken};
+pub use policy::CallPolicy;
+pub use user::{UserClient, UserInfo, Web3Account};
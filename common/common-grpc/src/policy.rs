@@ -0,0 +1,158 @@
+use common_web::deadline;
+use std::{future::Future, time::Duration};
+use tonic::{Code, Status};
+
+use crate::application::GrpcClient;
+
+/// 调用策略：单次调用的超时受入站请求剩余时间约束，下游不可用时退避重试
+///
+/// 只重试 `Unavailable`（连接失败、实例下线等），其余错误（包括超时）直接返回
+#[derive(Debug, Clone)]
+pub struct CallPolicy {
+    config: GrpcClient,
+}
+
+impl CallPolicy {
+    pub fn new(config: &GrpcClient) -> Self {
+        Self {
+            config: config.clone(),
+        }
+    }
+
+    /// 执行调用（含重试），每次尝试都会调用 `call`，参数为该次尝试的超时
+    ///
+    /// `call` 应把超时写入请求（`Request::set_timeout`），下游据此得知剩余时间
+    pub async fn call<T, F, Fut>(&self, method: &str, mut call: F) -> Result<T, Status>
+    where
+        F: FnMut(Duration) -> Fut,
+        Fut: Future<Output = Result<T, Status>>,
+    {
+        let mut attempt = 0;
+        loop {
+            let timeout = self.attempt_timeout().ok_or_else(|| {
+                Status::deadline_exceeded(format!("Request deadline exceeded before {}", method))
+            })?;
+            let status = match tokio::time::timeout(timeout, call(timeout)).await {
+                Err(_) => {
+                    return Err(Status::deadline_exceeded(format!(
+                        "{} timed out after {}ms",
+                        method,
+                        timeout.as_millis()
+                    )));
+                }
+                Ok(Err(status))
+                    if status.code() == Code::Unavailable && attempt < self.config.max_retries =>
+                {
+                    status
+                }
+                Ok(result) => return result,
+            };
+
+            attempt += 1;
+            let backoff = self.config.retry_backoff(attempt);
+            // 剩余时间不够再试一次时直接返回本次错误
+            if deadline::remaining().is_some_and(|remaining| remaining <= backoff) {
+                return Err(status);
+            }
+            tracing::warn!(
+                "{} unavailable, retrying in {}ms ({}/{}): {}",
+                method,
+                backoff.as_millis(),
+                attempt,
+                self.config.max_retries,
+                status.message()
+            );
+            tokio::time::sleep(backoff).await;
+        }
+    }
+
+    /// 本次尝试的超时：配置的超时与入站请求剩余时间取较小者，已超时返回 `None`
+    fn attempt_timeout(&self) -> Option<Duration> {
+        match deadline::remaining() {
+            Some(remaining) if remaining.is_zero() => None,
+            Some(remaining) => Some(remaining.min(self.config.timeout())),
+            None => Some(self.config.timeout()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_web::deadline;
+    use std::{
+        sync::atomic::{AtomicU32, Ordering},
+        time::Duration,
+    };
+    use tonic::{Code, Status};
+
+    use super::CallPolicy;
+    use crate::application::GrpcClient;
+
+    fn policy() -> CallPolicy {
+        CallPolicy::new(&GrpcClient {
+            service: "user-service".to_string(),
+            addr: None,
+            connect_timeout_ms: 1000,
+            timeout_ms: 1000,
+            max_retries: 2,
+            retry_backoff_ms: 100,
+        })
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retries_only_unavailable() {
+        let attempts = AtomicU32::new(0);
+        let result: Result<(), Status> = policy()
+            .call("GetUserInfo", |_| async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err(Status::unavailable("connection refused"))
+            })
+            .await;
+        assert_eq!(result.unwrap_err().code(), Code::Unavailable);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+
+        let attempts = AtomicU32::new(0);
+        let result: Result<(), Status> = policy()
+            .call("GetUserInfo", |_| async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err(Status::not_found("User not found"))
+            })
+            .await;
+        assert_eq!(result.unwrap_err().code(), Code::NotFound);
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn propagates_incoming_deadline() {
+        // 入站请求只剩 300ms：单次调用的超时随之缩短
+        let result = deadline::scope(Duration::from_millis(300), async {
+            policy()
+                .call("GetUserInfo", |timeout| async move { Ok(timeout) })
+                .await
+        })
+        .await;
+        assert_eq!(result.unwrap(), Duration::from_millis(300));
+
+        // 超过剩余时间的调用被中止，且不再重试
+        let attempts = AtomicU32::new(0);
+        let result: Result<(), Status> = deadline::scope(Duration::from_millis(300), async {
+            policy()
+                .call("GetUserInfo", |_| async {
+                    attempts.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    Ok(())
+                })
+                .await
+        })
+        .await;
+        assert_eq!(result.unwrap_err().code(), Code::DeadlineExceeded);
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+        // 已经超时的请求不再发起调用
+        let result: Result<(), Status> = deadline::scope(Duration::ZERO, async {
+            policy().call("GetUserInfo", |_| async { Ok(()) }).await
+        })
+        .await;
+        assert_eq!(result.unwrap_err().code(), Code::DeadlineExceeded);
+    }
+}
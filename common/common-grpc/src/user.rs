@@ -0,0 +1,145 @@
+use common_core::{AppError, AppResult};
+use common_metrics::GrpcMetrics;
+use common_proto::user::{
+    RegisterType, RegisterUserReq, UserInfoReq, UserInfoRes, Web3InfoReq,
+    user_service_client::UserServiceClient, user_service_server::SERVICE_NAME,
+};
+use common_registry::RegistryClient;
+use common_tracing::GrpcTrace;
+use common_web::request_id::GrpcRequestId;
+use tonic::{Request, service::interceptor::InterceptedService, transport::Channel};
+
+use crate::{CallPolicy, ServiceToken, application::GrpcClient, channel};
+
+/// 带调用指标、链路追踪、请求 ID 传播和服务间凭证的生成客户端
+type Stub = UserServiceClient<
+    InterceptedService<GrpcMetrics<GrpcTrace<GrpcRequestId<Channel>>>, ServiceToken>,
+>;
+
+/// 用户信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserInfo {
+    pub id: i64,
+    pub username: String,
+    pub email: Option<String>,
+    pub avatar_url: Option<String>,
+    /// 绑定的 Web3 钱包
+    pub web3: Option<Web3Account>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Web3Account {
+    pub chain_id: i64,
+    pub address: String,
+}
+
+impl From<UserInfoRes> for UserInfo {
+    fn from(res: UserInfoRes) -> Self {
+        Self {
+            id: res.id,
+            username: res.username,
+            email: res.email,
+            avatar_url: res.avatar_url,
+            web3: res.web3_info.map(|info| Web3Account {
+                chain_id: info.chain_id,
+                address: info.address,
+            }),
+            created_at: res.created_at,
+            updated_at: res.updated_at,
+        }
+    }
+}
+
+/// 用户服务客户端（可克隆，共享底层连接）
+///
+/// 超时与重试按 [`GrpcClient`] 配置，入站请求带有截止时间时随之缩短；
+/// 下游错误按 gRPC 状态码转换为 [`AppError`]（如 `NotFound`、`Conflict`）
+#[derive(Clone)]
+pub struct UserClient {
+    stub: Stub,
+    /// 原始通道，供就绪检查使用
+    channel: Channel,
+    policy: CallPolicy,
+}
+
+impl UserClient {
+    pub async fn connect(
+        config: &GrpcClient,
+        registry: Option<&RegistryClient>,
+        token: ServiceToken,
+    ) -> AppResult<Self> {
+        let channel = channel::connect(config, registry).await?;
+        let stub = UserServiceClient::with_interceptor(
+            GrpcMetrics::client(GrpcTrace::client(GrpcRequestId::client(channel.clone()))),
+            token,
+        );
+        Ok(Self {
+            stub,
+            channel,
+            policy: CallPolicy::new(config),
+        })
+    }
+
+    /// 通过标准健康检查协议确认用户服务可用
+    pub async fn check_health(&self) -> AppResult<()> {
+        common_proto::health::check(self.channel.clone(), SERVICE_NAME)
+            .await
+            .map_err(AppError::from)
+    }
+
+    /// 按用户 ID 查询，不存在时返回 `AppError::NotFound`
+    pub async fn get_user(&self, user_id: i64) -> AppResult<UserInfo> {
+        let res = self
+            .policy
+            .call("GetUserInfo", |timeout| {
+                let mut stub = self.stub.clone();
+                let mut request = Request::new(UserInfoReq { user_id });
+                request.set_timeout(timeout);
+                async move { stub.get_user_info(request).await }
+            })
+            .await?;
+        Ok(res.into_inner().into())
+    }
+
+    /// 按钱包地址查询，不存在时返回 `AppError::NotFound`
+    pub async fn get_user_by_web3(&self, chain_id: i64, address: &str) -> AppResult<UserInfo> {
+        let res = self
+            .policy
+            .call("GetUserInfoByWeb3", |timeout| {
+                let mut stub = self.stub.clone();
+                let mut request = Request::new(Web3InfoReq {
+                    chain_id,
+                    address: address.to_string(),
+                });
+                request.set_timeout(timeout);
+                async move { stub.get_user_info_by_web3(request).await }
+            })
+            .await?;
+        Ok(res.into_inner().into())
+    }
+
+    /// 以钱包地址注册新用户，返回用户 ID；地址已注册时返回 `AppError::Conflict`
+    ///
+    /// 重试时若前一次实际已注册成功，同样返回 `Conflict`，调用方重新查询即可
+    pub async fn register_web3_user(&self, chain_id: i64, address: &str) -> AppResult<i64> {
+        let res = self
+            .policy
+            .call("RegisterUser", |timeout| {
+                let mut stub = self.stub.clone();
+                let mut request = Request::new(RegisterUserReq {
+                    register_type: RegisterType::Web3 as i32,
+                    username: None,
+                    password: None,
+                    web3_address: Some(address.to_string()),
+                    web3_chain_id: Some(chain_id),
+                    email: None,
+                });
+                request.set_timeout(timeout);
+                async move { stub.register_user(request).await }
+            })
+            .await?;
+        Ok(res.into_inner().user_id)
+    }
+}
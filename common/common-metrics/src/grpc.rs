@@ -0,0 +1,142 @@
+use axum::http::{Request, Response};
+use futures_util::future::BoxFuture;
+use prometheus::{HistogramVec, IntCounterVec, register_histogram_vec, register_int_counter_vec};
+use std::{
+    sync::LazyLock,
+    task::{Context, Poll},
+    time::Instant,
+};
+use tower::{Layer, Service};
+
+static GRPC_SERVER_HANDLED: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "grpc_server_handled_total",
+        "Total number of gRPC calls handled by the server",
+        &["method", "code"]
+    )
+    .expect("register grpc_server_handled_total")
+});
+
+static GRPC_SERVER_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "grpc_server_handling_seconds",
+        "gRPC server handling latency in seconds",
+        &["method", "code"]
+    )
+    .expect("register grpc_server_handling_seconds")
+});
+
+static GRPC_CLIENT_HANDLED: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "grpc_client_handled_total",
+        "Total number of gRPC calls completed by the client",
+        &["method", "code"]
+    )
+    .expect("register grpc_client_handled_total")
+});
+
+static GRPC_CLIENT_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "grpc_client_handling_seconds",
+        "gRPC client call latency in seconds",
+        &["method", "code"]
+    )
+    .expect("register grpc_client_handling_seconds")
+});
+
+#[derive(Debug, Clone, Copy)]
+enum Side {
+    Server,
+    Client,
+}
+
+/// gRPC 指标层，服务端挂在 `Server::builder().layer(..)`，客户端包裹 `Channel`
+///
+/// 状态码取自响应头中的 `grpc-status`：tonic 对失败的一元调用返回 trailers-only 响应，
+/// 状态码在响应头里；成功时状态码在 trailers 中，响应头没有则按 `Ok` 统计
+#[derive(Debug, Clone, Copy)]
+pub struct GrpcMetricsLayer {
+    side: Side,
+}
+
+impl GrpcMetricsLayer {
+    pub fn server() -> Self {
+        Self { side: Side::Server }
+    }
+
+    pub fn client() -> Self {
+        Self { side: Side::Client }
+    }
+}
+
+impl<S> Layer<S> for GrpcMetricsLayer {
+    type Service = GrpcMetrics<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        GrpcMetrics {
+            inner,
+            side: self.side,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GrpcMetrics<S> {
+    inner: S,
+    side: Side,
+}
+
+impl<S> GrpcMetrics<S> {
+    /// 包裹客户端通道：`UserServiceClient::new(GrpcMetrics::client(channel))`
+    pub fn client(inner: S) -> Self {
+        GrpcMetricsLayer::client().layer(inner)
+    }
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for GrpcMetrics<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        // 路径即 `/包名.服务名/方法名`
+        let method = request.uri().path().to_string();
+        let side = self.side;
+        let start = Instant::now();
+        let future = self.inner.call(request);
+
+        Box::pin(async move {
+            let result = future.await;
+            let code = match &result {
+                Ok(response) => response
+                    .headers()
+                    .get("grpc-status")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.parse::<i32>().ok())
+                    .map(tonic::Code::from)
+                    .unwrap_or(tonic::Code::Ok),
+                // 传输层错误（连接失败、超时等）
+                Err(_) => tonic::Code::Unavailable,
+            };
+            let code = format!("{:?}", code);
+            let (handled, duration) = match side {
+                Side::Server => (&GRPC_SERVER_HANDLED, &GRPC_SERVER_DURATION),
+                Side::Client => (&GRPC_CLIENT_HANDLED, &GRPC_CLIENT_DURATION),
+            };
+            let labels = [method.as_str(), code.as_str()];
+            handled.with_label_values(&labels).inc();
+            duration
+                .with_label_values(&labels)
+                .observe(start.elapsed().as_secs_f64());
+            result
+        })
+    }
+}
@@ -0,0 +1,64 @@
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+use prometheus::{HistogramVec, IntCounterVec, register_histogram_vec, register_int_counter_vec};
+use std::{sync::LazyLock, time::Instant};
+
+static HTTP_REQUESTS_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "http_requests_total",
+        "Total number of HTTP requests",
+        &["method", "route", "status"]
+    )
+    .expect("register http_requests_total")
+});
+
+static HTTP_REQUEST_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "http_request_duration_seconds",
+        "HTTP request latency in seconds",
+        &["method", "route", "status"]
+    )
+    .expect("register http_request_duration_seconds")
+});
+
+/// 未匹配任何路由的请求统一归为一类，避免任意路径撑爆标签基数
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// 自定义路由标签（响应扩展）
+///
+/// 默认使用 axum 匹配到的路由模板；网关等所有请求走同一个通配路由的服务，
+/// 可以在响应中放入该扩展，按自己的路由规则统计
+#[derive(Debug, Clone)]
+pub struct RouteLabel(pub String);
+
+/// HTTP 指标中间件：按方法、路由、状态码统计请求数和耗时
+///
+/// 需要通过 `Router::layer` 挂载，才能取到匹配的路由模板
+pub async fn http_metrics(request: Request, next: Next) -> Response {
+    let method = request.method().to_string();
+    let matched = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string());
+    let start = Instant::now();
+
+    let response = next.run(request).await;
+
+    let route = response
+        .extensions()
+        .get::<RouteLabel>()
+        .map(|label| label.0.clone())
+        .or(matched)
+        .unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+    let status = response.status().as_u16().to_string();
+    let labels = [method.as_str(), route.as_str(), status.as_str()];
+    HTTP_REQUESTS_TOTAL.with_label_values(&labels).inc();
+    HTTP_REQUEST_DURATION
+        .with_label_values(&labels)
+        .observe(start.elapsed().as_secs_f64());
+
+    response
+}
@@ -0,0 +1,29 @@
+//! Prometheus 指标：HTTP 请求、gRPC 调用、连接池使用情况
+//!
+//! 所有指标注册到 `prometheus` 默认注册表，由 [`metrics_handler`] 以文本格式输出。
+//! 各服务的专有指标（如网关的上游耗时）同样注册到默认注册表即可一并导出。
+
+use axum::{
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use prometheus::{Encoder, TextEncoder};
+
+pub mod grpc;
+pub mod http;
+pub mod pool;
+
+pub use grpc::{GrpcMetrics, GrpcMetricsLayer};
+pub use http::{RouteLabel, http_metrics};
+pub use pool::{PoolStats, register_pool};
+
+/// `GET /metrics`：Prometheus 文本格式
+pub async fn metrics_handler() -> Response {
+    let encoder = TextEncoder::new();
+    let mut buffer = Vec::new();
+    if let Err(e) = encoder.encode(&prometheus::gather(), &mut buffer) {
+        tracing::error!("Failed to encode metrics: {}", e);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+    ([(header::CONTENT_TYPE, encoder.format_type())], buffer).into_response()
+}
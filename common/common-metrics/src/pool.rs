@@ -0,0 +1,130 @@
+use prometheus::{
+    IntGaugeVec, Opts,
+    core::{Collector, Desc},
+    proto::MetricFamily,
+};
+use std::{
+    collections::BTreeMap,
+    sync::{LazyLock, Once, RwLock},
+};
+
+/// 连接池快照
+#[derive(Debug, Clone, Copy)]
+pub struct PoolStats {
+    /// 最大连接数
+    pub max: u32,
+    /// 当前已建立的连接数
+    pub open: u32,
+    /// 其中空闲的连接数
+    pub idle: u32,
+}
+
+type StatsFn = Box<dyn Fn() -> PoolStats + Send + Sync>;
+
+/// 已登记的连接池，抓取指标时才读取各池的实时状态
+static POOLS: LazyLock<RwLock<BTreeMap<String, StatsFn>>> =
+    LazyLock::new(|| RwLock::new(BTreeMap::new()));
+static REGISTER_COLLECTOR: Once = Once::new();
+
+/// 登记连接池；同名重复登记时替换旧的（如网关热更新后重建的 Redis 连接池）
+pub fn register_pool(name: &str, stats: impl Fn() -> PoolStats + Send + Sync + 'static) {
+    REGISTER_COLLECTOR.call_once(|| {
+        if let Err(e) = prometheus::register(Box::new(PoolCollector::new())) {
+            tracing::error!("Failed to register pool metrics: {}", e);
+        }
+    });
+    POOLS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(name.to_string(), Box::new(stats));
+}
+
+struct PoolCollector {
+    connections: IntGaugeVec,
+    max_connections: IntGaugeVec,
+}
+
+impl PoolCollector {
+    fn new() -> Self {
+        Self {
+            connections: IntGaugeVec::new(
+                Opts::new("pool_connections", "Connections in the pool by state"),
+                &["pool", "state"],
+            )
+            .expect("pool_connections opts"),
+            max_connections: IntGaugeVec::new(
+                Opts::new("pool_max_connections", "Maximum connections of the pool"),
+                &["pool"],
+            )
+            .expect("pool_max_connections opts"),
+        }
+    }
+}
+
+impl Collector for PoolCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        let mut desc = self.connections.desc();
+        desc.extend(self.max_connections.desc());
+        desc
+    }
+
+    fn collect(&self) -> Vec<MetricFamily> {
+        self.connections.reset();
+        self.max_connections.reset();
+
+        let pools = POOLS.read().unwrap_or_else(|e| e.into_inner());
+        for (name, stats) in pools.iter() {
+            let stats = stats();
+            let in_use = stats.open.saturating_sub(stats.idle);
+            self.connections
+                .with_label_values(&[name.as_str(), "idle"])
+                .set(i64::from(stats.idle));
+            self.connections
+                .with_label_values(&[name.as_str(), "in_use"])
+                .set(i64::from(in_use));
+            self.max_connections
+                .with_label_values(&[name.as_str()])
+                .set(i64::from(stats.max));
+        }
+
+        let mut families = self.connections.collect();
+        families.extend(self.max_connections.collect());
+        families
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prometheus::{Encoder, TextEncoder};
+
+    use super::{PoolStats, register_pool};
+
+    fn exported() -> String {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&prometheus::gather(), &mut buffer)
+            .unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn exports_registered_pools_and_replaces_by_name() {
+        register_pool("test", || PoolStats {
+            max: 10,
+            open: 4,
+            idle: 1,
+        });
+        let text = exported();
+        assert!(text.contains(r#"pool_connections{pool="test",state="in_use"} 3"#));
+        assert!(text.contains(r#"pool_connections{pool="test",state="idle"} 1"#));
+        assert!(text.contains(r#"pool_max_connections{pool="test"} 10"#));
+
+        register_pool("test", || PoolStats {
+            max: 10,
+            open: 2,
+            idle: 2,
+        });
+        let text = exported();
+        assert!(text.contains(r#"pool_connections{pool="test",state="in_use"} 0"#));
+    }
+}
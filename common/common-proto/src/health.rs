@@ -0,0 +1,28 @@
+//! 标准 gRPC 健康检查协议（`grpc.health.v1.Health`）
+
+use tonic::{Status, transport::Channel};
+use tonic_health::pb::{HealthCheckRequest, health_check_response, health_client::HealthClient};
+
+pub use tonic_health::{
+    ServingStatus,
+    server::{HealthReporter, health_reporter},
+};
+
+/// 查询下游服务的健康状态，非 `SERVING` 时返回 `Unavailable`
+///
+/// `service` 为完整服务名（如 `user::user_service_server::SERVICE_NAME`），空字符串表示整个服务器
+pub async fn check(channel: Channel, service: &str) -> Result<(), Status> {
+    let response = HealthClient::new(channel)
+        .check(HealthCheckRequest {
+            service: service.to_string(),
+        })
+        .await?;
+    match response.into_inner().status() {
+        health_check_response::ServingStatus::Serving => Ok(()),
+        status => Err(Status::unavailable(format!(
+            "{} is {}",
+            service,
+            status.as_str_name()
+        ))),
+    }
+}
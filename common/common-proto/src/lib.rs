@@ -1,3 +1,5 @@
+pub mod health;
+
 pub mod user {
     tonic::include_proto!("user");
 }
@@ -3,16 +3,21 @@ use bb8_redis::{
     bb8::{Pool, PooledConnection},
 };
 use common_core::{AppError, AppResult};
-use redis::{AsyncCommands, cmd};
+use common_metrics::PoolStats;
+use redis::{AsyncCommands, FromRedisValue, ToRedisArgs, cmd};
+use std::time::Duration;
 
 pub mod application;
 
+pub use redis::Script;
+
 pub type ConnectionPool = Pool<RedisConnectionManager>;
 pub type Connection<'a> = PooledConnection<'a, RedisConnectionManager>;
 
 #[derive(Clone)]
 pub struct RedisClient {
     pool: ConnectionPool,
+    max_size: u32,
 }
 
 impl RedisClient {
@@ -32,7 +37,10 @@ impl RedisClient {
                     url_safe, redis_config.pool_size
                 ))
             })?;
-        let client = Self { pool };
+        let client = Self {
+            pool,
+            max_size: redis_config.pool_size,
+        };
         // 启动即校验 Redis 可用性，避免运行期才发现连接问题
         client.ping().await.map_err(|e| {
             AppError::redis(format!(
@@ -44,6 +52,43 @@ impl RedisClient {
         Ok(client)
     }
 
+    /// 创建连接池但不立即建立连接，Redis 不可用时也能启动（调用方自行降级）
+    ///
+    /// `connection_timeout` 为获取连接的最长等待时间，Redis 宕机时请求最多阻塞这么久
+    pub fn new_lazy(
+        redis_config: application::Redis,
+        connection_timeout: Duration,
+    ) -> AppResult<Self> {
+        let url_safe = redis_config.url_safe();
+        let manager = RedisConnectionManager::new(redis_config.url()).map_err(|e| {
+            AppError::redis(format!("create manager failed (url={}): {e}", url_safe))
+        })?;
+
+        let pool = Pool::builder()
+            .max_size(redis_config.pool_size)
+            .connection_timeout(connection_timeout)
+            .build_unchecked(manager);
+
+        Ok(Self {
+            pool,
+            max_size: redis_config.pool_size,
+        })
+    }
+
+    /// 登记连接池指标（`pool_connections{pool="<name>"}` 等），同名登记会替换旧的连接池
+    pub fn register_pool_metrics(&self, name: &str) {
+        let pool = self.pool.clone();
+        let max = self.max_size;
+        common_metrics::register_pool(name, move || {
+            let state = pool.state();
+            PoolStats {
+                max,
+                open: state.connections,
+                idle: state.idle_connections,
+            }
+        });
+    }
+
     pub async fn get(&self) -> AppResult<Connection<'_>> {
         self.pool
             .get()
@@ -89,6 +134,27 @@ impl RedisClient {
             .map_err(|e| AppError::redis(format!("DEL failed (key={}): {}", key, e)))
     }
 
+    /// 执行 Lua 脚本（EVALSHA，脚本未缓存时自动回退为 EVAL）
+    pub async fn run_script<K, A, T>(&self, script: &Script, keys: &[K], args: &[A]) -> AppResult<T>
+    where
+        K: ToRedisArgs,
+        A: ToRedisArgs,
+        T: FromRedisValue,
+    {
+        let mut conn = self.get().await?;
+        let mut invocation = script.prepare_invoke();
+        for key in keys {
+            invocation.key(key);
+        }
+        for arg in args {
+            invocation.arg(arg);
+        }
+        invocation
+            .invoke_async(&mut *conn)
+            .await
+            .map_err(|e| AppError::redis(format!("EVALSHA failed: {}", e)))
+    }
+
     /// 获取字符串值
     pub async fn get_str(&self, key: &str) -> AppResult<Option<String>> {
         let mut conn = self.get().await?;
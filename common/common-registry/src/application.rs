@@ -0,0 +1,95 @@
+use common_core::{AppError, AppResult};
+use common_redis::application::Redis;
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::Instance;
+
+fn default_key_prefix() -> String {
+    "registry".into()
+}
+
+fn default_ttl_seconds() -> u64 {
+    15
+}
+
+fn default_heartbeat_seconds() -> u64 {
+    5
+}
+
+fn default_watch_interval_seconds() -> u64 {
+    5
+}
+
+fn default_advertise_host() -> String {
+    "127.0.0.1".into()
+}
+
+/// 注册中心后端
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// 实例列表写在文件中，不支持注册（文件修改后在下一次解析时生效）
+    Static,
+    /// 实例定期心跳写入 Redis，超过 `ttl_seconds` 未续期自动失效
+    Redis,
+}
+
+/// 服务注册与发现
+#[derive(Debug, Clone, Deserialize)]
+pub struct Registry {
+    pub backend: Backend,
+    /// `static` 后端的实例列表文件（YAML：服务名 -> 实例列表）
+    #[serde(default)]
+    pub file: Option<String>,
+    /// `redis` 后端的连接配置
+    #[serde(default)]
+    pub redis: Option<Redis>,
+    /// `redis` 后端的 key 前缀，每个服务一个 key：`{key_prefix}:{服务名}`
+    #[serde(default = "default_key_prefix")]
+    pub key_prefix: String,
+    /// 注册信息的有效期（秒），实例异常退出后最多这么久被摘除
+    #[serde(default = "default_ttl_seconds")]
+    pub ttl_seconds: u64,
+    /// 心跳间隔（秒），应明显小于 `ttl_seconds`
+    #[serde(default = "default_heartbeat_seconds")]
+    pub heartbeat_seconds: u64,
+    /// 客户端重新解析实例列表的间隔（秒）
+    #[serde(default = "default_watch_interval_seconds")]
+    pub watch_interval_seconds: u64,
+    /// 注册时公布的主机名或 IP（其他服务用它访问本实例），端口取自监听地址
+    #[serde(default = "default_advertise_host")]
+    pub advertise_host: String,
+}
+
+impl Registry {
+    pub fn ttl(&self) -> Duration {
+        Duration::from_secs(self.ttl_seconds.max(1))
+    }
+
+    pub fn heartbeat_interval(&self) -> Duration {
+        Duration::from_secs(self.heartbeat_seconds.max(1))
+    }
+
+    pub fn watch_interval(&self) -> Duration {
+        Duration::from_secs(self.watch_interval_seconds.max(1))
+    }
+
+    /// 本实例的注册信息：`http://{advertise_host}:{监听端口}`
+    pub fn instance(&self, bind_addr: &str, grpc_addr: Option<&str>) -> AppResult<Instance> {
+        Ok(Instance {
+            url: self.advertise_url(bind_addr)?,
+            grpc_url: grpc_addr.map(|addr| self.advertise_url(addr)).transpose()?,
+        })
+    }
+
+    fn advertise_url(&self, bind_addr: &str) -> AppResult<String> {
+        let port = bind_addr
+            .rsplit_once(':')
+            .and_then(|(_, port)| port.parse::<u16>().ok())
+            .ok_or_else(|| {
+                AppError::internal(format!("Missing port in listen address `{}`", bind_addr))
+            })?;
+        Ok(format!("http://{}:{}", self.advertise_host, port))
+    }
+}
@@ -0,0 +1,68 @@
+use common_core::AppResult;
+use std::collections::HashSet;
+use tokio::sync::watch;
+use tonic::transport::{Channel, Endpoint, channel::Change};
+
+use crate::{Instance, RegistryClient};
+
+/// 实例变更通知的缓冲大小
+const CHANGE_BUFFER: usize = 16;
+
+/// 按实例列表动态负载均衡的 gRPC 通道：实例上线时加入，下线时移除
+///
+/// `endpoint` 根据 gRPC 地址创建连接配置（超时、keep-alive 等），地址无效的实例被忽略
+pub fn balanced_channel<F>(mut instances: watch::Receiver<Vec<Instance>>, endpoint: F) -> Channel
+where
+    F: Fn(String) -> AppResult<Endpoint> + Send + 'static,
+{
+    let (channel, changes) = Channel::balance_channel::<String>(CHANGE_BUFFER);
+    tokio::spawn(async move {
+        let mut current = HashSet::new();
+        loop {
+            let latest: HashSet<String> = instances
+                .borrow_and_update()
+                .iter()
+                .filter_map(|instance| instance.grpc_url.clone())
+                .collect();
+
+            for removed in current.difference(&latest) {
+                if changes.send(Change::Remove(removed.clone())).await.is_err() {
+                    return;
+                }
+            }
+            for added in latest.difference(&current) {
+                let endpoint = match endpoint(added.clone()) {
+                    Ok(endpoint) => endpoint,
+                    Err(e) => {
+                        tracing::warn!("Ignoring gRPC instance {}: {}", added, e);
+                        continue;
+                    }
+                };
+                if changes
+                    .send(Change::Insert(added.clone(), endpoint))
+                    .await
+                    .is_err()
+                {
+                    return;
+                }
+            }
+            current = latest;
+
+            // 注册中心监听任务退出（或通道已释放）后不再更新
+            if instances.changed().await.is_err() {
+                return;
+            }
+        }
+    });
+    channel
+}
+
+impl RegistryClient {
+    /// 到某个服务的 gRPC 通道，随注册中心中的实例列表自动更新
+    pub async fn grpc_channel<F>(&self, service: &str, endpoint: F) -> Channel
+    where
+        F: Fn(String) -> AppResult<Endpoint> + Send + 'static,
+    {
+        balanced_channel(self.watch(service).await, endpoint)
+    }
+}
@@ -0,0 +1,319 @@
+//! 服务注册与发现
+//!
+//! 服务启动后把自己的地址注册到注册中心并定期心跳，停机时注销；
+//! 网关和 gRPC 客户端按服务名解析实例列表并定期刷新，实例上下线无需修改配置或重启。
+
+use async_trait::async_trait;
+use common_core::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::{future::Future, sync::Arc};
+use tokio::{sync::watch, task::JoinHandle, time::MissedTickBehavior};
+
+use application::{Backend, Registry};
+
+pub mod application;
+pub mod grpc;
+pub mod redis;
+pub mod static_file;
+
+pub use redis::RedisRegistry;
+pub use static_file::StaticFileRegistry;
+
+/// 一个服务实例
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Instance {
+    /// HTTP 地址，如 `http://10.0.0.5:5010`
+    pub url: String,
+    /// gRPC 地址，不提供 gRPC 服务时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grpc_url: Option<String>,
+}
+
+/// 注册中心抽象
+#[async_trait]
+pub trait ServiceRegistry: Send + Sync {
+    /// 注册或续期实例（心跳即重复注册）
+    async fn register(&self, service: &str, instance: &Instance) -> AppResult<()>;
+
+    /// 注销实例，不存在时视为成功
+    async fn deregister(&self, service: &str, instance: &Instance) -> AppResult<()>;
+
+    /// 服务当前存活的实例
+    async fn resolve(&self, service: &str) -> AppResult<Vec<Instance>>;
+}
+
+/// 注册中心客户端：注册本实例，解析和监听其他服务的实例
+#[derive(Clone)]
+pub struct RegistryClient {
+    registry: Arc<dyn ServiceRegistry>,
+    config: Registry,
+}
+
+impl RegistryClient {
+    /// 根据配置创建注册中心实现
+    pub fn new(config: &Registry) -> AppResult<Self> {
+        let registry: Arc<dyn ServiceRegistry> = match config.backend {
+            Backend::Static => {
+                let file = config.file.as_deref().ok_or_else(|| {
+                    AppError::internal("registry.file is required for the static backend")
+                })?;
+                tracing::info!("Service registry: static file ({})", file);
+                Arc::new(StaticFileRegistry::new(file))
+            }
+            Backend::Redis => {
+                let redis = config.redis.as_ref().ok_or_else(|| {
+                    AppError::internal("registry.redis is required for the redis backend")
+                })?;
+                tracing::info!(
+                    "Service registry: redis ({}, prefix={})",
+                    redis.url_safe(),
+                    config.key_prefix
+                );
+                Arc::new(RedisRegistry::new(redis, &config.key_prefix, config.ttl())?)
+            }
+        };
+        Ok(Self::with_registry(registry, config))
+    }
+
+    /// 使用自定义的注册中心实现
+    pub fn with_registry(registry: Arc<dyn ServiceRegistry>, config: &Registry) -> Self {
+        Self {
+            registry,
+            config: config.clone(),
+        }
+    }
+
+    pub fn config(&self) -> &Registry {
+        &self.config
+    }
+
+    /// 服务当前存活的实例（按地址排序，便于比较变化）
+    pub async fn resolve(&self, service: &str) -> AppResult<Vec<Instance>> {
+        let mut instances = self.registry.resolve(service).await?;
+        instances.sort();
+        instances.dedup();
+        Ok(instances)
+    }
+
+    /// 注册本实例并定期心跳，`shutdown` 完成后注销并退出
+    ///
+    /// 注册失败不影响启动，下一次心跳时重试；`static` 后端不注册，返回 `None`
+    pub fn register<F>(
+        &self,
+        service: &str,
+        instance: Instance,
+        shutdown: F,
+    ) -> Option<JoinHandle<()>>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        if self.config.backend == Backend::Static {
+            tracing::info!(
+                "Static service registry, skipping registration of {}",
+                service
+            );
+            return None;
+        }
+
+        let registry = self.registry.clone();
+        let service = service.to_string();
+        let interval = self.config.heartbeat_interval();
+        Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            tokio::pin!(shutdown);
+
+            let mut registered = false;
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = &mut shutdown => break,
+                }
+                match registry.register(&service, &instance).await {
+                    Ok(()) if !registered => {
+                        tracing::info!("Registered {} instance {}", service, instance.url);
+                        registered = true;
+                    }
+                    Ok(()) => {}
+                    Err(e) => {
+                        tracing::warn!(
+                            "Failed to register {} instance {}: {}",
+                            service,
+                            instance.url,
+                            e
+                        );
+                        registered = false;
+                    }
+                }
+            }
+
+            match registry.deregister(&service, &instance).await {
+                Ok(()) => tracing::info!("Deregistered {} instance {}", service, instance.url),
+                Err(e) => tracing::warn!(
+                    "Failed to deregister {} instance {}, it expires after the TTL: {}",
+                    service,
+                    instance.url,
+                    e
+                ),
+            }
+        }))
+    }
+
+    /// 监听服务的实例列表：先解析一次，之后按 `watch_interval_seconds` 刷新，列表变化时通知
+    ///
+    /// 解析失败时保留上一次的结果（注册中心短暂不可用不会摘除所有实例）；
+    /// 所有接收端释放后后台任务自动退出
+    pub async fn watch(&self, service: &str) -> watch::Receiver<Vec<Instance>> {
+        let initial = self.resolve(service).await.unwrap_or_else(|e| {
+            tracing::warn!("Failed to resolve {}: {}", service, e);
+            Vec::new()
+        });
+        tracing::info!("Resolved {} instances of {}", initial.len(), service);
+        let (sender, receiver) = watch::channel(initial);
+
+        let client = self.clone();
+        let service = service.to_string();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(client.config.watch_interval());
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            ticker.tick().await;
+
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = sender.closed() => break,
+                }
+                match client.resolve(&service).await {
+                    Ok(instances) => {
+                        sender.send_if_modified(|current| {
+                            if *current == instances {
+                                return false;
+                            }
+                            tracing::info!(
+                                "Instances of {} changed: {:?}",
+                                service,
+                                instances.iter().map(|i| &i.url).collect::<Vec<_>>()
+                            );
+                            *current = instances;
+                            true
+                        });
+                    }
+                    Err(e) => tracing::warn!(
+                        "Failed to resolve {}, keeping {} known instances: {}",
+                        service,
+                        sender.borrow().len(),
+                        e
+                    ),
+                }
+            }
+        });
+        receiver
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use common_core::{AppError, AppResult};
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use super::{Instance, RegistryClient, ServiceRegistry, application::Registry};
+
+    /// 内存实现，`fail` 为 true 时模拟注册中心不可用
+    #[derive(Default)]
+    struct MemoryRegistry {
+        instances: Mutex<Vec<(String, Instance)>>,
+        fail: Mutex<bool>,
+    }
+
+    impl MemoryRegistry {
+        fn check(&self) -> AppResult<()> {
+            if *self.fail.lock().unwrap() {
+                return Err(AppError::redis("connection refused"));
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ServiceRegistry for MemoryRegistry {
+        async fn register(&self, service: &str, instance: &Instance) -> AppResult<()> {
+            self.check()?;
+            let mut instances = self.instances.lock().unwrap();
+            let entry = (service.to_string(), instance.clone());
+            if !instances.contains(&entry) {
+                instances.push(entry);
+            }
+            Ok(())
+        }
+
+        async fn deregister(&self, service: &str, instance: &Instance) -> AppResult<()> {
+            self.check()?;
+            self.instances
+                .lock()
+                .unwrap()
+                .retain(|(s, i)| s != service || i != instance);
+            Ok(())
+        }
+
+        async fn resolve(&self, service: &str) -> AppResult<Vec<Instance>> {
+            self.check()?;
+            Ok(self
+                .instances
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(s, _)| s == service)
+                .map(|(_, i)| i.clone())
+                .collect())
+        }
+    }
+
+    fn config() -> Registry {
+        serde_yml::from_str(
+            "backend: redis\nheartbeat_seconds: 1\nwatch_interval_seconds: 1\nadvertise_host: 10.0.0.5",
+        )
+        .unwrap()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn registers_until_shutdown_and_watchers_follow() {
+        let memory = Arc::new(MemoryRegistry::default());
+        let client = RegistryClient::with_registry(memory.clone(), &config());
+        let instance = client
+            .config()
+            .instance("0.0.0.0:5010", Some("0.0.0.0:50051"))
+            .unwrap();
+        assert_eq!(instance.url, "http://10.0.0.5:5010");
+        assert_eq!(instance.grpc_url.as_deref(), Some("http://10.0.0.5:50051"));
+
+        let mut watcher = client.watch("user-service").await;
+        assert!(watcher.borrow().is_empty());
+
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let registration = client
+            .register("user-service", instance.clone(), async {
+                let _ = stopped.await;
+            })
+            .unwrap();
+
+        watcher.changed().await.unwrap();
+        assert_eq!(*watcher.borrow_and_update(), vec![instance.clone()]);
+
+        // 注册中心不可用时保留已知实例
+        *memory.fail.lock().unwrap() = true;
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        assert!(!watcher.has_changed().unwrap());
+        assert_eq!(*watcher.borrow(), vec![instance]);
+
+        // 停机时注销
+        *memory.fail.lock().unwrap() = false;
+        stop.send(()).unwrap();
+        registration.await.unwrap();
+        watcher.changed().await.unwrap();
+        assert!(watcher.borrow().is_empty());
+    }
+}
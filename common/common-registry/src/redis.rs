@@ -0,0 +1,113 @@
+use async_trait::async_trait;
+use common_core::{AppError, AppResult};
+use common_redis::{RedisClient, Script, application::Redis};
+use std::time::Duration;
+
+use crate::{Instance, ServiceRegistry};
+
+/// 获取 Redis 连接的最长等待时间，注册中心不可用时心跳和解析最多阻塞这么久
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 注册或续期：实例序列化后作为有序集合成员，分数为过期时间（Redis 服务器时间，毫秒）
+///
+/// KEYS[1] = 服务 key；ARGV[1] = 实例；ARGV[2] = 有效期（毫秒）
+const REGISTER_SCRIPT: &str = r#"
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+local ttl = tonumber(ARGV[2])
+redis.call('ZADD', KEYS[1], now + ttl, ARGV[1])
+redis.call('PEXPIRE', KEYS[1], ttl)
+return 1
+"#;
+
+/// 清理已过期的实例，返回仍存活的实例
+///
+/// KEYS[1] = 服务 key
+const RESOLVE_SCRIPT: &str = r#"
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+redis.call('ZREMRANGEBYSCORE', KEYS[1], '-inf', now)
+return redis.call('ZRANGEBYSCORE', KEYS[1], now, '+inf')
+"#;
+
+/// KEYS[1] = 服务 key；ARGV[1] = 实例
+const DEREGISTER_SCRIPT: &str = r#"
+return redis.call('ZREM', KEYS[1], ARGV[1])
+"#;
+
+/// 基于 Redis 的注册中心：每个服务一个有序集合，实例靠心跳续期，过期后在解析时清理
+pub struct RedisRegistry {
+    client: RedisClient,
+    key_prefix: String,
+    ttl: Duration,
+    register: Script,
+    resolve: Script,
+    deregister: Script,
+}
+
+impl RedisRegistry {
+    /// 不立即建立连接，Redis 暂时不可用时服务也能启动，恢复后由心跳补上注册
+    pub fn new(config: &Redis, key_prefix: &str, ttl: Duration) -> AppResult<Self> {
+        Ok(Self {
+            client: RedisClient::new_lazy(config.clone(), CONNECTION_TIMEOUT)?,
+            key_prefix: key_prefix.to_string(),
+            ttl,
+            register: Script::new(REGISTER_SCRIPT),
+            resolve: Script::new(RESOLVE_SCRIPT),
+            deregister: Script::new(DEREGISTER_SCRIPT),
+        })
+    }
+
+    fn key(&self, service: &str) -> String {
+        format!("{}:{}", self.key_prefix, service)
+    }
+}
+
+fn encode(instance: &Instance) -> AppResult<String> {
+    serde_json::to_string(instance)
+        .map_err(|e| AppError::internal(format!("Failed to encode instance: {}", e)))
+}
+
+#[async_trait]
+impl ServiceRegistry for RedisRegistry {
+    async fn register(&self, service: &str, instance: &Instance) -> AppResult<()> {
+        let ttl_ms = self.ttl.as_millis().to_string();
+        self.client
+            .run_script::<_, _, i64>(
+                &self.register,
+                &[self.key(service)],
+                &[encode(instance)?, ttl_ms],
+            )
+            .await
+            .map(|_| ())
+    }
+
+    async fn deregister(&self, service: &str, instance: &Instance) -> AppResult<()> {
+        self.client
+            .run_script::<_, _, i64>(&self.deregister, &[self.key(service)], &[encode(instance)?])
+            .await
+            .map(|_| ())
+    }
+
+    async fn resolve(&self, service: &str) -> AppResult<Vec<Instance>> {
+        let members: Vec<String> = self
+            .client
+            .run_script::<_, String, _>(&self.resolve, &[self.key(service)], &[])
+            .await?;
+        Ok(members
+            .iter()
+            .filter_map(|member| match serde_json::from_str(member) {
+                Ok(instance) => Some(instance),
+                Err(e) => {
+                    tracing::warn!(
+                        "Ignoring malformed instance of {}: {} ({})",
+                        service,
+                        member,
+                        e
+                    );
+                    None
+                }
+            })
+            .collect())
+    }
+}
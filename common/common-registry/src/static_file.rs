@@ -0,0 +1,57 @@
+use async_trait::async_trait;
+use common_core::{AppError, AppResult};
+use std::{collections::HashMap, path::PathBuf};
+
+use crate::{Instance, ServiceRegistry};
+
+/// 基于文件的注册中心：实例列表由运维维护，每次解析时重新读取文件，修改后无需重启
+///
+/// ```yaml
+/// user-service:
+///   - url: http://10.0.0.5:5010
+///     grpc_url: http://10.0.0.5:50051
+/// article-service:
+///   - url: http://10.0.0.6:5030
+/// ```
+pub struct StaticFileRegistry {
+    path: PathBuf,
+}
+
+impl StaticFileRegistry {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    async fn load(&self) -> AppResult<HashMap<String, Vec<Instance>>> {
+        let content = tokio::fs::read_to_string(&self.path).await.map_err(|e| {
+            AppError::internal(format!(
+                "Failed to read registry file {}: {}",
+                self.path.display(),
+                e
+            ))
+        })?;
+        serde_yml::from_str(&content).map_err(|e| {
+            AppError::internal(format!(
+                "Invalid registry file {}: {}",
+                self.path.display(),
+                e
+            ))
+        })
+    }
+}
+
+#[async_trait]
+impl ServiceRegistry for StaticFileRegistry {
+    /// 文件由运维维护，实例不自行注册
+    async fn register(&self, _service: &str, _instance: &Instance) -> AppResult<()> {
+        Ok(())
+    }
+
+    async fn deregister(&self, _service: &str, _instance: &Instance) -> AppResult<()> {
+        Ok(())
+    }
+
+    async fn resolve(&self, service: &str) -> AppResult<Vec<Instance>> {
+        Ok(self.load().await?.remove(service).unwrap_or_default())
+    }
+}
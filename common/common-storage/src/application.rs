@@ -0,0 +1,37 @@
+use serde::Deserialize;
+
+fn default_region() -> String {
+    "us-east-1".into()
+}
+
+/// 对象存储配置，`backend` 决定使用哪种实现
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "backend", rename_all = "lowercase")]
+pub enum Storage {
+    Local(LocalStorage),
+    S3(S3Storage),
+}
+
+/// 本地文件系统存储
+#[derive(Debug, Clone, Deserialize)]
+pub struct LocalStorage {
+    /// 文件根目录
+    pub root: String,
+    /// 对外访问地址前缀，如 `http://127.0.0.1:8080/api/article/media/files`
+    pub public_base_url: String,
+}
+
+/// S3 兼容存储（AWS S3 / MinIO）
+#[derive(Debug, Clone, Deserialize)]
+pub struct S3Storage {
+    /// 自定义 endpoint，MinIO 如 `http://127.0.0.1:9000`，AWS 可不填
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    pub bucket: String,
+    #[serde(default = "default_region")]
+    pub region: String,
+    pub access_key: String,
+    pub secret_key: String,
+    /// 对外访问地址前缀，如 `http://127.0.0.1:9000/blog-media`
+    pub public_base_url: String,
+}
@@ -0,0 +1,122 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use common_core::AppResult;
+
+pub mod application;
+pub mod local;
+pub mod s3;
+
+pub use local::LocalBlobStore;
+pub use s3::S3BlobStore;
+
+/// 对象存储抽象，key 形如 `covers/1/123.jpg`
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    /// 写入对象（已存在则覆盖）
+    async fn put(&self, key: &str, data: Bytes, content_type: &str) -> AppResult<()>;
+
+    /// 读取对象，不存在时返回 `None`
+    async fn get(&self, key: &str) -> AppResult<Option<Bytes>>;
+
+    /// 删除对象，不存在时视为成功
+    async fn delete(&self, key: &str) -> AppResult<()>;
+
+    /// 对象的对外访问地址
+    fn public_url(&self, key: &str) -> String;
+}
+
+/// 根据配置创建存储实现
+pub fn build_blob_store(config: &application::Storage) -> AppResult<Arc<dyn BlobStore>> {
+    let store: Arc<dyn BlobStore> = match config {
+        application::Storage::Local(local) => {
+            tracing::info!("Blob store: local filesystem (root={})", local.root);
+            Arc::new(LocalBlobStore::new(local))
+        }
+        application::Storage::S3(s3) => {
+            tracing::info!(
+                "Blob store: S3 (endpoint={:?}, bucket={})",
+                s3.endpoint,
+                s3.bucket
+            );
+            Arc::new(S3BlobStore::new(s3)?)
+        }
+    };
+    Ok(store)
+}
+
+pub(crate) fn join_url(base: &str, key: &str) -> String {
+    format!(
+        "{}/{}",
+        base.trim_end_matches('/'),
+        key.trim_start_matches('/')
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::{
+        BlobStore, LocalBlobStore, S3BlobStore,
+        application::{LocalStorage, S3Storage},
+    };
+
+    #[tokio::test]
+    async fn local_store_round_trip() {
+        let root = std::env::temp_dir().join(format!("blob-store-test-{}", std::process::id()));
+        let store = LocalBlobStore::new(&LocalStorage {
+            root: root.to_string_lossy().into_owned(),
+            public_base_url: "http://localhost/media/".into(),
+        });
+
+        store
+            .put("covers/1/a.png", Bytes::from_static(b"png"), "image/png")
+            .await
+            .unwrap();
+        assert_eq!(
+            store.get("covers/1/a.png").await.unwrap(),
+            Some(Bytes::from_static(b"png"))
+        );
+        assert_eq!(
+            store.public_url("covers/1/a.png"),
+            "http://localhost/media/covers/1/a.png"
+        );
+
+        store.delete("covers/1/a.png").await.unwrap();
+        assert_eq!(store.get("covers/1/a.png").await.unwrap(), None);
+        assert!(store.get("../etc/passwd").await.is_err());
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    /// 需要本地 MinIO：
+    /// `docker run -p 9000:9000 -e MINIO_ROOT_USER=minioadmin -e MINIO_ROOT_PASSWORD=minioadmin minio/minio server /data`
+    /// 并预先创建 bucket `blog-media`
+    #[tokio::test]
+    #[ignore]
+    async fn s3_store_round_trip_against_minio() {
+        let store = S3BlobStore::new(&S3Storage {
+            endpoint: Some("http://127.0.0.1:9000".into()),
+            bucket: "blog-media".into(),
+            region: "us-east-1".into(),
+            access_key: "minioadmin".into(),
+            secret_key: "minioadmin".into(),
+            public_base_url: "http://127.0.0.1:9000/blog-media".into(),
+        })
+        .unwrap();
+
+        store
+            .put("test/hello.txt", Bytes::from_static(b"hello"), "text/plain")
+            .await
+            .unwrap();
+        assert_eq!(
+            store.get("test/hello.txt").await.unwrap(),
+            Some(Bytes::from_static(b"hello"))
+        );
+
+        store.delete("test/hello.txt").await.unwrap();
+        assert_eq!(store.get("test/hello.txt").await.unwrap(), None);
+    }
+}
@@ -0,0 +1,69 @@
+use std::path::{Component, Path, PathBuf};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use common_core::{AppError, AppResult};
+
+use crate::{BlobStore, application::LocalStorage, join_url};
+
+/// 本地文件系统实现
+pub struct LocalBlobStore {
+    root: PathBuf,
+    public_base_url: String,
+}
+
+impl LocalBlobStore {
+    pub fn new(config: &LocalStorage) -> Self {
+        Self {
+            root: PathBuf::from(&config.root),
+            public_base_url: config.public_base_url.clone(),
+        }
+    }
+
+    /// 解析 key 对应的文件路径，拒绝绝对路径和 `..` 以防目录穿越
+    fn resolve(&self, key: &str) -> AppResult<PathBuf> {
+        let relative = Path::new(key);
+        let is_safe = !key.is_empty()
+            && relative
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)));
+        if !is_safe {
+            return Err(AppError::bad_request(format!("Invalid object key: {key}")));
+        }
+        Ok(self.root.join(relative))
+    }
+}
+
+#[async_trait]
+impl BlobStore for LocalBlobStore {
+    async fn put(&self, key: &str, data: Bytes, _content_type: &str) -> AppResult<()> {
+        let path = self.resolve(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&path, &data).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> AppResult<Option<Bytes>> {
+        let path = self.resolve(key)?;
+        match tokio::fs::read(&path).await {
+            Ok(data) => Ok(Some(Bytes::from(data))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> AppResult<()> {
+        let path = self.resolve(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn public_url(&self, key: &str) -> String {
+        join_url(&self.public_base_url, key)
+    }
+}
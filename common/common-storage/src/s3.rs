@@ -0,0 +1,95 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use common_core::{AppError, AppResult};
+use object_store::{
+    Attribute, Attributes, ObjectStore, PutOptions, PutPayload,
+    aws::{AmazonS3, AmazonS3Builder},
+    path::Path,
+};
+
+use crate::{BlobStore, application::S3Storage, join_url};
+
+/// S3 兼容实现（AWS S3 / MinIO）
+pub struct S3BlobStore {
+    store: AmazonS3,
+    public_base_url: String,
+}
+
+impl S3BlobStore {
+    pub fn new(config: &S3Storage) -> AppResult<Self> {
+        let mut builder = AmazonS3Builder::new()
+            .with_bucket_name(&config.bucket)
+            .with_region(&config.region)
+            .with_access_key_id(&config.access_key)
+            .with_secret_access_key(&config.secret_key);
+
+        // MinIO 等自建服务：path-style 访问，允许 http
+        if let Some(endpoint) = &config.endpoint {
+            builder = builder
+                .with_endpoint(endpoint)
+                .with_allow_http(endpoint.starts_with("http://"))
+                .with_virtual_hosted_style_request(false);
+        }
+
+        let store = builder.build().map_err(|e| {
+            AppError::internal(format!(
+                "create S3 client failed (bucket={}): {e}",
+                config.bucket
+            ))
+        })?;
+
+        Ok(Self {
+            store,
+            public_base_url: config.public_base_url.clone(),
+        })
+    }
+}
+
+fn object_path(key: &str) -> AppResult<Path> {
+    Path::parse(key).map_err(|e| AppError::bad_request(format!("Invalid object key: {e}")))
+}
+
+fn storage_error(op: &str, key: &str, e: object_store::Error) -> AppError {
+    AppError::internal(format!("S3 {op} failed (key={key}): {e}"))
+}
+
+#[async_trait]
+impl BlobStore for S3BlobStore {
+    async fn put(&self, key: &str, data: Bytes, content_type: &str) -> AppResult<()> {
+        let mut attributes = Attributes::new();
+        attributes.insert(Attribute::ContentType, content_type.to_string().into());
+
+        self.store
+            .put_opts(
+                &object_path(key)?,
+                PutPayload::from_bytes(data),
+                PutOptions::from(attributes),
+            )
+            .await
+            .map_err(|e| storage_error("PUT", key, e))?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> AppResult<Option<Bytes>> {
+        match self.store.get(&object_path(key)?).await {
+            Ok(result) => result
+                .bytes()
+                .await
+                .map(Some)
+                .map_err(|e| storage_error("GET", key, e)),
+            Err(object_store::Error::NotFound { .. }) => Ok(None),
+            Err(e) => Err(storage_error("GET", key, e)),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> AppResult<()> {
+        match self.store.delete(&object_path(key)?).await {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(e) => Err(storage_error("DELETE", key, e)),
+        }
+    }
+
+    fn public_url(&self, key: &str) -> String {
+        join_url(&self.public_base_url, key)
+    }
+}
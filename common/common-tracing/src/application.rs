@@ -3,4 +3,82 @@ use serde::Deserialize;
 #[derive(Debug, Clone, Deserialize)]
 pub struct Logs {
     pub path: String,
+    /// 输出格式：compact / pretty / json
+    #[serde(default)]
+    pub format: LogFormat,
+    /// 级别过滤（EnvFilter 语法），如 `info,sqlx=warn,user_service=debug`，运行时可通过管理接口修改
+    #[serde(default = "default_level")]
+    pub level: String,
+    /// 文件切分方式：minutely / hourly / daily / never / size
+    #[serde(default)]
+    pub rotation: Rotation,
+    /// 按大小切分时单个文件的上限（MB）
+    #[serde(default = "default_max_size_mb")]
+    pub max_size_mb: u64,
+    /// 保留的历史文件个数，超出后删除最旧的，0 表示不清理
+    #[serde(default = "default_max_files")]
+    pub max_files: usize,
+    /// ERROR 日志额外写入的文件（切分和保留规则同上），不配置则不单独输出
+    #[serde(default)]
+    pub error_path: Option<String>,
+    /// 日志级别管理接口的访问令牌，为空时不开放该接口
+    #[serde(default)]
+    pub admin_token: Option<String>,
+    /// 链路追踪导出配置，不配置时仍生成 trace id 并向下游传播，只是不导出 span
+    #[serde(default)]
+    pub otlp: Option<Otlp>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Compact,
+    Pretty,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Rotation {
+    Minutely,
+    Hourly,
+    #[default]
+    Daily,
+    Never,
+    /// 按 `max_size_mb` 切分
+    Size,
+}
+
+/// OTLP（gRPC 协议）导出配置
+#[derive(Debug, Clone, Deserialize)]
+pub struct Otlp {
+    /// Collector 地址，如 `http://127.0.0.1:4317`
+    pub endpoint: String,
+    /// 根 span 的采样率（0.0 ~ 1.0），下游服务沿用上游的采样决定
+    #[serde(default = "default_sample_ratio")]
+    pub sample_ratio: f64,
+    /// 单次导出超时（秒）
+    #[serde(default = "default_timeout_seconds")]
+    pub timeout_seconds: u64,
+}
+
+fn default_level() -> String {
+    "info".to_string()
+}
+
+fn default_max_size_mb() -> u64 {
+    100
+}
+
+fn default_max_files() -> usize {
+    7
+}
+
+fn default_sample_ratio() -> f64 {
+    1.0
+}
+
+fn default_timeout_seconds() -> u64 {
+    10
 }
@@ -0,0 +1,61 @@
+use std::fmt;
+use tracing::{Event, Subscriber};
+
+use crate::application::LogFormat;
+use tracing_opentelemetry::OtelData;
+use tracing_subscriber::{
+    fmt::{FmtContext, FormatEvent, FormatFields, format::Writer},
+    registry::LookupSpan,
+};
+
+/// 在每条日志中追加 trace id，用于串起同一请求在网关和各服务中的日志
+///
+/// compact 追加在行尾（`trace_id=...`），pretty 另起一行，json 作为 `trace_id` 字段；
+/// 不在任何 span 内的日志（启动、后台任务等）保持原样
+pub struct TraceIdFormat<F> {
+    inner: F,
+    format: LogFormat,
+}
+
+impl<F> TraceIdFormat<F> {
+    /// `format` 需与 `inner` 的输出格式一致
+    pub fn new(inner: F, format: LogFormat) -> Self {
+        Self { inner, format }
+    }
+}
+
+impl<S, N, F> FormatEvent<S, N> for TraceIdFormat<F>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+    F: FormatEvent<S, N>,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let trace_id = ctx.event_scope().and_then(|scope| {
+            scope
+                .into_iter()
+                .find_map(|span| span.extensions().get::<OtelData>()?.trace_id())
+        });
+        let Some(trace_id) = trace_id else {
+            return self.inner.format_event(ctx, writer, event);
+        };
+
+        let mut line = String::new();
+        self.inner
+            .format_event(ctx, Writer::new(&mut line), event)?;
+        let line = line.trim_end_matches('\n');
+        match self.format {
+            LogFormat::Compact => writeln!(writer, "{} trace_id={}", line, trace_id),
+            LogFormat::Pretty => writeln!(writer, "{}\n    trace_id: {}\n", line, trace_id),
+            LogFormat::Json => match line.strip_suffix('}') {
+                Some(object) => writeln!(writer, "{},\"trace_id\":\"{}\"}}", object, trace_id),
+                None => writeln!(writer, "{}", line),
+            },
+        }
+    }
+}
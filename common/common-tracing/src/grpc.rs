@@ -0,0 +1,74 @@
+use axum::http::{Request, Response};
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+use tracing::{Instrument, Span, field, instrument::Instrumented};
+
+use crate::propagation::{inject_context, set_parent_from_headers};
+
+/// gRPC 服务端 span，用于 `Server::builder().trace_fn(grpc_server_span)`
+///
+/// 从请求 metadata 中接续调用方的 `traceparent`
+pub fn grpc_server_span(request: &Request<()>) -> Span {
+    // 路径即 `/包名.服务名/方法名`
+    let method = request.uri().path().trim_start_matches('/');
+    let span = tracing::info_span!(
+        "grpc",
+        otel.name = %method,
+        otel.kind = "server",
+        rpc.system = "grpc",
+        // 由 common-web 的 gRPC 请求 ID 层填充
+        request_id = field::Empty,
+    );
+    set_parent_from_headers(&span, request.headers());
+    span
+}
+
+/// gRPC 客户端追踪层：每次调用创建 client span，并把 trace 上下文写入请求 metadata
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GrpcTraceLayer;
+
+impl<S> Layer<S> for GrpcTraceLayer {
+    type Service = GrpcTrace<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        GrpcTrace { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GrpcTrace<S> {
+    inner: S,
+}
+
+impl<S> GrpcTrace<S> {
+    /// 包裹客户端通道：`UserServiceClient::new(GrpcTrace::client(channel))`
+    pub fn client(inner: S) -> Self {
+        GrpcTraceLayer.layer(inner)
+    }
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for GrpcTrace<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Instrumented<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<ReqBody>) -> Self::Future {
+        // 在调用方的 span 下创建，调用方没有 span 时作为新 trace 的根
+        let method = request.uri().path().trim_start_matches('/').to_string();
+        let span = tracing::info_span!(
+            "grpc client",
+            otel.name = %method,
+            otel.kind = "client",
+            rpc.system = "grpc",
+        );
+        inject_context(&span, request.headers_mut());
+        self.inner.call(request).instrument(span)
+    }
+}
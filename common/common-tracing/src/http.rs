@@ -0,0 +1,40 @@
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+use tracing::{Instrument, field};
+
+use crate::propagation::set_parent_from_headers;
+
+/// HTTP 服务端 span：接续请求头中的 `traceparent`，请求处理期间的日志都带上同一个 trace id
+///
+/// 挂在最外层（`Router::layer` 的最后一个），让其余中间件的日志也落在该 span 内
+pub async fn http_trace(request: Request, next: Next) -> Response {
+    let method = request.method().clone();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+
+    let span = tracing::info_span!(
+        "http",
+        otel.name = %format!("{} {}", method, route),
+        otel.kind = "server",
+        http.response.status_code = field::Empty,
+        otel.status_code = field::Empty,
+        // 由 common-web 的请求 ID 中间件填充
+        request_id = field::Empty,
+    );
+    set_parent_from_headers(&span, request.headers());
+
+    let response = next.run(request).instrument(span.clone()).await;
+
+    let status = response.status();
+    span.record("http.response.status_code", status.as_u16());
+    if status.is_server_error() {
+        span.record("otel.status_code", "ERROR");
+    }
+    response
+}
@@ -0,0 +1,112 @@
+//! 运行时调整日志级别
+//!
+//! `GET /admin/log-level` 查看当前过滤规则，`PUT /admin/log-level` 以 `{"level": "debug,sqlx=warn"}` 修改，
+//! 需携带 `Authorization: Bearer <logs.admin_token>`。修改只在当前进程生效，重启后恢复配置文件中的级别。
+
+use axum::{
+    Json, Router,
+    extract::{Request, State},
+    http::{StatusCode, header},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, OnceLock};
+use tracing_subscriber::{EnvFilter, Registry, reload};
+
+pub const LOG_LEVEL_PATH: &str = "/admin/log-level";
+
+static LOG_FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+pub(crate) fn install(handle: reload::Handle<EnvFilter, Registry>) {
+    let _ = LOG_FILTER.set(handle);
+}
+
+/// 当前生效的过滤规则，日志未初始化时返回 `None`
+pub fn log_level() -> Option<String> {
+    LOG_FILTER
+        .get()?
+        .with_current(|filter| filter.to_string())
+        .ok()
+}
+
+/// 替换过滤规则（EnvFilter 语法），返回生效后的规则
+pub fn set_log_level(directives: &str) -> Result<String, String> {
+    let handle = LOG_FILTER
+        .get()
+        .ok_or_else(|| "Logging is not initialized".to_string())?;
+    let filter = EnvFilter::try_new(directives)
+        .map_err(|e| format!("Invalid log level `{}`: {}", directives, e))?;
+    let level = filter.to_string();
+    handle
+        .reload(filter)
+        .map_err(|e| format!("Failed to reload log level: {}", e))?;
+    Ok(level)
+}
+
+#[derive(Debug, Deserialize)]
+struct LogLevelRequest {
+    level: String,
+}
+
+#[derive(Debug, Serialize)]
+struct LogLevelResponse {
+    level: String,
+}
+
+/// 日志级别管理路由，未配置令牌时返回空路由（不开放接口）
+pub fn log_level_router<S>(admin_token: Option<&str>) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let Some(token) = admin_token.filter(|token| !token.is_empty()) else {
+        return Router::new();
+    };
+    Router::new()
+        .route(LOG_LEVEL_PATH, get(get_level).put(put_level))
+        .route_layer(middleware::from_fn_with_state(
+            Arc::<str>::from(token),
+            require_admin_token,
+        ))
+}
+
+async fn require_admin_token(
+    State(token): State<Arc<str>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .unwrap_or_default();
+    if !constant_time_eq(provided.as_bytes(), token.as_bytes()) {
+        tracing::warn!("Rejected log level admin request with invalid token");
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    next.run(request).await
+}
+
+async fn get_level() -> Response {
+    match log_level() {
+        Some(level) => Json(LogLevelResponse { level }).into_response(),
+        None => StatusCode::SERVICE_UNAVAILABLE.into_response(),
+    }
+}
+
+async fn put_level(Json(body): Json<LogLevelRequest>) -> Response {
+    match set_log_level(&body.level) {
+        Ok(level) => {
+            tracing::warn!("Log level changed to `{}`", level);
+            Json(LogLevelResponse { level }).into_response()
+        }
+        Err(e) => (StatusCode::BAD_REQUEST, e).into_response(),
+    }
+}
+
+/// 比较耗时与内容无关，避免逐字节猜测令牌
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
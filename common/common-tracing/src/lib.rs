@@ -1,28 +1,132 @@
-use std::path::Path;
+//! 日志与链路追踪
+//!
+//! 日志中的 span 同时作为 OpenTelemetry span：服务端 span 接续请求头（或 gRPC metadata）中的
+//! `traceparent`，调用下游时再把当前上下文写回请求头，整条调用链共享同一个 trace id。
+
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{
+    Resource,
+    trace::{Sampler, SdkTracerProvider},
+};
+use std::time::Duration;
+use tracing::Subscriber;
 use tracing_appender::non_blocking::WorkerGuard;
-use tracing_subscriber::fmt::writer::MakeWriterExt;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::{
+    EnvFilter, Layer, Registry,
+    filter::LevelFilter,
+    fmt::{MakeWriter, writer::MakeWriterExt},
+    layer::{Layered, SubscriberExt},
+    registry::LookupSpan,
+    reload,
+    util::SubscriberInitExt,
+};
+
+use application::LogFormat;
 
-pub struct TracingService;
 pub mod application;
+mod format;
+pub mod grpc;
+pub mod http;
+pub mod level;
+pub mod propagation;
+mod rolling;
+
+pub use format::TraceIdFormat;
+pub use grpc::{GrpcTrace, GrpcTraceLayer, grpc_server_span};
+pub use http::http_trace;
+pub use level::{log_level, log_level_router, set_log_level};
+pub use propagation::{extract_context, inject_context, set_parent_from_headers};
+
+/// 级别过滤之后的订阅者，输出层都挂在它上面
+type Filtered = Layered<reload::Layer<EnvFilter, Registry>, Registry>;
+type BoxedLayer = Box<dyn Layer<Filtered> + Send + Sync>;
+
+/// 级别配置无效时使用的默认级别
+const DEFAULT_LEVEL: &str = "info";
+
+pub struct TracingService;
+
+/// 日志与 span 导出的守卫，需持有到进程退出：drop 时导出剩余 span 并刷新日志文件
+pub struct TracingGuard {
+    _workers: Vec<WorkerGuard>,
+    provider: SdkTracerProvider,
+}
+
+impl Drop for TracingGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            tracing::warn!("Failed to flush spans: {}", e);
+        }
+    }
+}
 
 impl TracingService {
-    pub fn init(log_config: &application::Logs) -> WorkerGuard {
-        let path = Path::new(&log_config.path);
-        // 1. 获取父目录 (Default 为当前目录 ".")
-        let parent = path.parent().and_then(|p| p.to_str()).unwrap_or(".");
-        // 2. 获取文件名前缀 (去除扩展名)
-        let file_stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("app");
-        // 3. 初始化 file_appender
-        let file_appender = tracing_appender::rolling::daily(parent, file_stem);
-        let (non_blocking, guard) = tracing_appender::non_blocking(file_appender);
-
-        let stdout = std::io::stdout.and(non_blocking);
-        tracing_subscriber::fmt()
-            .with_target(false)
-            .with_writer(stdout)
-            .compact()
+    /// 初始化日志输出（控制台 + 文件，可选单独的 ERROR 文件）与链路追踪
+    ///
+    /// 日志文件无法创建时直接 panic，此时服务还未开始处理请求
+    pub fn init(service_name: &str, log_config: &application::Logs) -> TracingGuard {
+        let mut warnings = Vec::new();
+
+        // 1. 级别过滤（可在运行时替换）
+        let filter = EnvFilter::try_new(&log_config.level).unwrap_or_else(|e| {
+            warnings.push(format!(
+                "Invalid log level `{}`, falling back to `{}`: {}",
+                log_config.level, DEFAULT_LEVEL, e
+            ));
+            EnvFilter::new(DEFAULT_LEVEL)
+        });
+        let (filter, handle) = reload::Layer::new(filter);
+        level::install(handle);
+
+        // 2. 控制台 + 日志文件
+        let mut workers = Vec::new();
+        let file = rolling::file_writer(&log_config.path, log_config)
+            .unwrap_or_else(|e| panic!("Failed to open log file {}: {}", log_config.path, e));
+        let (file, worker) = tracing_appender::non_blocking(file);
+        workers.push(worker);
+
+        // 3. 链路追踪
+        let (provider, exporter_error) =
+            Self::tracer_provider(service_name, log_config.otlp.as_ref());
+        warnings.extend(exporter_error.map(|e| {
+            format!(
+                "Failed to create OTLP exporter, spans will not be exported: {}",
+                e
+            )
+        }));
+
+        let mut layers: Vec<BoxedLayer> = vec![
+            otel_layer(&provider).boxed(),
+            fmt_layer(log_config.format, std::io::stdout.and(file)),
+        ];
+
+        // 4. ERROR 日志单独成文件，便于告警和排查
+        if let Some(error_path) = &log_config.error_path {
+            let file = rolling::file_writer(error_path, log_config)
+                .unwrap_or_else(|e| panic!("Failed to open error log file {}: {}", error_path, e));
+            let (file, worker) = tracing_appender::non_blocking(file);
+            workers.push(worker);
+            layers.push(
+                fmt_layer(log_config.format, file)
+                    .with_filter(LevelFilter::ERROR)
+                    .boxed(),
+            );
+        }
+
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(layers)
             .init();
 
+        for warning in warnings {
+            tracing::error!("{}", warning);
+        }
+        if let Some(otlp) = &log_config.otlp {
+            tracing::info!("Exporting spans to {}", otlp.endpoint);
+        }
+
         // 设置 panic hook，将 panic 信息记录到 user-service 日志中
         let prev_hook = std::panic::take_hook();
         std::panic::set_hook(Box::new(move |panic_info| {
@@ -48,6 +152,176 @@ impl TracingService {
             prev_hook(panic_info);
         }));
 
-        guard
+        TracingGuard {
+            _workers: workers,
+            provider,
+        }
+    }
+
+    /// 始终创建 provider 以生成和传播 trace id；配置了 OTLP 时才挂载批量导出器
+    fn tracer_provider(
+        service_name: &str,
+        otlp: Option<&application::Otlp>,
+    ) -> (SdkTracerProvider, Option<String>) {
+        let builder = SdkTracerProvider::builder().with_resource(
+            Resource::builder()
+                .with_service_name(service_name.to_string())
+                .build(),
+        );
+        let Some(otlp) = otlp else {
+            return (builder.build(), None);
+        };
+
+        let exporter = SpanExporter::builder()
+            .with_tonic()
+            .with_endpoint(otlp.endpoint.clone())
+            .with_timeout(Duration::from_secs(otlp.timeout_seconds))
+            .build();
+        match exporter {
+            Ok(exporter) => {
+                let sampler =
+                    Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(otlp.sample_ratio)));
+                let provider = builder
+                    .with_sampler(sampler)
+                    .with_batch_exporter(exporter)
+                    .build();
+                (provider, None)
+            }
+            Err(e) => (builder.build(), Some(e.to_string())),
+        }
+    }
+}
+
+/// 按配置格式输出日志的层（不使用终端颜色，控制台与文件内容一致）
+fn fmt_layer<W>(format: LogFormat, writer: W) -> BoxedLayer
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer()
+        .with_ansi(false)
+        .with_target(false)
+        .with_writer(writer);
+    match format {
+        LogFormat::Compact => layer
+            .compact()
+            .map_event_format(|inner| TraceIdFormat::new(inner, format))
+            .boxed(),
+        LogFormat::Pretty => layer
+            .pretty()
+            .map_event_format(|inner| TraceIdFormat::new(inner, format))
+            .boxed(),
+        LogFormat::Json => layer
+            .json()
+            .with_current_span(true)
+            .with_span_list(false)
+            .map_event_format(|inner| TraceIdFormat::new(inner, format))
+            .boxed(),
+    }
+}
+
+/// 把 tracing span 转换为 OpenTelemetry span 的层
+fn otel_layer<S>(
+    provider: &SdkTracerProvider,
+) -> OpenTelemetryLayer<S, opentelemetry_sdk::trace::SdkTracer>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    tracing_opentelemetry::layer().with_tracer(provider.tracer("common-tracing"))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{HeaderMap, HeaderValue};
+    use opentelemetry::trace::SpanId;
+    use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider};
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::{LogFormat, TraceIdFormat, inject_context, otel_layer, set_parent_from_headers};
+
+    const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+    const PARENT_SPAN_ID: &str = "00f067aa0ba902b7";
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn continues_incoming_trace_and_tags_log_lines() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let logs = Buffer::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::registry()
+            .with(otel_layer(&provider))
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .with_ansi(false)
+                    .with_writer(move || writer.clone())
+                    .event_format(TraceIdFormat::new(
+                        tracing_subscriber::fmt::format().compact(),
+                        LogFormat::Compact,
+                    )),
+            );
+
+        let mut incoming = HeaderMap::new();
+        incoming.insert(
+            "traceparent",
+            HeaderValue::from_str(&format!("00-{}-{}-01", TRACE_ID, PARENT_SPAN_ID)).unwrap(),
+        );
+        let mut outgoing = HeaderMap::new();
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("outside any request");
+            let server = tracing::info_span!("server");
+            set_parent_from_headers(&server, &incoming);
+            server.in_scope(|| {
+                tracing::info!("handling request");
+                let client = tracing::info_span!("client");
+                inject_context(&client, &mut outgoing);
+            });
+        });
+        provider.force_flush().unwrap();
+
+        // 下游收到同一个 trace id，父节点是网关的 client span 而不是上游的 span
+        let traceparent = outgoing["traceparent"].to_str().unwrap();
+        assert!(
+            traceparent.starts_with(&format!("00-{}-", TRACE_ID)),
+            "{}",
+            traceparent
+        );
+        assert!(!traceparent.contains(PARENT_SPAN_ID), "{}", traceparent);
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let server = spans.iter().find(|span| span.name == "server").unwrap();
+        assert_eq!(server.span_context.trace_id().to_string(), TRACE_ID);
+        assert_eq!(
+            server.parent_span_id,
+            SpanId::from_hex(PARENT_SPAN_ID).unwrap()
+        );
+
+        let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = logs.lines().collect();
+        assert!(!lines[0].contains("trace_id="), "{}", lines[0]);
+        assert!(
+            lines[1].ends_with(&format!("trace_id={}", TRACE_ID)),
+            "{}",
+            lines[1]
+        );
     }
 }
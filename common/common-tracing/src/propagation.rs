@@ -0,0 +1,57 @@
+//! W3C trace-context（`traceparent` / `tracestate`）在请求头中的传播
+//!
+//! gRPC 的 metadata 就是 HTTP/2 请求头，HTTP 与 gRPC 共用同一套读写逻辑
+
+use axum::http::{HeaderMap, HeaderName, HeaderValue};
+use opentelemetry::{
+    Context,
+    propagation::{Extractor, Injector, TextMapPropagator},
+    trace::TraceContextExt,
+};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(HeaderName::as_str).collect()
+    }
+}
+
+struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+impl Injector for HeaderInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(key.as_bytes()),
+            HeaderValue::from_str(&value),
+        ) {
+            self.0.insert(name, value);
+        }
+    }
+}
+
+/// 从请求头解析上游传入的 trace 上下文，没有或格式错误时返回空上下文（开启新的 trace）
+pub fn extract_context(headers: &HeaderMap) -> Context {
+    TraceContextPropagator::new().extract(&HeaderExtractor(headers))
+}
+
+/// 把 span 的 trace 上下文写入请求头（覆盖已有的 `traceparent`），下游据此把自己的 span 挂到该 span 之下
+pub fn inject_context(span: &Span, headers: &mut HeaderMap) {
+    TraceContextPropagator::new().inject_context(&span.context(), &mut HeaderInjector(headers));
+}
+
+/// 把上游传入的 trace 上下文设为 span 的父节点
+pub fn set_parent_from_headers(span: &Span, headers: &HeaderMap) {
+    let parent = extract_context(headers);
+    if parent.span().span_context().is_valid() {
+        // 只有在 span 已被关闭等极端情况下才会失败，此时忽略上游上下文即可
+        let _ = span.set_parent(parent);
+    }
+}
@@ -0,0 +1,201 @@
+//! 日志文件的切分与保留
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+use tracing_appender::rolling::{self, RollingFileAppender};
+
+use crate::application::{Logs, Rotation};
+
+/// 按配置创建日志文件写入器
+///
+/// 按时间切分时，同目录下以相同前缀开头的文件都会参与保留个数的清理，
+/// 因此 ERROR 日志应放在单独的目录（如 `logs/error/user-service.log`）
+///
+/// `logs/user-service.log` 按时间切分时写入 `logs/user-service.2026-01-01.log`，
+/// 按大小切分时写入 `logs/user-service.log`，写满后改名为 `logs/user-service.20260101-120000.log`
+pub(crate) fn file_writer(path: &str, config: &Logs) -> io::Result<Box<dyn Write + Send>> {
+    let path = Path::new(path);
+    // 1. 获取父目录 (Default 为当前目录 ".")
+    let dir = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    // 2. 获取文件名前缀 (去除扩展名)
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("app");
+    let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("log");
+
+    let rotation = match config.rotation {
+        Rotation::Size => {
+            let writer = SizeRollingWriter::open(
+                dir,
+                stem,
+                ext,
+                config.max_size_mb.max(1) * 1024 * 1024,
+                config.max_files,
+            )?;
+            return Ok(Box::new(writer));
+        }
+        Rotation::Minutely => rolling::Rotation::MINUTELY,
+        Rotation::Hourly => rolling::Rotation::HOURLY,
+        Rotation::Daily => rolling::Rotation::DAILY,
+        Rotation::Never => rolling::Rotation::NEVER,
+    };
+
+    // 目录不存在时清理历史文件会报错，先创建
+    fs::create_dir_all(dir)?;
+    let mut builder = RollingFileAppender::builder()
+        .rotation(rotation)
+        .filename_prefix(stem)
+        .filename_suffix(ext);
+    if config.max_files > 0 {
+        builder = builder.max_log_files(config.max_files);
+    }
+    builder
+        .build(dir)
+        .map(|appender| Box::new(appender) as Box<dyn Write + Send>)
+        .map_err(io::Error::other)
+}
+
+/// 按大小切分的文件写入器（由 `tracing_appender::non_blocking` 的后台线程独占使用）
+struct SizeRollingWriter {
+    dir: PathBuf,
+    stem: String,
+    ext: String,
+    /// 正在写入的文件
+    active: PathBuf,
+    file: File,
+    written: u64,
+    max_bytes: u64,
+    max_files: usize,
+}
+
+impl SizeRollingWriter {
+    fn open(
+        dir: &Path,
+        stem: &str,
+        ext: &str,
+        max_bytes: u64,
+        max_files: usize,
+    ) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let active = dir.join(format!("{}.{}", stem, ext));
+        let file = open_append(&active)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            stem: stem.to_string(),
+            ext: ext.to_string(),
+            active,
+            file,
+            written,
+            max_bytes,
+            max_files,
+        })
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        let mut archived = self
+            .dir
+            .join(format!("{}.{}.{}", self.stem, timestamp, self.ext));
+        // 同一秒内多次切分
+        let mut seq = 1;
+        while archived.exists() {
+            archived = self
+                .dir
+                .join(format!("{}.{}-{}.{}", self.stem, timestamp, seq, self.ext));
+            seq += 1;
+        }
+        fs::rename(&self.active, archived)?;
+        self.file = open_append(&self.active)?;
+        self.written = 0;
+        self.prune();
+        Ok(())
+    }
+
+    /// 删除超出保留个数的历史文件（文件名中的时间戳按字典序即时间顺序）
+    fn prune(&self) {
+        if self.max_files == 0 {
+            return;
+        }
+        let prefix = format!("{}.", self.stem);
+        let suffix = format!(".{}", self.ext);
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        let mut archived: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                // 只匹配 `{stem}.{时间戳}.{ext}`，不误删同目录下的其他日志
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                name.strip_prefix(&prefix)
+                    .and_then(|rest| rest.strip_suffix(&suffix))
+                    .is_some_and(|stamp| {
+                        !stamp.is_empty() && stamp.bytes().all(|b| b.is_ascii_digit() || b == b'-')
+                    })
+            })
+            .collect();
+        archived.sort();
+        let excess = archived.len().saturating_sub(self.max_files);
+        for path in &archived[..excess] {
+            if let Err(e) = fs::remove_file(path) {
+                eprintln!("Failed to remove old log file {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+impl Write for SizeRollingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written > 0 && self.written + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(buf)?;
+        self.written += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io::Write};
+
+    use super::SizeRollingWriter;
+
+    #[test]
+    fn rotates_by_size_and_keeps_max_files() {
+        let dir =
+            std::env::temp_dir().join(format!("common-tracing-rolling-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut writer = SizeRollingWriter::open(&dir, "app", "log", 10, 2).unwrap();
+
+        for _ in 0..5 {
+            writer.write_all(b"0123456789").unwrap();
+        }
+        writer.flush().unwrap();
+
+        let mut names: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        // 当前文件 + 保留的 2 个历史文件
+        assert_eq!(names.len(), 3, "{:?}", names);
+        assert!(names.contains(&"app.log".to_string()));
+        assert_eq!(fs::read(dir.join("app.log")).unwrap(), b"0123456789");
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,8 +1,86 @@
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer, de::Error};
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Server {
     pub name: String,
     pub bind_addr: String,
     pub grpc_addr: Option<String>,
+    #[serde(default)]
+    pub shutdown: Shutdown,
+    #[serde(default)]
+    pub health: Health,
+}
+
+/// 就绪探针（`/health/ready`）
+#[derive(Debug, Clone, Deserialize)]
+pub struct Health {
+    /// 单项依赖检查的超时（毫秒），超时视为不可用
+    #[serde(default = "default_health_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Self {
+            timeout_ms: default_health_timeout_ms(),
+        }
+    }
+}
+
+fn default_health_timeout_ms() -> u64 {
+    2000
+}
+
+/// 优雅停机：收到 SIGTERM/SIGINT 后先标记为未就绪，等待摘除实例后再停止接收新连接
+#[derive(Debug, Clone, Deserialize)]
+pub struct Shutdown {
+    /// 标记未就绪后、停止接收新连接前的等待时间（秒），留给负载均衡和网关健康检查发现
+    #[serde(default = "default_readiness_delay_seconds")]
+    pub readiness_delay_seconds: u64,
+    /// 等待进行中的请求和后台任务结束的最长时间（秒），超时后强制退出
+    #[serde(default = "default_drain_timeout_seconds")]
+    pub drain_timeout_seconds: u64,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self {
+            readiness_delay_seconds: default_readiness_delay_seconds(),
+            drain_timeout_seconds: default_drain_timeout_seconds(),
+        }
+    }
+}
+
+fn default_readiness_delay_seconds() -> u64 {
+    5
+}
+
+fn default_drain_timeout_seconds() -> u64 {
+    30
+}
+
+fn default_max_skew_seconds() -> i64 {
+    60
+}
+
+/// 不能为空的密钥：空字符串会让签名形同虚设，启动时即拒绝
+pub fn non_empty_secret<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let secret = String::deserialize(deserializer)?;
+    if secret.trim().is_empty() {
+        return Err(D::Error::custom("secret must not be empty"));
+    }
+    Ok(secret)
+}
+
+/// 网关内部身份签名（与网关 `identity.secret` 保持一致）
+#[derive(Debug, Clone, Deserialize)]
+pub struct InternalIdentity {
+    #[serde(deserialize_with = "non_empty_secret")]
+    pub secret: String,
+    /// 允许的签名时间偏差（秒）
+    #[serde(default = "default_max_skew_seconds")]
+    pub max_skew_seconds: i64,
 }
@@ -0,0 +1,87 @@
+//! 请求截止时间：网关按路由超时写入 `X-Request-Timeout-Ms`，后端在剩余时间内调用下游，
+//! 网关已放弃等待的请求不再发起新的下游调用（gRPC 客户端据此设置 `grpc-timeout`）。
+
+use axum::{extract::Request, http::HeaderMap, middleware::Next, response::Response};
+use std::{future::Future, time::Duration};
+use tokio::time::Instant;
+
+pub const REQUEST_TIMEOUT_HEADER: &str = "x-request-timeout-ms";
+
+tokio::task_local! {
+    static DEADLINE: Instant;
+}
+
+/// 当前请求的剩余时间：没有截止时间时为 `None`，已超时为 `Some(Duration::ZERO)`
+pub fn remaining() -> Option<Duration> {
+    DEADLINE
+        .try_with(|deadline| deadline.saturating_duration_since(Instant::now()))
+        .ok()
+}
+
+/// 在给定时限内执行 `future`，其中的下游调用受该时限约束（已有更早的截止时间时沿用）
+pub async fn scope<F: Future>(timeout: Duration, future: F) -> F::Output {
+    let now = Instant::now();
+    let Some(mut deadline) = now.checked_add(timeout) else {
+        return future.await;
+    };
+    if let Some(remaining) = remaining() {
+        deadline = deadline.min(now + remaining);
+    }
+    DEADLINE.scope(deadline, future).await
+}
+
+/// 请求头中的超时（毫秒），缺失或无效时忽略
+fn parse(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(REQUEST_TIMEOUT_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(Duration::from_millis)
+}
+
+/// 截止时间中间件：请求头带有超时时，处理期间可通过 [`remaining`] 获取剩余时间
+pub async fn deadline(request: Request, next: Next) -> Response {
+    match parse(request.headers()) {
+        Some(timeout) => scope(timeout, next.run(request)).await,
+        None => next.run(request).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{HeaderMap, HeaderValue};
+    use std::time::Duration;
+
+    use super::{REQUEST_TIMEOUT_HEADER, parse, remaining, scope};
+
+    #[test]
+    fn parses_timeout_header() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(parse(&headers), None);
+
+        headers.insert(REQUEST_TIMEOUT_HEADER, HeaderValue::from_static("1500"));
+        assert_eq!(parse(&headers), Some(Duration::from_millis(1500)));
+
+        headers.insert(REQUEST_TIMEOUT_HEADER, HeaderValue::from_static("-1"));
+        assert_eq!(parse(&headers), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn nested_scopes_keep_the_earlier_deadline() {
+        assert_eq!(remaining(), None);
+
+        scope(Duration::from_secs(2), async {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            assert_eq!(remaining(), Some(Duration::from_millis(1500)));
+
+            scope(Duration::from_secs(10), async {
+                assert_eq!(remaining(), Some(Duration::from_millis(1500)));
+            })
+            .await;
+
+            tokio::time::sleep(Duration::from_secs(2)).await;
+            assert_eq!(remaining(), Some(Duration::ZERO));
+        })
+        .await;
+    }
+}
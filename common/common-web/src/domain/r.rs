@@ -1,10 +1,15 @@
 use serde::Serialize;
 
+use crate::request_id::current_request_id;
+
 #[derive(Serialize)]
 pub struct R<T> {
     pub code: u16,
     pub message: String,
     pub data: Option<T>,
+    /// 错误响应附带请求 ID，便于按 ID 检索日志
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 impl<T> R<T> {
@@ -13,6 +18,7 @@ impl<T> R<T> {
             code: 0,
             message: "ok".into(),
             data: Some(data),
+            request_id: None,
         }
     }
 
@@ -21,6 +27,17 @@ impl<T> R<T> {
             code,
             message: message.into(),
             data: None,
+            request_id: current_request_id(),
+        }
+    }
+
+    /// 带附加数据的错误响应（如字段级校验错误）
+    pub fn error_with_data(message: impl Into<String>, code: u16, data: T) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: Some(data),
+            request_id: current_request_id(),
         }
     }
 }
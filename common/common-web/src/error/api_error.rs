@@ -0,0 +1,136 @@
+use axum::{
+    Json,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use common_core::AppError;
+
+use crate::domain::r::R;
+
+pub struct ApiError(pub AppError);
+
+impl From<AppError> for ApiError {
+    fn from(err: AppError) -> Self {
+        Self(err)
+    }
+}
+
+impl ApiError {
+    /// 错误对应的 HTTP 状态码
+    pub fn status(&self) -> StatusCode {
+        match &self.0 {
+            AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            AppError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::MethodNotAllowed(_) => StatusCode::METHOD_NOT_ALLOWED,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            AppError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            AppError::BadGateway(_) => StatusCode::BAD_GATEWAY,
+            AppError::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            AppError::Internal(_)
+            | AppError::Redis(_)
+            | AppError::Db(_)
+            | AppError::Io(_)
+            | AppError::Other(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+
+        // 内部错误记录完整信息，客户端只拿到通用提示
+        if self.0.is_internal() {
+            tracing::error!(error = ?self.0, "API Error occurred");
+        } else {
+            tracing::warn!(status = status.as_u16(), error = %self.0, "API request rejected");
+        }
+
+        let body = R::<()>::error(self.0.public_message(), self.0.code());
+        (status, Json(body)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use common_core::{AppError, error::code};
+
+    use super::ApiError;
+
+    #[test]
+    fn maps_client_errors_to_status_and_code() {
+        let cases = [
+            (
+                AppError::bad_request("x"),
+                StatusCode::BAD_REQUEST,
+                code::BAD_REQUEST,
+            ),
+            (
+                AppError::unauthorized("x"),
+                StatusCode::UNAUTHORIZED,
+                code::UNAUTHORIZED,
+            ),
+            (
+                AppError::forbidden("x"),
+                StatusCode::FORBIDDEN,
+                code::FORBIDDEN,
+            ),
+            (
+                AppError::not_found("x"),
+                StatusCode::NOT_FOUND,
+                code::NOT_FOUND,
+            ),
+            (
+                AppError::conflict("x"),
+                StatusCode::CONFLICT,
+                code::CONFLICT,
+            ),
+            (
+                AppError::rate_limited("x"),
+                StatusCode::TOO_MANY_REQUESTS,
+                code::RATE_LIMITED,
+            ),
+            (
+                AppError::method_not_allowed("x"),
+                StatusCode::METHOD_NOT_ALLOWED,
+                code::METHOD_NOT_ALLOWED,
+            ),
+            (
+                AppError::payload_too_large("x"),
+                StatusCode::PAYLOAD_TOO_LARGE,
+                code::PAYLOAD_TOO_LARGE,
+            ),
+            (
+                AppError::bad_gateway("x"),
+                StatusCode::BAD_GATEWAY,
+                code::BAD_GATEWAY,
+            ),
+            (
+                AppError::service_unavailable("x"),
+                StatusCode::SERVICE_UNAVAILABLE,
+                code::SERVICE_UNAVAILABLE,
+            ),
+        ];
+
+        for (err, status, biz_code) in cases {
+            assert_eq!(err.code(), biz_code);
+            assert_eq!(err.public_message(), "x");
+            assert_eq!(ApiError(err).status(), status);
+        }
+    }
+
+    #[test]
+    fn hides_internal_details() {
+        let err = AppError::db("duplicate key value violates unique constraint");
+
+        assert!(err.is_internal());
+        assert_eq!(err.code(), code::DB);
+        assert_eq!(err.public_message(), "Internal Server Error");
+        assert_eq!(ApiError(err).status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -1,2 +1,3 @@
-pub mod error;
-pub use error::ApiError;
+mod api_error;
+
+pub use api_error::ApiError;
@@ -0,0 +1,262 @@
+//! 存活与就绪探针
+//!
+//! - `GET /health/live`：进程能响应请求即返回 200，停机排空期间也不失败（避免被编排系统重启）
+//! - `GET /health/ready`：实例就绪且关键依赖都可用时返回 200，否则 503，响应体列出每项依赖的状态与耗时
+//!
+//! 依赖分为关键（数据库、缓存等，失败时实例未就绪）和可降级（下游服务等，失败时只报告 `DEGRADED`）。
+
+use axum::{
+    Json, Router,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use common_core::AppResult;
+use futures_util::future::{BoxFuture, join_all};
+use serde::Serialize;
+use std::{
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::{application::Health, runner::Lifecycle};
+
+pub const LIVENESS_PATH: &str = "/health/live";
+pub const READINESS_PATH: &str = "/health/ready";
+
+type CheckFn = Arc<dyn Fn() -> BoxFuture<'static, AppResult<()>> + Send + Sync>;
+
+#[derive(Clone)]
+struct Check {
+    name: String,
+    critical: bool,
+    run: CheckFn,
+}
+
+/// 就绪检查：登记依赖后通过 [`HealthChecks::router`] 挂载探针路由
+///
+/// ```ignore
+/// let health = HealthChecks::new(lifecycle, &config.server.health)
+///     .critical("postgres", move || common_db::ping(&pool))
+///     .optional("user-service", move || check_user_service(channel.clone()));
+/// ```
+#[derive(Clone)]
+pub struct HealthChecks {
+    lifecycle: Lifecycle,
+    timeout: Duration,
+    checks: Vec<Check>,
+}
+
+impl HealthChecks {
+    pub fn new(lifecycle: Lifecycle, config: &Health) -> Self {
+        Self {
+            lifecycle,
+            timeout: Duration::from_millis(config.timeout_ms),
+            checks: Vec::new(),
+        }
+    }
+
+    /// 关键依赖：检查失败或超时时实例未就绪
+    pub fn critical<F, Fut>(self, name: &str, check: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = AppResult<()>> + Send + 'static,
+    {
+        self.add(name, true, check)
+    }
+
+    /// 可降级的依赖：检查失败只把整体状态降为 `DEGRADED`，不影响就绪
+    pub fn optional<F, Fut>(self, name: &str, check: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = AppResult<()>> + Send + 'static,
+    {
+        self.add(name, false, check)
+    }
+
+    fn add<F, Fut>(mut self, name: &str, critical: bool, check: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = AppResult<()>> + Send + 'static,
+    {
+        self.checks.push(Check {
+            name: name.to_string(),
+            critical,
+            run: Arc::new(move || Box::pin(check())),
+        });
+        self
+    }
+
+    /// 停机开始（或启动尚未完成）时不再检查依赖，直接报告未就绪
+    pub async fn report(&self) -> HealthReport {
+        if !self.lifecycle.is_ready() {
+            return HealthReport {
+                status: Status::Down,
+                reason: Some("not ready"),
+                checks: Vec::new(),
+            };
+        }
+        self.run_checks().await
+    }
+
+    /// 并发执行所有检查，单项超过超时时间视为失败
+    ///
+    /// 失败原因（可能含地址、账号等内部信息）只记录日志，报告中只给出概括
+    async fn run_checks(&self) -> HealthReport {
+        let checks = join_all(self.checks.iter().map(|check| async move {
+            let started = Instant::now();
+            let result = match tokio::time::timeout(self.timeout, (check.run)()).await {
+                Ok(Ok(())) => Ok(()),
+                Ok(Err(e)) => {
+                    tracing::warn!("Health check {} failed: {}", check.name, e);
+                    Err("check failed")
+                }
+                Err(_) => {
+                    tracing::warn!(
+                        "Health check {} timed out after {}ms",
+                        check.name,
+                        self.timeout.as_millis()
+                    );
+                    Err("timed out")
+                }
+            };
+            let latency_ms = started.elapsed().as_millis() as u64;
+            CheckResult {
+                name: check.name.clone(),
+                status: if result.is_ok() {
+                    Status::Up
+                } else {
+                    Status::Down
+                },
+                critical: check.critical,
+                latency_ms,
+                error: result.err(),
+            }
+        }))
+        .await;
+
+        let failed = |critical: bool| {
+            checks
+                .iter()
+                .any(|c| c.critical == critical && c.status == Status::Down)
+        };
+        let status = if failed(true) {
+            Status::Down
+        } else if failed(false) {
+            Status::Degraded
+        } else {
+            Status::Up
+        };
+        HealthReport {
+            status,
+            reason: None,
+            checks,
+        }
+    }
+
+    /// 探针路由，可合并到任意状态的路由上
+    pub fn router<S>(self) -> Router<S>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        Router::new()
+            .route(LIVENESS_PATH, get(live))
+            .route(READINESS_PATH, get(ready))
+            .with_state(Arc::new(self))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Status {
+    Up,
+    Degraded,
+    Down,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HealthReport {
+    pub status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<&'static str>,
+    pub checks: Vec<CheckResult>,
+}
+
+impl HealthReport {
+    /// `DEGRADED` 仍视为就绪
+    pub fn is_ready(&self) -> bool {
+        self.status != Status::Down
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CheckResult {
+    pub name: String,
+    pub status: Status,
+    pub critical: bool,
+    pub latency_ms: u64,
+    /// 失败概括（`check failed` / `timed out`），详细原因见日志
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+struct Liveness {
+    status: Status,
+}
+
+async fn live() -> Response {
+    Json(Liveness { status: Status::Up }).into_response()
+}
+
+async fn ready(State(health): State<Arc<HealthChecks>>) -> Response {
+    let report = health.report().await;
+    let status_code = if report.is_ready() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status_code, Json(report)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use common_core::AppError;
+    use std::time::Duration;
+
+    use super::{HealthChecks, Status};
+    use crate::{application::Health, runner::ServiceRunner};
+
+    #[tokio::test]
+    async fn reports_each_dependency_and_applies_timeout() {
+        let lifecycle = ServiceRunner::new("test-service", &Default::default()).lifecycle();
+        let health = HealthChecks::new(lifecycle, &Health { timeout_ms: 50 })
+            .critical("postgres", || async { Ok(()) })
+            .optional("user-service", || async {
+                Err(AppError::internal("connection refused"))
+            });
+
+        let report = health.run_checks().await;
+        assert_eq!(report.status, Status::Degraded);
+        assert!(report.is_ready());
+        assert_eq!(report.checks[0].status, Status::Up);
+        // 错误细节不出现在报告中
+        assert_eq!(report.checks[1].error, Some("check failed"));
+
+        let health = health.critical("redis", || async {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            Ok(())
+        });
+        let report = health.run_checks().await;
+        assert_eq!(report.status, Status::Down);
+        assert_eq!(report.checks[2].error, Some("timed out"));
+
+        // 未进入运行状态（或已开始停机）时不检查依赖，直接报告未就绪
+        let report = health.report().await;
+        assert_eq!(report.status, Status::Down);
+        assert_eq!(report.reason, Some("not ready"));
+        assert!(report.checks.is_empty());
+    }
+}
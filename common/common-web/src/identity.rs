@@ -0,0 +1,58 @@
+use axum::{
+    extract::{Request, State},
+    http::HeaderMap,
+    middleware::Next,
+    response::Response,
+};
+use common_core::utils::identity_utils::{
+    IDENTITY_SIGNATURE_HEADER, IDENTITY_TIMESTAMP_HEADER, IdentityUtils, USER_ID_HEADER,
+};
+use std::sync::Arc;
+
+use crate::application::InternalIdentity;
+
+/// 校验网关签发的身份头，签名无效时移除 `x-user-id`，后续按未登录处理
+///
+/// 用法：`.layer(middleware::from_fn_with_state(Arc::new(identity), verify_identity))`
+pub async fn verify_identity(
+    State(identity): State<Arc<InternalIdentity>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    if request.headers().contains_key(USER_ID_HEADER)
+        && let Err(reason) = check(&identity, request.headers())
+    {
+        tracing::warn!(
+            "Rejected unsigned or invalid identity header: {} ({})",
+            request.uri().path(),
+            reason
+        );
+        let headers = request.headers_mut();
+        headers.remove(USER_ID_HEADER);
+        headers.remove(IDENTITY_TIMESTAMP_HEADER);
+        headers.remove(IDENTITY_SIGNATURE_HEADER);
+    }
+
+    next.run(request).await
+}
+
+fn check(identity: &InternalIdentity, headers: &HeaderMap) -> Result<(), &'static str> {
+    let get = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+    let user_id = get(USER_ID_HEADER)
+        .and_then(|v| v.parse::<i64>().ok())
+        .ok_or("malformed user id")?;
+    let timestamp = get(IDENTITY_TIMESTAMP_HEADER)
+        .and_then(|v| v.parse::<i64>().ok())
+        .ok_or("missing timestamp")?;
+    let signature = get(IDENTITY_SIGNATURE_HEADER).ok_or("missing signature")?;
+
+    IdentityUtils::verify(
+        &identity.secret,
+        user_id,
+        timestamp,
+        signature,
+        identity.max_skew_seconds,
+    )
+    .map_err(|_| "invalid or expired signature")
+}
@@ -1,3 +1,9 @@
 pub mod application;
+pub mod deadline;
 pub mod domain;
 pub mod error;
+pub mod health;
+pub mod identity;
+pub mod request_id;
+pub mod runner;
+pub mod validation;
@@ -0,0 +1,180 @@
+//! 请求 ID：网关为每个请求分配（或沿用客户端传入的）`X-Request-Id`，
+//! 经 HTTP 请求头和 gRPC metadata 传给下游，写入日志 span、错误响应体和响应头，
+//! 用户反馈问题时凭它即可检索整条调用链的日志。
+
+use axum::{
+    extract::Request,
+    http::{self, HeaderMap, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use futures_util::future::BoxFuture;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+use tracing::Span;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// 客户端传入的请求 ID 最大长度，超长或含非法字符时重新生成
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// span 中记录请求 ID 的字段，由 `common_tracing` 的服务端 span 预先声明
+const SPAN_FIELD: &str = "request_id";
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// 当前请求的 ID，仅在 [`request_id`] 中间件或 gRPC 服务端层处理的请求内有值
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// 沿用请求头中合法的请求 ID，否则生成新的
+fn resolve(headers: &HeaderMap) -> String {
+    headers
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| is_valid(id))
+        .map(str::to_string)
+        .unwrap_or_else(generate)
+}
+
+fn is_valid(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LEN
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b':'))
+}
+
+fn generate() -> String {
+    format!("{:032x}", rand::random::<u128>())
+}
+
+/// 请求 ID 中间件：写回请求头（网关据此转发给上游）、记录到当前 span，并在响应头中返回
+///
+/// 挂在 `common_tracing::http_trace` 之内，才能把 ID 记录到请求 span 上
+pub async fn request_id(mut request: Request, next: Next) -> Response {
+    let id = resolve(request.headers());
+    // 只含可见 ASCII 字符，转换不会失败
+    let value = HeaderValue::from_str(&id).expect("request id is a valid header value");
+    request
+        .headers_mut()
+        .insert(REQUEST_ID_HEADER, value.clone());
+    Span::current().record(SPAN_FIELD, id.as_str());
+
+    let mut response = REQUEST_ID.scope(id, next.run(request)).await;
+    response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    response
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Side {
+    Server,
+    Client,
+}
+
+/// gRPC 请求 ID 层：服务端挂在 `Server::builder().layer(..)`，客户端包裹 `Channel`
+///
+/// - 服务端：从 metadata 读取（没有则生成），记录到 span 并在处理期间可通过 [`current_request_id`] 获取
+/// - 客户端：把当前请求 ID 写入 metadata
+#[derive(Debug, Clone, Copy)]
+pub struct GrpcRequestIdLayer {
+    side: Side,
+}
+
+impl GrpcRequestIdLayer {
+    pub fn server() -> Self {
+        Self { side: Side::Server }
+    }
+
+    pub fn client() -> Self {
+        Self { side: Side::Client }
+    }
+}
+
+impl<S> Layer<S> for GrpcRequestIdLayer {
+    type Service = GrpcRequestId<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        GrpcRequestId {
+            inner,
+            side: self.side,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GrpcRequestId<S> {
+    inner: S,
+    side: Side,
+}
+
+impl<S> GrpcRequestId<S> {
+    /// 包裹客户端通道：`UserServiceClient::new(GrpcRequestId::client(channel))`
+    pub fn client(inner: S) -> Self {
+        GrpcRequestIdLayer::client().layer(inner)
+    }
+}
+
+impl<S, ReqBody, ResBody> Service<http::Request<ReqBody>> for GrpcRequestId<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: http::Request<ReqBody>) -> Self::Future {
+        match self.side {
+            Side::Client => {
+                if let Some(value) =
+                    current_request_id().and_then(|id| HeaderValue::from_str(&id).ok())
+                {
+                    request.headers_mut().insert(REQUEST_ID_HEADER, value);
+                }
+                Box::pin(self.inner.call(request))
+            }
+            Side::Server => {
+                let id = resolve(request.headers());
+                let future = self.inner.call(request);
+                Box::pin(async move {
+                    // tonic 的 trace_fn span 在轮询时才进入，所以在 future 内记录
+                    Span::current().record(SPAN_FIELD, id.as_str());
+                    REQUEST_ID.scope(id, future).await
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{HeaderMap, HeaderValue};
+
+    use super::{REQUEST_ID_HEADER, resolve};
+
+    #[test]
+    fn keeps_valid_ids_and_replaces_invalid_ones() {
+        let mut headers = HeaderMap::new();
+        let generated = resolve(&headers);
+        assert_eq!(generated.len(), 32);
+        assert_ne!(generated, resolve(&headers));
+
+        headers.insert(REQUEST_ID_HEADER, HeaderValue::from_static("req-42_a.b:c"));
+        assert_eq!(resolve(&headers), "req-42_a.b:c");
+
+        for invalid in ["", "has space", "<script>", &"x".repeat(129)] {
+            headers.insert(REQUEST_ID_HEADER, HeaderValue::from_str(invalid).unwrap());
+            let resolved = resolve(&headers);
+            assert_ne!(resolved, invalid);
+            assert_eq!(resolved.len(), 32);
+        }
+    }
+}
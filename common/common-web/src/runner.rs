@@ -0,0 +1,314 @@
+//! 服务运行与优雅停机
+//!
+//! 收到 SIGTERM/SIGINT（或任一服务器意外退出）后按顺序停机：
+//! 1. 标记为未就绪，`/health/ready` 返回 503，负载均衡和网关健康检查随之摘除实例
+//! 2. 等待 `readiness_delay_seconds`，期间仍正常处理请求
+//! 3. 通知各服务器停止接收新连接、后台任务退出循环，等待进行中的请求完成
+//! 4. 超过 `drain_timeout_seconds` 或再次收到信号时强制中止
+//!
+//! `run` 正常返回后 `main` 中持有的日志守卫随之 drop，剩余日志和 span 得以刷新。
+
+use common_core::AppError;
+use std::{
+    future::Future,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+use tokio::{
+    sync::{mpsc, watch},
+    task::{JoinError, JoinHandle, JoinSet},
+};
+
+use crate::application::Shutdown;
+
+/// 服务器任务的返回值：名称 + 运行结果
+type ServerExit = (String, Result<(), AppError>);
+
+/// 传给服务器和后台任务的生命周期句柄：查询就绪状态、等待停机通知
+#[derive(Debug, Clone)]
+pub struct Lifecycle {
+    ready: Arc<AtomicBool>,
+    shutdown: watch::Receiver<bool>,
+}
+
+impl Lifecycle {
+    /// 是否可以接收新请求（启动完成且未开始停机）
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Acquire)
+    }
+
+    /// 是否已通知停止接收新连接
+    pub fn is_shutting_down(&self) -> bool {
+        *self.shutdown.borrow()
+    }
+
+    /// 停机通知：传给 `with_graceful_shutdown` / `serve_with_shutdown`，或在后台任务的 `select!` 中等待
+    pub fn shutdown(&self) -> impl Future<Output = ()> + Send + 'static {
+        let mut shutdown = self.shutdown.clone();
+        async move {
+            // 发送端已释放也视为停机
+            let _ = shutdown.wait_for(|stopping| *stopping).await;
+        }
+    }
+}
+
+/// 统一管理一个进程内的服务器和后台任务
+///
+/// ```ignore
+/// let mut runner = ServiceRunner::new(&config.server.name, &config.server.shutdown);
+/// let lifecycle = runner.lifecycle();
+/// runner.spawn_server("HTTP", start_http_server(app_state.clone(), http_addr, lifecycle.clone()));
+/// runner.spawn_server("gRPC", start_grpc_server(app_state, grpc_addr, lifecycle));
+/// runner.run().await
+/// ```
+pub struct ServiceRunner {
+    name: String,
+    config: Shutdown,
+    ready: Arc<AtomicBool>,
+    shutdown: watch::Sender<bool>,
+    servers: JoinSet<ServerExit>,
+    background: Vec<(String, JoinHandle<()>)>,
+}
+
+impl ServiceRunner {
+    pub fn new(name: &str, config: &Shutdown) -> Self {
+        let (shutdown, _) = watch::channel(false);
+        Self {
+            name: name.to_string(),
+            config: config.clone(),
+            ready: Arc::new(AtomicBool::new(false)),
+            shutdown,
+            servers: JoinSet::new(),
+            background: Vec::new(),
+        }
+    }
+
+    pub fn lifecycle(&self) -> Lifecycle {
+        Lifecycle {
+            ready: self.ready.clone(),
+            shutdown: self.shutdown.subscribe(),
+        }
+    }
+
+    /// 启动服务器：收到 [`Lifecycle::shutdown`] 后应停止接收新连接，处理完进行中的请求再返回
+    pub fn spawn_server<F>(&mut self, name: &str, server: F)
+    where
+        F: Future<Output = Result<(), AppError>> + Send + 'static,
+    {
+        let name = name.to_string();
+        self.servers.spawn(async move { (name, server.await) });
+    }
+
+    /// 登记后台任务：收到 [`Lifecycle::shutdown`] 后应退出循环，停机时等待其结束
+    pub fn add_background(&mut self, name: &str, task: JoinHandle<()>) {
+        self.background.push((name.to_string(), task));
+    }
+
+    /// 运行到停机完成，任一服务器失败时返回其错误
+    pub async fn run(mut self) -> Result<(), AppError> {
+        let mut signals = listen_signals();
+        self.ready.store(true, Ordering::Release);
+        tracing::info!("{} is ready", self.name);
+
+        // 1. 等待停机信号，或任一服务器意外退出（如端口被占用），此时其余服务器一并停止
+        let mut result = Ok(());
+        tokio::select! {
+            signal = next_signal(&mut signals) => {
+                tracing::info!("Received {}, shutting down {}", signal, self.name);
+            }
+            Some(exit) = self.servers.join_next() => {
+                result = server_result(exit)
+                    .and(Err(AppError::internal("Server stopped before shutdown was requested")));
+            }
+        }
+
+        // 2. 先标记为未就绪，等负载均衡摘除实例后再停止接收新连接
+        self.ready.store(false, Ordering::Release);
+        let delay = Duration::from_secs(self.config.readiness_delay_seconds);
+        if result.is_ok() && !delay.is_zero() {
+            tracing::info!(
+                "Marked not ready, closing listeners in {}s",
+                self.config.readiness_delay_seconds
+            );
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                signal = next_signal(&mut signals) => {
+                    tracing::warn!("Received {} again, skipping readiness delay", signal);
+                }
+            }
+        }
+
+        // 3. 通知服务器和后台任务停止，等待进行中的请求完成
+        let _ = self.shutdown.send(true);
+        let drain_timeout = Duration::from_secs(self.config.drain_timeout_seconds);
+        let aborted = tokio::select! {
+            drained = tokio::time::timeout(
+                drain_timeout,
+                drain(&mut self.servers, &mut self.background),
+            ) => match drained {
+                Ok(drained) => {
+                    result = result.and(drained);
+                    None
+                }
+                Err(_) => Some(format!(
+                    "Drain timed out after {}s",
+                    self.config.drain_timeout_seconds
+                )),
+            },
+            signal = next_signal(&mut signals) => Some(format!("Received {} again", signal)),
+        };
+
+        // 4. 超时或再次收到信号时中止剩余的连接和任务
+        if let Some(reason) = aborted {
+            tracing::warn!("{}, aborting remaining connections and tasks", reason);
+            self.servers.abort_all();
+            for (_, task) in &self.background {
+                task.abort();
+            }
+        }
+
+        tracing::info!("{} stopped", self.name);
+        result
+    }
+}
+
+/// 等待所有服务器退出、后台任务结束，返回第一个服务器错误
+async fn drain(
+    servers: &mut JoinSet<ServerExit>,
+    background: &mut [(String, JoinHandle<()>)],
+) -> Result<(), AppError> {
+    let mut result = Ok(());
+    while let Some(exit) = servers.join_next().await {
+        result = result.and(server_result(exit));
+    }
+    for (name, task) in background.iter_mut() {
+        match task.await {
+            Ok(()) => tracing::info!("{} stopped", name),
+            Err(e) => tracing::error!("{} failed: {}", name, e),
+        }
+    }
+    result
+}
+
+fn server_result(exit: Result<ServerExit, JoinError>) -> Result<(), AppError> {
+    match exit {
+        Ok((name, Ok(()))) => {
+            tracing::info!("{} server stopped", name);
+            Ok(())
+        }
+        Ok((name, Err(e))) => {
+            tracing::error!("{} server failed: {}", name, e);
+            Err(e)
+        }
+        Err(e) => {
+            tracing::error!("Server task failed: {}", e);
+            Err(AppError::internal(format!("Server task failed: {}", e)))
+        }
+    }
+}
+
+/// 把停机信号转发到通道：首个信号开始停机，停机过程中再次收到则立即中止
+fn listen_signals() -> mpsc::UnboundedReceiver<&'static str> {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    tokio::spawn(forward_signals(sender));
+    receiver
+}
+
+/// 无法监听信号时永不返回，只能由服务器退出触发停机
+async fn next_signal(signals: &mut mpsc::UnboundedReceiver<&'static str>) -> &'static str {
+    match signals.recv().await {
+        Some(signal) => signal,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(unix)]
+async fn forward_signals(sender: mpsc::UnboundedSender<&'static str>) {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let (mut terminate, mut interrupt) = match (
+        signal(SignalKind::terminate()),
+        signal(SignalKind::interrupt()),
+    ) {
+        (Ok(terminate), Ok(interrupt)) => (terminate, interrupt),
+        (Err(e), _) | (_, Err(e)) => {
+            tracing::error!("Failed to listen for shutdown signals: {}", e);
+            return;
+        }
+    };
+    loop {
+        let signal = tokio::select! {
+            _ = terminate.recv() => "SIGTERM",
+            _ = interrupt.recv() => "SIGINT",
+        };
+        if sender.send(signal).is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(not(unix))]
+async fn forward_signals(sender: mpsc::UnboundedSender<&'static str>) {
+    loop {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for shutdown signals: {}", e);
+            return;
+        }
+        if sender.send("Ctrl-C").is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_core::AppError;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    };
+
+    use super::ServiceRunner;
+    use crate::application::Shutdown;
+
+    #[tokio::test]
+    async fn failed_server_stops_the_others() {
+        let mut runner = ServiceRunner::new("test-service", &Shutdown::default());
+        let lifecycle = runner.lifecycle();
+
+        let drained = Arc::new(AtomicBool::new(false));
+        runner.spawn_server("HTTP", {
+            let lifecycle = lifecycle.clone();
+            let drained = drained.clone();
+            async move {
+                lifecycle.shutdown().await;
+                drained.store(true, Ordering::Release);
+                Ok(())
+            }
+        });
+        runner.spawn_server("gRPC", async { Err(AppError::internal("address in use")) });
+
+        let stopped = Arc::new(AtomicBool::new(false));
+        runner.add_background(
+            "worker",
+            tokio::spawn({
+                let lifecycle = lifecycle.clone();
+                let stopped = stopped.clone();
+                async move {
+                    lifecycle.shutdown().await;
+                    stopped.store(true, Ordering::Release);
+                }
+            }),
+        );
+
+        let result = runner.run().await;
+        assert!(matches!(result, Err(AppError::Internal(ref e)) if e == "address in use"));
+        assert!(drained.load(Ordering::Acquire));
+        assert!(stopped.load(Ordering::Acquire));
+        assert!(!lifecycle.is_ready());
+        assert!(lifecycle.is_shutting_down());
+    }
+}
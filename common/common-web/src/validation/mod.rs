@@ -0,0 +1,182 @@
+use axum::{
+    Json,
+    extract::{FromRequest, FromRequestParts, Query, Request},
+    http::{StatusCode, request::Parts},
+    response::{IntoResponse, Response},
+};
+use common_core::error::code;
+use serde::{Serialize, de::DeserializeOwned};
+use validator::{Validate, ValidationErrors, ValidationErrorsKind};
+
+use crate::domain::r::R;
+
+/// 字段级校验错误
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+/// 校验失败时的响应
+///
+/// - 请求体 / 查询参数无法解析：400 + `BAD_REQUEST`
+/// - 解析成功但不满足校验规则：422 + `VALIDATION`，`data` 中带字段级错误
+#[derive(Debug)]
+pub enum ValidationRejection {
+    Malformed(String),
+    Invalid(Vec<FieldError>),
+}
+
+impl From<ValidationErrors> for ValidationRejection {
+    fn from(errors: ValidationErrors) -> Self {
+        let mut fields = Vec::new();
+        collect_field_errors("", &errors, &mut fields);
+        fields.sort_by(|a, b| a.field.cmp(&b.field));
+        Self::Invalid(fields)
+    }
+}
+
+impl IntoResponse for ValidationRejection {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Malformed(msg) => {
+                tracing::warn!(error = %msg, "Malformed request rejected");
+                (
+                    StatusCode::BAD_REQUEST,
+                    Json(R::<()>::error(msg, code::BAD_REQUEST)),
+                )
+                    .into_response()
+            }
+            Self::Invalid(fields) => {
+                tracing::warn!(errors = ?fields, "Request validation failed");
+                (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    Json(R::error_with_data(
+                        "Validation failed",
+                        code::VALIDATION,
+                        fields,
+                    )),
+                )
+                    .into_response()
+            }
+        }
+    }
+}
+
+/// 把嵌套的校验错误展开为 `a.b[0].c` 形式的字段路径
+fn collect_field_errors(prefix: &str, errors: &ValidationErrors, out: &mut Vec<FieldError>) {
+    for (field, kind) in errors.errors() {
+        let path = if prefix.is_empty() {
+            field.to_string()
+        } else {
+            format!("{prefix}.{field}")
+        };
+
+        match kind {
+            ValidationErrorsKind::Field(errs) => {
+                out.extend(errs.iter().map(|e| {
+                    FieldError {
+                        field: path.clone(),
+                        message: e
+                            .message
+                            .as_ref()
+                            .map(|m| m.to_string())
+                            .unwrap_or_else(|| format!("invalid value ({})", e.code)),
+                    }
+                }));
+            }
+            ValidationErrorsKind::Struct(nested) => collect_field_errors(&path, nested, out),
+            ValidationErrorsKind::List(items) => {
+                for (index, nested) in items {
+                    collect_field_errors(&format!("{path}[{index}]"), nested, out);
+                }
+            }
+        }
+    }
+}
+
+/// 带校验的 JSON 请求体提取器
+pub struct ValidatedJson<T>(pub T);
+
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = ValidationRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(|e| ValidationRejection::Malformed(e.body_text()))?;
+        value.validate()?;
+        Ok(Self(value))
+    }
+}
+
+/// 带校验的查询参数提取器
+pub struct ValidatedQuery<T>(pub T);
+
+impl<T, S> FromRequestParts<S> for ValidatedQuery<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = ValidationRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(value) = Query::<T>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| ValidationRejection::Malformed(e.body_text()))?;
+        value.validate()?;
+        Ok(Self(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use validator::Validate;
+
+    use super::{FieldError, ValidationRejection};
+
+    #[derive(Validate)]
+    struct Inner {
+        #[validate(range(min = 1, message = "must be positive"))]
+        n: i64,
+    }
+
+    #[derive(Validate)]
+    struct Outer {
+        #[validate(length(min = 1, max = 3, message = "length must be 1-3"))]
+        name: String,
+        #[validate(nested)]
+        inner: Inner,
+    }
+
+    #[test]
+    fn flattens_nested_field_errors() {
+        let value = Outer {
+            name: "toolong".into(),
+            inner: Inner { n: 0 },
+        };
+
+        let rejection = ValidationRejection::from(value.validate().unwrap_err());
+        let ValidationRejection::Invalid(fields) = rejection else {
+            panic!("expected field errors");
+        };
+
+        assert_eq!(
+            fields,
+            vec![
+                FieldError {
+                    field: "inner.n".into(),
+                    message: "must be positive".into(),
+                },
+                FieldError {
+                    field: "name".into(),
+                    message: "length must be 1-3".into(),
+                },
+            ]
+        );
+    }
+}
@@ -31,7 +31,7 @@ impl TryFrom<i64> for Chain {
             56 => Ok(Chain::Bsc),
             137 => Ok(Chain::Polygon),
             101 => Ok(Chain::Solana),
-            _ => Err(AppError::internal(format!(
+            _ => Err(AppError::bad_request(format!(
                 "Unsupported Chain ID: {}",
                 value
             ))),
@@ -8,10 +8,10 @@ impl EvmCrypto {
     pub fn recover_address(message: &str, signature: &str) -> Result<String, AppError> {
         let sig_hex = signature.strip_prefix("0x").unwrap_or(signature);
         let sig_bytes =
-            hex::decode(sig_hex).map_err(|_| AppError::internal("Invalid hex signature"))?;
+            hex::decode(sig_hex).map_err(|_| AppError::bad_request("Invalid hex signature"))?;
 
         if sig_bytes.len() != 65 {
-            return Err(AppError::internal("Signature must be 65 bytes"));
+            return Err(AppError::bad_request("Signature must be 65 bytes"));
         }
 
         // 构造以太坊特定格式消息
@@ -23,7 +23,7 @@ impl EvmCrypto {
         let recovery_id = if v >= 27 { v - 27 } else { v } as i32;
 
         let addr = recover(&msg_hash, &sig_bytes[..64], recovery_id)
-            .map_err(|e| AppError::internal(format!("Recovery failed: {}", e)))?;
+            .map_err(|e| AppError::bad_request(format!("Recovery failed: {}", e)))?;
 
         Ok(format!("{:?}", addr).to_lowercase())
     }
@@ -0,0 +1,392 @@
+use arc_swap::ArcSwap;
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Instant,
+};
+
+use crate::config::application::LoadBalanceStrategy;
+
+/// 一致性哈希环上每个实例的虚拟节点数
+const VIRTUAL_NODES: usize = 100;
+
+/// 单个上游实例
+#[derive(Debug)]
+pub struct Endpoint {
+    /// 实例地址，不带末尾 `/`
+    pub url: String,
+    /// 熔断器 key（每个实例独立熔断）
+    pub breaker_key: String,
+    in_flight: AtomicUsize,
+    health: Mutex<HealthStatus>,
+}
+
+/// 主动健康检查结果
+#[derive(Debug, Clone)]
+pub struct HealthStatus {
+    pub healthy: bool,
+    pub consecutive_successes: u32,
+    pub consecutive_failures: u32,
+    pub last_checked: Option<Instant>,
+    pub last_error: Option<String>,
+}
+
+impl Default for HealthStatus {
+    /// 启动时先视为健康，避免首轮探测完成前无实例可用
+    fn default() -> Self {
+        Self {
+            healthy: true,
+            consecutive_successes: 0,
+            consecutive_failures: 0,
+            last_checked: None,
+            last_error: None,
+        }
+    }
+}
+
+impl Endpoint {
+    fn new(service: &str, url: String) -> Self {
+        Self {
+            breaker_key: format!("{}@{}", service, url),
+            url,
+            in_flight: AtomicUsize::new(0),
+            health: Mutex::new(HealthStatus::default()),
+        }
+    }
+
+    /// 标记一个进行中的请求，guard 释放时自动减一
+    pub fn track(self: &Arc<Self>) -> InFlightGuard {
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+        InFlightGuard(self.clone())
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Relaxed)
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.health_status().healthy
+    }
+
+    pub fn health_status(&self) -> HealthStatus {
+        self.health
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// 记录一次探测结果，达到阈值时切换状态，返回切换后的状态（未切换返回 `None`）
+    pub fn record_probe(
+        &self,
+        result: Result<(), String>,
+        healthy_threshold: u32,
+        unhealthy_threshold: u32,
+    ) -> Option<bool> {
+        let mut health = self
+            .health
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        health.last_checked = Some(Instant::now());
+
+        match result {
+            Ok(()) => {
+                health.consecutive_successes += 1;
+                health.consecutive_failures = 0;
+                health.last_error = None;
+                if !health.healthy && health.consecutive_successes >= healthy_threshold {
+                    health.healthy = true;
+                    return Some(true);
+                }
+            }
+            Err(e) => {
+                health.consecutive_failures += 1;
+                health.consecutive_successes = 0;
+                health.last_error = Some(e);
+                if health.healthy && health.consecutive_failures >= unhealthy_threshold {
+                    health.healthy = false;
+                    return Some(false);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// 进行中请求计数 guard
+#[derive(Debug)]
+pub struct InFlightGuard(Arc<Endpoint>);
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// 实例列表快照，服务发现更新实例时整体替换
+#[derive(Debug, Default)]
+pub struct Members {
+    endpoints: Vec<Arc<Endpoint>>,
+    /// 一致性哈希环：(哈希值, 实例下标)，按哈希值升序
+    ring: Vec<(u64, usize)>,
+}
+
+impl Members {
+    fn new(endpoints: Vec<Arc<Endpoint>>, strategy: LoadBalanceStrategy) -> Self {
+        let mut ring = Vec::new();
+        if strategy == LoadBalanceStrategy::ConsistentHash {
+            for (index, endpoint) in endpoints.iter().enumerate() {
+                for node in 0..VIRTUAL_NODES {
+                    ring.push((hash(&(endpoint.url.as_str(), node)), index));
+                }
+            }
+            ring.sort_unstable();
+        }
+        Self { endpoints, ring }
+    }
+
+    pub fn endpoints(&self) -> &[Arc<Endpoint>] {
+        &self.endpoints
+    }
+}
+
+/// 服务的上游实例池
+#[derive(Debug)]
+pub struct UpstreamPool {
+    service: String,
+    strategy: LoadBalanceStrategy,
+    /// 注册中心中的服务名，为空表示实例固定写在配置中
+    discovery: Option<String>,
+    members: ArcSwap<Members>,
+    next: AtomicUsize,
+}
+
+impl UpstreamPool {
+    pub fn new(service: &str, urls: Vec<String>, strategy: LoadBalanceStrategy) -> Self {
+        let endpoints = urls
+            .into_iter()
+            .map(|url| Arc::new(Endpoint::new(service, url)))
+            .collect();
+        Self {
+            service: service.to_string(),
+            strategy,
+            discovery: None,
+            members: ArcSwap::from_pointee(Members::new(endpoints, strategy)),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// 实例从注册中心解析的实例池，初始为空，由服务发现任务填充
+    pub fn discovered(service: &str, discovery: &str, strategy: LoadBalanceStrategy) -> Self {
+        Self {
+            discovery: Some(discovery.to_string()),
+            ..Self::new(service, Vec::new(), strategy)
+        }
+    }
+
+    pub fn discovery(&self) -> Option<&str> {
+        self.discovery.as_deref()
+    }
+
+    /// 当前实例列表，同一次选择过程应始终使用同一份快照
+    pub fn members(&self) -> Arc<Members> {
+        self.members.load_full()
+    }
+
+    /// 替换实例列表，地址未变的实例沿用原对象（保留健康状态和进行中计数），返回是否有变化
+    pub fn update(&self, urls: &[String]) -> bool {
+        let current = self.members.load();
+        if current.endpoints.iter().map(|e| &e.url).eq(urls) {
+            return false;
+        }
+        let endpoints = urls
+            .iter()
+            .map(|url| {
+                current
+                    .endpoints
+                    .iter()
+                    .find(|e| &e.url == url)
+                    .cloned()
+                    .unwrap_or_else(|| Arc::new(Endpoint::new(&self.service, url.clone())))
+            })
+            .collect();
+        self.members
+            .store(Arc::new(Members::new(endpoints, self.strategy)));
+        true
+    }
+
+    /// 负载均衡策略和实例来源是否都相同（服务发现的实例池只比较服务名）
+    pub fn same_upstreams(&self, other: &UpstreamPool) -> bool {
+        self.strategy == other.strategy
+            && self.discovery == other.discovery
+            && (self.discovery.is_some()
+                || self.members().endpoints.iter().map(|e| &e.url).eq(other
+                    .members()
+                    .endpoints
+                    .iter()
+                    .map(|e| &e.url)))
+    }
+
+    /// 在可用实例中选择一个
+    ///
+    /// `available[i]` 表示 `members` 中第 i 个实例是否可用（健康且熔断器未打开）；
+    /// `hash_key` 用于一致性哈希（通常为用户 ID），缺失时退化为轮询
+    pub fn select(
+        &self,
+        members: &Members,
+        available: &[bool],
+        hash_key: Option<&str>,
+    ) -> Option<Arc<Endpoint>> {
+        let index = match (self.strategy, hash_key) {
+            (LoadBalanceStrategy::ConsistentHash, Some(key)) => {
+                Self::select_by_hash(members, available, key)
+            }
+            (LoadBalanceStrategy::LeastInFlight, _) => {
+                self.select_least_in_flight(members, available)
+            }
+            _ => self.select_round_robin(members, available),
+        }?;
+        Some(members.endpoints[index].clone())
+    }
+
+    fn select_round_robin(&self, members: &Members, available: &[bool]) -> Option<usize> {
+        let len = members.endpoints.len();
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        (0..len)
+            .map(|offset| (start + offset) % len)
+            .find(|&i| available[i])
+    }
+
+    fn select_least_in_flight(&self, members: &Members, available: &[bool]) -> Option<usize> {
+        // 从轮询位置开始扫描，负载相同时请求均匀分布
+        let len = members.endpoints.len();
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        (0..len)
+            .map(|offset| (start + offset) % len)
+            .filter(|&i| available[i])
+            .min_by_key(|&i| members.endpoints[i].in_flight())
+    }
+
+    fn select_by_hash(members: &Members, available: &[bool], key: &str) -> Option<usize> {
+        let ring = &members.ring;
+        if ring.is_empty() {
+            return None;
+        }
+        // 顺时针找到第一个可用实例，实例被摘除时只影响落在它上面的 key
+        let key_hash = hash(key);
+        let start = ring.partition_point(|(h, _)| *h < key_hash);
+        (0..ring.len())
+            .map(|offset| ring[(start + offset) % ring.len()].1)
+            .find(|&i| available[i])
+    }
+}
+
+fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UpstreamPool;
+    use crate::config::application::LoadBalanceStrategy;
+
+    fn pool(strategy: LoadBalanceStrategy) -> UpstreamPool {
+        UpstreamPool::new(
+            "user",
+            vec![
+                "http://a".to_string(),
+                "http://b".to_string(),
+                "http://c".to_string(),
+            ],
+            strategy,
+        )
+    }
+
+    /// 连续选择 `n` 次，返回选中实例的地址
+    fn pick(pool: &UpstreamPool, available: &[bool], key: Option<&str>, n: usize) -> Vec<String> {
+        let members = pool.members();
+        (0..n)
+            .map(|_| pool.select(&members, available, key).unwrap().url.clone())
+            .collect()
+    }
+
+    #[test]
+    fn round_robin_skips_unavailable_endpoints() {
+        let pool = pool(LoadBalanceStrategy::RoundRobin);
+        assert_eq!(
+            pick(&pool, &[true, true, true], None, 4),
+            ["http://a", "http://b", "http://c", "http://a"]
+        );
+        assert_eq!(
+            pick(&pool, &[true, false, true], None, 3),
+            ["http://c", "http://c", "http://a"]
+        );
+        let members = pool.members();
+        assert!(
+            pool.select(&members, &[false, false, false], None)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn least_in_flight_prefers_idle_endpoints() {
+        let pool = pool(LoadBalanceStrategy::LeastInFlight);
+        let members = pool.members();
+        let endpoints = members.endpoints();
+        let _a = endpoints[0].track();
+        let _c1 = endpoints[2].track();
+        let _c2 = endpoints[2].track();
+
+        assert_eq!(pick(&pool, &[true, true, true], None, 3), ["http://b"; 3]);
+        // 负载最低的实例不可用时选择次低的
+        assert_eq!(pick(&pool, &[true, false, true], None, 2), ["http://a"; 2]);
+
+        // guard 释放后计数归还
+        drop(_c1);
+        drop(_c2);
+        assert_eq!(endpoints[2].in_flight(), 0);
+    }
+
+    #[test]
+    fn consistent_hash_keeps_keys_on_the_same_endpoint() {
+        let pool = pool(LoadBalanceStrategy::ConsistentHash);
+        let all = [true, true, true];
+
+        let picked: Vec<String> = (0..100)
+            .map(|id| pick(&pool, &all, Some(&id.to_string()), 1).remove(0))
+            .collect();
+        // 同一个 key 总是落在同一个实例上，不同 key 分布到所有实例
+        for (id, url) in picked.iter().enumerate() {
+            assert!(
+                pick(&pool, &all, Some(&id.to_string()), 3)
+                    .iter()
+                    .all(|p| p == url)
+            );
+        }
+        for url in ["http://a", "http://b", "http://c"] {
+            assert!(picked.iter().any(|p| p == url));
+        }
+
+        // 摘除一个实例只影响原本落在它上面的 key
+        let without_b = [true, false, true];
+        for (id, url) in picked.iter().enumerate() {
+            let now = pick(&pool, &without_b, Some(&id.to_string()), 1).remove(0);
+            if url == "http://b" {
+                assert_ne!(now, "http://b");
+            } else {
+                assert_eq!(&now, url);
+            }
+        }
+
+        // 没有 key（未登录）时退化为轮询
+        assert_eq!(
+            pick(&pool, &all, None, 3),
+            ["http://a", "http://b", "http://c"]
+        );
+    }
+}
@@ -1,18 +1,30 @@
-use common_core::AppError;
+use common_config::ConfigLoader;
+use common_redis::application::Redis;
+use common_registry::application::Registry;
 use common_tracing::application::Logs;
-use common_web::application::Server;
-use serde::Deserialize;
-use std::{collections::HashMap, fs};
+use common_web::application::{Server, non_empty_secret};
+use serde::{Deserialize, Deserializer};
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct AppConfig {
     pub server: Server,
     pub cors: CorsConfig,
     pub jwt: JwtConfig,
+    pub identity: IdentityConfig,
     pub rate_limit: RateLimitConfig,
+    #[serde(default)]
+    pub client_ip: ClientIpConfig,
     pub circuit_breaker: CircuitBreakerConfig,
+    #[serde(default)]
+    pub health_check: HealthCheckConfig,
+    #[serde(default)]
+    pub retry: RetryConfig,
     pub services: HashMap<String, ServiceConfig>,
     pub logs: Logs,
+    /// 服务发现（`services.<name>.discovery`），修改后需重启网关
+    #[serde(default)]
+    pub registry: Option<Registry>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -42,34 +54,283 @@ impl JwtConfig {
     }
 }
 
+/// 网关向后端传递身份的方式
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdentityConfig {
+    /// 内部身份签名密钥（与各后端服务的 `identity.secret` 一致），不能为空
+    #[serde(deserialize_with = "non_empty_secret")]
+    pub secret: String,
+    /// 入站请求中一律移除的内部请求头（身份相关请求头始终移除）
+    #[serde(default)]
+    pub strip_headers: Vec<String>,
+}
+
+/// 客户端 IP 解析
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ClientIpConfig {
+    /// 可信代理（IP 或 CIDR），只有来自这些地址的转发头才会被采信
+    pub trusted_proxies: Vec<String>,
+}
+
+/// 限流配置（GCRA 算法，配置 Redis 时多个网关实例共享配额）
 #[derive(Debug, Clone, Deserialize)]
 pub struct RateLimitConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 默认配额，路由未配置 `rate_limit` 时使用
+    #[serde(flatten)]
+    pub default: RateLimitQuota,
+    /// 共享计数的 Redis，未配置时只在本实例内限流
+    #[serde(default)]
+    pub redis: Option<Redis>,
+    /// Redis key 前缀
+    #[serde(default = "default_rate_limit_key_prefix")]
+    pub key_prefix: String,
+    /// 获取 Redis 连接的超时（毫秒），超时后降级为本地限流
+    #[serde(default = "default_redis_timeout_ms")]
+    pub redis_timeout_ms: u64,
+    /// 按 API Key 限流时读取的请求头
+    #[serde(default = "default_api_key_header")]
+    pub api_key_header: String,
+    /// 已发放的 API Key，只有其中的 Key 单独计数，未知或未携带时按客户端 IP 计数
+    #[serde(default)]
+    pub api_keys: HashSet<String>,
+}
+
+/// 单个限流配额：每秒补充 `requests_per_second` 个令牌，最多积累 `burst_size` 个
+#[derive(Debug, Clone, Deserialize)]
+pub struct RateLimitQuota {
     pub requests_per_second: u32,
     pub burst_size: u32,
+    /// 计数维度
+    #[serde(default)]
+    pub key: RateLimitKey,
 }
 
+/// 限流计数维度
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitKey {
+    /// 客户端 IP
+    #[default]
+    Ip,
+    /// 登录用户 ID，未登录时按 IP
+    User,
+    /// API Key 请求头，未携带时按 IP
+    ApiKey,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_rate_limit_key_prefix() -> String {
+    "gateway:ratelimit".to_string()
+}
+
+fn default_redis_timeout_ms() -> u64 {
+    200
+}
+
+fn default_api_key_header() -> String {
+    "x-api-key".to_string()
+}
+
+/// 熔断器配置（每个上游实例独立统计）
 #[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
 pub struct CircuitBreakerConfig {
-    pub failure_threshold: u32,
+    /// 滚动统计窗口（秒）
+    pub window_seconds: u64,
+    /// 窗口内调用数达到该值后才计算失败率
+    pub minimum_calls: u32,
+    /// 失败率阈值（百分比），达到后打开
+    pub failure_rate_threshold: f64,
+    /// 超过该耗时（毫秒）视为慢调用
+    pub slow_call_duration_ms: u64,
+    /// 慢调用率阈值（百分比），100 表示不按慢调用熔断
+    pub slow_call_rate_threshold: f64,
+    /// 打开状态持续时间（秒），之后进入半开状态
+    #[serde(alias = "timeout_seconds")]
+    pub open_seconds: u64,
+    /// 半开状态允许的试探请求数，全部成功后关闭
+    pub half_open_max_calls: u32,
+    /// 视为失败的上游响应状态码
+    pub failure_status_codes: Vec<u16>,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            window_seconds: 10,
+            minimum_calls: 10,
+            failure_rate_threshold: 50.0,
+            slow_call_duration_ms: 3000,
+            slow_call_rate_threshold: 100.0,
+            open_seconds: 30,
+            half_open_max_calls: 3,
+            failure_status_codes: vec![502, 503, 504],
+        }
+    }
+}
+
+/// 幂等请求重试
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RetryConfig {
+    pub enabled: bool,
+    /// 最大尝试次数（含首次）
+    pub max_attempts: u32,
+    /// 指数退避基准延迟（毫秒），实际延迟带随机抖动
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+    /// 重试预算：每个正常请求为所属服务积累的重试额度
+    pub budget_ratio: f64,
+    /// 重试预算：每秒保底额度，低流量时也能重试
+    pub budget_min_per_second: f64,
+    /// 请求体不超过该大小时才缓存以便重放，更大的请求只尝试一次
+    pub max_buffered_body: usize,
+    /// 触发重试的上游状态码
+    pub retry_on_status: Vec<u16>,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_attempts: 3,
+            base_delay_ms: 50,
+            max_delay_ms: 1000,
+            budget_ratio: 0.2,
+            budget_min_per_second: 5.0,
+            max_buffered_body: 64 * 1024,
+            retry_on_status: vec![502, 503, 504],
+        }
+    }
+}
+
+/// 上游主动健康检查
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HealthCheckConfig {
+    pub enabled: bool,
+    /// 探测路径（上游的就绪探针）
+    pub path: String,
+    pub interval_seconds: u64,
     pub timeout_seconds: u64,
+    /// 连续成功多少次标记为健康
+    pub healthy_threshold: u32,
+    /// 连续失败多少次标记为不健康
+    pub unhealthy_threshold: u32,
+}
+
+impl Default for HealthCheckConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            path: "/health/ready".to_string(),
+            interval_seconds: 10,
+            timeout_seconds: 2,
+            healthy_threshold: 2,
+            unhealthy_threshold: 3,
+        }
+    }
+}
+
+fn default_max_body_size() -> usize {
+    2 * 1024 * 1024
+}
+
+/// 负载均衡策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoadBalanceStrategy {
+    /// 轮询
+    #[default]
+    RoundRobin,
+    /// 进行中请求数最少
+    LeastInFlight,
+    /// 按用户 ID 一致性哈希（未登录请求退化为轮询）
+    ConsistentHash,
+}
+
+/// 兼容单个地址和地址列表两种写法
+fn one_or_many<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(url) => vec![url],
+        OneOrMany::Many(urls) => urls,
+    })
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct ServiceConfig {
-    pub url: String,
+    /// 上游实例地址，`url` 为单实例的旧写法
+    #[serde(default, alias = "url", deserialize_with = "one_or_many")]
+    pub upstreams: Vec<String>,
+    /// 注册中心中的服务名，配置后从 `registry` 解析上游实例（不能与 `upstreams` 同时配置）
+    #[serde(default)]
+    pub discovery: Option<String>,
+    /// 多实例时的负载均衡策略
+    #[serde(default)]
+    pub load_balance: LoadBalanceStrategy,
     pub path_prefix: String,
     pub timeout_seconds: u64,
+    /// 请求体最大字节数（流式转发，超出时返回 413）
+    #[serde(default = "default_max_body_size")]
+    pub max_body_size: usize,
+    /// 转发时替换 `path_prefix` 的路径，默认直接去掉前缀
+    #[serde(default)]
+    pub rewrite: Option<String>,
+    /// 允许的 HTTP 方法，为空表示不限制
+    #[serde(default)]
+    pub methods: Vec<String>,
+    /// 是否需要 JWT：未设置时按 `jwt.whitelist_paths` 判断
+    #[serde(default)]
+    pub auth: Option<bool>,
+    /// 限流配额，未设置时使用 `rate_limit` 的默认配额
+    #[serde(default)]
+    pub rate_limit: Option<RateLimitQuota>,
+    /// 更细粒度的路由规则，上游地址继承自所属服务
+    #[serde(default)]
+    pub routes: Vec<RouteConfig>,
 }
 
-impl AppConfig {
-    pub fn from_default_yaml() -> Result<Self, AppError> {
-        let config_path = "gateway-service/application.yaml";
-        let content = fs::read_to_string(config_path)
-            .map_err(|e| AppError::internal(format!("Failed to read config: {}", e)))?;
+/// 服务下的细分路由，未设置的字段继承所属服务
+#[derive(Debug, Clone, Deserialize)]
+pub struct RouteConfig {
+    /// 必须位于所属服务的 `path_prefix` 之下
+    pub path_prefix: String,
+    #[serde(default)]
+    pub rewrite: Option<String>,
+    #[serde(default)]
+    pub methods: Vec<String>,
+    #[serde(default)]
+    pub timeout_seconds: Option<u64>,
+    #[serde(default)]
+    pub max_body_size: Option<usize>,
+    #[serde(default)]
+    pub auth: Option<bool>,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimitQuota>,
+}
 
-        let config: AppConfig = serde_yml::from_str(&content)
-            .map_err(|e| AppError::internal(format!("Failed to parse config: {}", e)))?;
+/// 默认配置文件路径（相对工作区根目录）
+pub const DEFAULT_CONFIG_PATH: &str = "gateway-service/application.yaml";
 
-        Ok(config)
+impl AppConfig {
+    /// 配置加载器（分层规则见 `common_config`），热更新时复用同一个加载器
+    pub fn loader() -> ConfigLoader {
+        ConfigLoader::new(DEFAULT_CONFIG_PATH)
     }
 }
@@ -0,0 +1,207 @@
+use common_registry::RegistryClient;
+use common_web::runner::Lifecycle;
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::{Arc, Mutex},
+};
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+
+use crate::{AppState, routing::RouteTable};
+
+/// 服务发现：配置了 `discovery` 的服务从注册中心解析上游实例
+///
+/// 按注册中心中的服务名缓存最近一次解析结果，配置热更新新建的实例池先用缓存填充，
+/// 注册中心暂时不可用时继续使用已知的实例
+#[derive(Clone)]
+pub struct Discovery {
+    registry: RegistryClient,
+    resolved: Arc<Mutex<HashMap<String, Vec<String>>>>,
+}
+
+impl Discovery {
+    pub fn new(registry: RegistryClient) -> Self {
+        Self {
+            registry,
+            resolved: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// 重新解析路由表中所有服务发现的服务，并更新实例池
+    pub async fn refresh(&self, route_table: &RouteTable) {
+        let names: BTreeSet<&str> = route_table
+            .upstreams()
+            .values()
+            .filter_map(|pool| pool.discovery())
+            .collect();
+
+        for name in names {
+            match self.registry.resolve(name).await {
+                Ok(instances) => {
+                    let urls = instances
+                        .into_iter()
+                        .map(|instance| instance.url.trim_end_matches('/').to_string())
+                        .collect();
+                    self.lock().insert(name.to_string(), urls);
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to resolve {}, keeping known upstreams: {}", name, e)
+                }
+            }
+        }
+        self.apply(route_table);
+    }
+
+    /// 用缓存的解析结果更新实例池
+    pub fn apply(&self, route_table: &RouteTable) {
+        let resolved = self.lock();
+        for (service, pool) in route_table.upstreams() {
+            let Some(urls) = pool.discovery().and_then(|name| resolved.get(name)) else {
+                continue;
+            };
+            if pool.update(urls) {
+                if urls.is_empty() {
+                    tracing::warn!("No upstreams registered for service={}", service);
+                } else {
+                    tracing::info!("Upstreams updated: service={}, urls={:?}", service, urls);
+                }
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Vec<String>>> {
+        self.resolved
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// 启动服务发现任务，按 `registry.watch_interval_seconds` 刷新实例列表，收到停机通知后退出
+pub fn spawn_discovery_watcher(state: AppState, lifecycle: Lifecycle) -> Option<JoinHandle<()>> {
+    let discovery = state.discovery.clone()?;
+
+    Some(tokio::spawn(async move {
+        let interval = discovery.registry.config().watch_interval();
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        // 启动时已解析过一次
+        ticker.tick().await;
+
+        tracing::info!(
+            "Upstream discovery started: interval={}s",
+            interval.as_secs()
+        );
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = lifecycle.shutdown() => break,
+            }
+            // 每轮使用最新的路由表，热更新新增的服务也会被解析
+            discovery.refresh(&state.runtime().route_table).await;
+            state.prune_circuit_breakers().await;
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use common_registry::{RegistryClient, application::Registry};
+    use std::{collections::HashMap, path::Path};
+
+    use super::Discovery;
+    use crate::{config::application::ServiceConfig, routing::RouteTable};
+
+    fn route_table() -> RouteTable {
+        let services: HashMap<String, ServiceConfig> = serde_yml::from_str(
+            r#"
+            user:
+              discovery: user-service
+              path_prefix: /api/user
+              timeout_seconds: 5
+            auth:
+              upstreams: ["http://127.0.0.1:5020"]
+              path_prefix: /api/auth
+              timeout_seconds: 5
+            "#,
+        )
+        .unwrap();
+        RouteTable::compile(&services).unwrap()
+    }
+
+    fn urls(table: &RouteTable, service: &str) -> Vec<String> {
+        table.upstreams()[service]
+            .members()
+            .endpoints()
+            .iter()
+            .map(|endpoint| endpoint.url.clone())
+            .collect()
+    }
+
+    fn discovery(file: &Path) -> Discovery {
+        let config: Registry =
+            serde_yml::from_str(&format!("backend: static\nfile: {}", file.display())).unwrap();
+        Discovery::new(RegistryClient::new(&config).unwrap())
+    }
+
+    #[tokio::test]
+    async fn follows_registry_and_keeps_known_upstreams_on_failure() {
+        let dir =
+            std::env::temp_dir().join(format!("gateway-discovery-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("registry.yaml");
+        let discovery = discovery(&file);
+        let table = route_table();
+
+        std::fs::write(
+            &file,
+            "user-service:\n  - url: http://10.0.0.5:5011/\n  - url: http://10.0.0.5:5010\n",
+        )
+        .unwrap();
+        discovery.refresh(&table).await;
+        assert_eq!(
+            urls(&table, "user"),
+            ["http://10.0.0.5:5010", "http://10.0.0.5:5011"]
+        );
+        // 静态配置的服务不受影响
+        assert_eq!(urls(&table, "auth"), ["http://127.0.0.1:5020"]);
+
+        // 实例上下线
+        std::fs::write(
+            &file,
+            "user-service:\n  - url: http://10.0.0.5:5010\n  - url: http://10.0.0.5:5012\n",
+        )
+        .unwrap();
+        discovery.refresh(&table).await;
+        assert_eq!(
+            urls(&table, "user"),
+            ["http://10.0.0.5:5010", "http://10.0.0.5:5012"]
+        );
+
+        // 热更新新建的路由表直接使用缓存的解析结果
+        let reloaded = route_table();
+        assert!(urls(&reloaded, "user").is_empty());
+        discovery.apply(&reloaded);
+        assert_eq!(urls(&reloaded, "user"), urls(&table, "user"));
+
+        // 注册中心文件损坏或丢失时保留最近一次解析到的实例
+        std::fs::write(&file, "user-service: [oops").unwrap();
+        discovery.refresh(&table).await;
+        assert_eq!(
+            urls(&table, "user"),
+            ["http://10.0.0.5:5010", "http://10.0.0.5:5012"]
+        );
+        std::fs::remove_file(&file).unwrap();
+        discovery.refresh(&table).await;
+        assert_eq!(
+            urls(&table, "user"),
+            ["http://10.0.0.5:5010", "http://10.0.0.5:5012"]
+        );
+
+        // 解析成功但服务已无实例时清空实例池
+        std::fs::write(&file, "article-service:\n  - url: http://10.0.0.6:5030\n").unwrap();
+        discovery.refresh(&table).await;
+        assert!(urls(&table, "user").is_empty());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,335 @@
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use common_core::{AppError, AppResult};
+use common_web::runner::Lifecycle;
+use futures_util::future::join_all;
+use serde::Serialize;
+use std::{sync::Arc, time::Duration};
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+
+use crate::{
+    AppState, balancer::Endpoint, config::application::HealthCheckConfig,
+    middleware::circuit_breaker::BreakerState,
+};
+
+/// 启动后台健康检查任务，按固定间隔探测所有上游实例，收到停机通知后退出
+pub fn spawn_health_checker(state: AppState, lifecycle: Lifecycle) -> Option<JoinHandle<()>> {
+    let config = state.runtime().app_config.health_check.clone();
+    if !config.enabled {
+        tracing::info!("Upstream health check disabled");
+        return None;
+    }
+
+    Some(tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(config.interval_seconds.max(1)));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        tracing::info!(
+            "Upstream health check started: path={}, interval={}s",
+            config.path,
+            config.interval_seconds
+        );
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = lifecycle.shutdown() => break,
+            }
+
+            // 每轮使用最新的路由表，热更新新增的实例也会被探测
+            let runtime = state.runtime();
+            let probes = runtime
+                .route_table
+                .upstreams()
+                .iter()
+                .flat_map(|(service, pool)| {
+                    pool.members()
+                        .endpoints()
+                        .iter()
+                        .map(|endpoint| (service.as_str(), endpoint.clone()))
+                        .collect::<Vec<_>>()
+                })
+                .map(|(service, endpoint)| {
+                    let state = &state;
+                    let config = &config;
+                    async move {
+                        let result = probe(state, &endpoint, config).await;
+                        match endpoint.record_probe(
+                            result,
+                            config.healthy_threshold,
+                            config.unhealthy_threshold,
+                        ) {
+                            Some(true) => tracing::info!(
+                                "Upstream recovered: service={}, url={}",
+                                service,
+                                endpoint.url
+                            ),
+                            Some(false) => tracing::warn!(
+                                "Upstream marked unhealthy: service={}, url={}, error={:?}",
+                                service,
+                                endpoint.url,
+                                endpoint.health_status().last_error
+                            ),
+                            None => {}
+                        }
+                    }
+                });
+
+            join_all(probes).await;
+        }
+    }))
+}
+
+/// 探测单个实例，2xx 视为健康
+async fn probe(
+    state: &AppState,
+    endpoint: &Endpoint,
+    config: &HealthCheckConfig,
+) -> Result<(), String> {
+    let url = format!("{}{}", endpoint.url, config.path);
+    let response = state
+        .http_client
+        .get(&url)
+        .timeout(Duration::from_secs(config.timeout_seconds))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("unexpected status {}", response.status()))
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+enum Status {
+    Up,
+    Degraded,
+    Down,
+}
+
+#[derive(Serialize)]
+struct HealthReport {
+    status: Status,
+    services: Vec<ServiceHealth>,
+}
+
+#[derive(Serialize)]
+struct ServiceHealth {
+    name: String,
+    status: Status,
+    upstreams: Vec<UpstreamHealth>,
+}
+
+/// 对外只暴露实例序号，地址和探测错误只写日志
+#[derive(Serialize)]
+struct UpstreamHealth {
+    index: usize,
+    healthy: bool,
+    circuit: BreakerState,
+    in_flight: usize,
+    consecutive_failures: u32,
+    last_checked_secs_ago: Option<u64>,
+}
+
+/// 上游报告（`/health/upstreams`）：所有服务都至少有一个可用实例时返回 200，否则 503
+pub async fn health_report(State(state): State<AppState>) -> Response {
+    let services = services_health(&state).await;
+
+    let any_down = services.iter().any(|s| matches!(s.status, Status::Down));
+    let any_degraded = services
+        .iter()
+        .any(|s| matches!(s.status, Status::Degraded));
+    let (status_code, status) = if any_down {
+        (StatusCode::SERVICE_UNAVAILABLE, Status::Down)
+    } else if any_degraded {
+        (StatusCode::OK, Status::Degraded)
+    } else {
+        (StatusCode::OK, Status::Up)
+    };
+
+    (status_code, Json(HealthReport { status, services })).into_response()
+}
+
+/// 就绪检查：任一服务没有可用实例时失败（详细状态见上游报告）
+pub async fn check_upstreams(state: &AppState) -> AppResult<()> {
+    let down: Vec<String> = services_health(state)
+        .await
+        .into_iter()
+        .filter(|s| matches!(s.status, Status::Down))
+        .map(|s| s.name)
+        .collect();
+    if down.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::internal(format!(
+            "No available upstream for: {}",
+            down.join(", ")
+        )))
+    }
+}
+
+/// 按服务汇总实例状态：健康且熔断未打开的实例视为可用
+async fn services_health(state: &AppState) -> Vec<ServiceHealth> {
+    let mut services = Vec::new();
+
+    let runtime = state.runtime();
+    for (name, pool) in runtime.route_table.upstreams() {
+        let mut upstreams = Vec::new();
+        for (index, endpoint) in pool.members().endpoints().iter().enumerate() {
+            upstreams.push(upstream_health(state, index, endpoint).await);
+        }
+
+        let available = upstreams
+            .iter()
+            .filter(|u| u.healthy && u.circuit != BreakerState::Open)
+            .count();
+        let status = match available {
+            0 => Status::Down,
+            n if n < upstreams.len() => Status::Degraded,
+            _ => Status::Up,
+        };
+
+        services.push(ServiceHealth {
+            name: name.clone(),
+            status,
+            upstreams,
+        });
+    }
+    services
+}
+
+async fn upstream_health(
+    state: &AppState,
+    index: usize,
+    endpoint: &Arc<Endpoint>,
+) -> UpstreamHealth {
+    let health = endpoint.health_status();
+    UpstreamHealth {
+        index,
+        healthy: health.healthy,
+        circuit: state.circuit_breaker.state(&endpoint.breaker_key).await,
+        in_flight: endpoint.in_flight(),
+        consecutive_failures: health.consecutive_failures,
+        last_checked_secs_ago: health.last_checked.map(|t| t.elapsed().as_secs()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{extract::State, http::StatusCode};
+    use reqwest::Client;
+    use serde_json::Value;
+    use std::sync::Arc;
+
+    use super::health_report;
+    use crate::{
+        AppState,
+        balancer::{Endpoint, UpstreamPool},
+        config::application::{AppConfig, LoadBalanceStrategy},
+        middleware::circuit_breaker::CircuitBreakerManager,
+        startup::build_runtime,
+    };
+
+    /// 仓库中的网关配置，article 服务启用第二个实例
+    fn state() -> AppState {
+        let yaml = std::fs::read_to_string("application.yaml")
+            .unwrap()
+            .replace("${JWT_SECRET}", "test-jwt-secret")
+            .replace("${INTERNAL_IDENTITY_SECRET}", "test-identity-secret")
+            .replace(
+                "# - \"http://127.0.0.1:5031\"",
+                "- \"http://127.0.0.1:5031\"",
+            );
+        let config: AppConfig = serde_yml::from_str(&yaml).unwrap();
+        AppState::new(
+            Client::new(),
+            CircuitBreakerManager::new(config.circuit_breaker.clone()),
+            None,
+            build_runtime(config, None, None).unwrap(),
+        )
+    }
+
+    fn article_endpoints(state: &AppState) -> Vec<Arc<Endpoint>> {
+        state.runtime().route_table.upstreams()["article"]
+            .members()
+            .endpoints()
+            .to_vec()
+    }
+
+    async fn report(state: &AppState) -> (StatusCode, Value) {
+        let response = health_report(State(state.clone())).await;
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[test]
+    fn probe_thresholds_control_health_transitions() {
+        let pool = UpstreamPool::new(
+            "article",
+            vec!["http://127.0.0.1:5030".to_string()],
+            LoadBalanceStrategy::RoundRobin,
+        );
+        let endpoint = pool.members().endpoints()[0].clone();
+        let failed = || Err("connection refused".to_string());
+
+        // 连续失败达到 unhealthy_threshold 才摘除
+        assert_eq!(endpoint.record_probe(failed(), 2, 3), None);
+        assert_eq!(endpoint.record_probe(failed(), 2, 3), None);
+        assert!(endpoint.health_status().healthy);
+        assert_eq!(endpoint.record_probe(failed(), 2, 3), Some(false));
+        assert!(!endpoint.health_status().healthy);
+        assert_eq!(endpoint.record_probe(failed(), 2, 3), None);
+
+        // 成功会清零失败计数，连续成功达到 healthy_threshold 才恢复
+        assert_eq!(endpoint.record_probe(Ok(()), 2, 3), None);
+        assert_eq!(endpoint.record_probe(failed(), 2, 3), None);
+        assert_eq!(endpoint.record_probe(Ok(()), 2, 3), None);
+        assert!(!endpoint.health_status().healthy);
+        assert_eq!(endpoint.record_probe(Ok(()), 2, 3), Some(true));
+        assert!(endpoint.health_status().healthy);
+        assert_eq!(endpoint.health_status().consecutive_failures, 0);
+    }
+
+    #[tokio::test]
+    async fn report_returns_503_only_when_a_service_has_no_available_upstream() {
+        let state = state();
+        let endpoints = article_endpoints(&state);
+        assert_eq!(endpoints.len(), 2);
+
+        let (status, body) = report(&state).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "UP");
+
+        // 部分实例不可用：降级但仍返回 200
+        endpoints[0].record_probe(Err("connection refused".to_string()), 1, 1);
+        let (status, body) = report(&state).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "DEGRADED");
+
+        // 服务没有可用实例：503
+        endpoints[1].record_probe(Err("connection refused".to_string()), 1, 1);
+        let (status, body) = report(&state).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], "DOWN");
+
+        // 报告中不包含实例地址和探测错误
+        let raw = body.to_string();
+        assert!(!raw.contains("127.0.0.1"));
+        assert!(!raw.contains("connection refused"));
+
+        endpoints[0].record_probe(Ok(()), 1, 1);
+        let (status, body) = report(&state).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "DEGRADED");
+    }
+}
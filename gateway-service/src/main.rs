@@ -1,29 +1,64 @@
+mod balancer;
 mod config;
+mod discovery;
+mod health;
+mod metrics;
 mod middleware;
 mod proxy;
+mod reload;
+mod retry;
+mod routing;
 mod startup;
 
 use common_core::AppError;
 use common_tracing::TracingService;
-use startup::{init_app_config, init_app_state, start_http_server};
+use common_web::runner::ServiceRunner;
+use config::application::AppConfig;
+use startup::{health_checks, init_app_config, init_app_state, start_http_server};
 
 pub use startup::AppState;
 
 #[tokio::main]
 async fn main() -> Result<(), AppError> {
     // 1. 加载配置
-    let app_config = init_app_config()?;
+    let config_loader = AppConfig::loader();
+    let app_config = init_app_config(&config_loader)?;
 
     // 初始化日志
-    let _guard = TracingService::init(&app_config.logs);
+    let _guard = TracingService::init(&app_config.server.name, &app_config.logs);
 
     let bind_addr = app_config.server.bind_addr.clone();
     let server_name = app_config.server.name.clone();
+    let mut runner = ServiceRunner::new(&server_name, &app_config.server.shutdown);
+    let lifecycle = runner.lifecycle();
 
     // 2. 初始化应用状态
     let app_state = init_app_state(app_config).await?;
 
-    // 3. 启动网关服务器
+    // 3. 启动上游健康检查
+    if let Some(health_checker) = health::spawn_health_checker(app_state.clone(), lifecycle.clone())
+    {
+        runner.add_background("Upstream health check", health_checker);
+    }
+
+    // 4. 定期从注册中心刷新服务发现的上游实例
+    if let Some(discovery_watcher) =
+        discovery::spawn_discovery_watcher(app_state.clone(), lifecycle.clone())
+    {
+        runner.add_background("Upstream discovery", discovery_watcher);
+    }
+
+    // 5. 监听配置文件变化和 SIGHUP，热更新路由、鉴权白名单、CORS、限流等配置
+    let config_watcher =
+        reload::spawn_config_watcher(app_state.clone(), config_loader, lifecycle.clone());
+    runner.add_background("Config watcher", config_watcher);
+
+    // 6. 启动网关服务器，运行到收到 SIGTERM/SIGINT，优雅停机后返回（日志守卫随之刷新）
     tracing::info!("🚀 {} starting on {}", server_name, bind_addr);
-    start_http_server(app_state, bind_addr).await
+    let health = health_checks(&app_state, lifecycle.clone());
+    runner.spawn_server(
+        "HTTP",
+        start_http_server(app_state, bind_addr, health, lifecycle),
+    );
+    runner.run().await
 }
@@ -0,0 +1,68 @@
+//! 网关专有指标，与公共 HTTP / 连接池指标一起由 `/metrics` 导出
+
+use prometheus::{
+    HistogramVec, IntCounterVec, IntGaugeVec, register_histogram_vec, register_int_counter_vec,
+    register_int_gauge_vec,
+};
+use std::{sync::LazyLock, time::Duration};
+
+use crate::{config::application::RateLimitKey, middleware::circuit_breaker::BreakerState};
+
+/// 单次上游调用耗时（到收到响应头为止，重试的每次尝试分别统计）
+static UPSTREAM_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "gateway_upstream_request_duration_seconds",
+        "Latency of a single upstream attempt until response headers are received",
+        &["service", "upstream", "status"]
+    )
+    .expect("register gateway_upstream_request_duration_seconds")
+});
+
+/// 熔断器状态：0 关闭 / 1 半开 / 2 打开
+static BREAKER_STATE: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register_int_gauge_vec!(
+        "gateway_circuit_breaker_state",
+        "Circuit breaker state per upstream (0 = closed, 1 = half-open, 2 = open)",
+        &["upstream"]
+    )
+    .expect("register gateway_circuit_breaker_state")
+});
+
+static RATE_LIMIT_REJECTIONS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "gateway_rate_limit_rejections_total",
+        "Requests rejected by the rate limiter",
+        &["scope", "key"]
+    )
+    .expect("register gateway_rate_limit_rejections_total")
+});
+
+/// 记录一次上游调用，`status` 为上游状态码，连接失败、超时等为 `error`
+pub fn observe_upstream(service: &str, upstream: &str, status: Option<u16>, elapsed: Duration) {
+    let status = status.map_or_else(|| "error".to_string(), |s| s.to_string());
+    UPSTREAM_DURATION
+        .with_label_values(&[service, upstream, status.as_str()])
+        .observe(elapsed.as_secs_f64());
+}
+
+pub fn set_breaker_state(upstream: &str, state: BreakerState) {
+    let value = match state {
+        BreakerState::Closed => 0,
+        BreakerState::HalfOpen => 1,
+        BreakerState::Open => 2,
+    };
+    BREAKER_STATE.with_label_values(&[upstream]).set(value);
+}
+
+pub fn remove_breaker_state(upstream: &str) {
+    let _ = BREAKER_STATE.remove_label_values(&[upstream]);
+}
+
+pub fn record_rate_limit_rejection(scope: &str, key: &RateLimitKey) {
+    let key = match key {
+        RateLimitKey::Ip => "ip",
+        RateLimitKey::User => "user",
+        RateLimitKey::ApiKey => "api_key",
+    };
+    RATE_LIMIT_REJECTIONS.with_label_values(&[scope, key]).inc();
+}
@@ -1,13 +1,50 @@
 use axum::{
     extract::{Request, State},
-    http::{HeaderMap, StatusCode},
+    http::{HeaderMap, HeaderValue},
     middleware::Next,
     response::{IntoResponse, Response},
 };
-use common_core::utils::jwt_utils::JwtUtils;
+use common_core::{
+    AppError,
+    utils::{
+        identity_utils::{
+            IDENTITY_SIGNATURE_HEADER, IDENTITY_TIMESTAMP_HEADER, IdentityUtils, USER_ID_HEADER,
+        },
+        jwt_utils::JwtUtils,
+    },
+};
+use common_web::error::ApiError;
 
 use crate::AppState;
 
+/// JWT 验证通过的用户 ID（请求扩展），后续中间件据此识别用户，而不是信任客户端请求头
+#[derive(Debug, Clone)]
+pub struct AuthenticatedUser(pub String);
+
+/// 移除客户端自带的内部请求头，防止在免鉴权路径上伪造身份
+///
+/// 身份头只能由 [`jwt_auth`] 在 JWT 验证通过后写入
+pub async fn strip_internal_headers(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let runtime = state.runtime();
+    let headers = request.headers_mut();
+    for name in [
+        USER_ID_HEADER,
+        IDENTITY_TIMESTAMP_HEADER,
+        IDENTITY_SIGNATURE_HEADER,
+    ] {
+        headers.remove(name);
+    }
+    for name in &runtime.app_config.identity.strip_headers {
+        headers.remove(name.as_str());
+    }
+
+    next.run(request).await
+}
+
 /// JWT 验证中间件（基于白名单）
 pub async fn jwt_auth(
     State(state): State<AppState>,
@@ -16,10 +53,19 @@ pub async fn jwt_auth(
     next: Next,
 ) -> Result<Response, Response> {
     let path = request.uri().path();
+    let runtime = state.runtime();
+    let config = &runtime.app_config;
 
-    // 检查是否在白名单中
-    if state.app_config.jwt.is_whitelisted(path) {
-        tracing::debug!("Path {} is whitelisted, skipping JWT verification", path);
+    // 路由显式配置 auth 时优先，否则按白名单判断
+    let auth_required = match runtime.route_table.find(path).and_then(|r| r.auth) {
+        Some(required) => required,
+        None => !config.jwt.is_whitelisted(path),
+    };
+    if !auth_required {
+        tracing::debug!(
+            "Path {} does not require auth, skipping JWT verification",
+            path
+        );
         return Ok(next.run(request).await);
     }
 
@@ -30,27 +76,33 @@ pub async fn jwt_auth(
         .and_then(|v| v.strip_prefix("Bearer "))
         .ok_or_else(|| {
             tracing::warn!("Missing or invalid Authorization header for path: {}", path);
-            (
-                StatusCode::UNAUTHORIZED,
+            ApiError(AppError::unauthorized(
                 "Missing or invalid Authorization header",
-            )
-                .into_response()
+            ))
+            .into_response()
         })?;
 
     // 验证 JWT
-    let claims = JwtUtils::verify_token(state.app_config.jwt.secret.clone(), token.to_string())
-        .map_err(|_| {
+    let claims =
+        JwtUtils::verify_token(config.jwt.secret.clone(), token.to_string()).map_err(|_| {
             tracing::warn!("Invalid or expired token for path: {}", path);
-            (StatusCode::UNAUTHORIZED, "Invalid or expired token").into_response()
+            ApiError(AppError::unauthorized("Invalid or expired token")).into_response()
         })?;
 
     // 验证通过，继续处理请求
     tracing::debug!("JWT verification passed for path: {}", path);
-    // 将 claims 信息注入到 request header 中
+    // 将用户 ID 连同内部签名注入到 request header 中，后端据此确认身份来自网关
     let mut request = request;
+    let (timestamp, signature) = IdentityUtils::sign(&config.identity.secret, claims.sub);
+    let headers = request.headers_mut();
+    headers.insert(USER_ID_HEADER, HeaderValue::from(claims.sub));
+    headers.insert(IDENTITY_TIMESTAMP_HEADER, HeaderValue::from(timestamp));
+    if let Ok(signature) = HeaderValue::from_str(&signature) {
+        headers.insert(IDENTITY_SIGNATURE_HEADER, signature);
+    }
     request
-        .headers_mut()
-        .insert("x-user-id", claims.sub.to_string().parse().unwrap());
+        .extensions_mut()
+        .insert(AuthenticatedUser(claims.sub.to_string()));
 
     Ok(next.run(request).await)
 }
@@ -1,118 +1,171 @@
+use common_core::AppError;
+use serde::Serialize;
 use std::{
-    collections::HashMap,
-    sync::Arc,
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
     time::{Duration, Instant},
 };
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, broadcast};
 
-/// 简化的熔断器管理器
-#[derive(Clone)]
-pub struct CircuitBreakerManager {
-    breakers: Arc<RwLock<HashMap<String, Arc<SimpleCircuitBreaker>>>>,
-    config: CircuitBreakerConfig,
-}
+use crate::{config::application::CircuitBreakerConfig, metrics};
 
-#[derive(Clone)]
-pub struct CircuitBreakerConfig {
-    pub failure_threshold: u32,
-    pub timeout: Duration,
+/// 熔断器状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    /// 正常放行，按滚动窗口统计失败率
+    Closed,
+    /// 拒绝所有请求，直到打开时长结束
+    Open,
+    /// 放行有限的试探请求，成功则关闭，失败则重新打开
+    HalfOpen,
 }
 
-/// 简单的熔断器实现
-pub struct SimpleCircuitBreaker {
-    failure_count: Arc<tokio::sync::RwLock<u32>>,
-    last_failure_time: Arc<tokio::sync::RwLock<Option<Instant>>>,
-    config: CircuitBreakerConfig,
+/// 熔断器状态变化事件
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerEvent {
+    pub name: String,
+    pub from: BreakerState,
+    pub to: BreakerState,
+    pub reason: String,
 }
 
-impl SimpleCircuitBreaker {
-    pub fn new(config: CircuitBreakerConfig) -> Self {
-        Self {
-            failure_count: Arc::new(tokio::sync::RwLock::new(0)),
-            last_failure_time: Arc::new(tokio::sync::RwLock::new(None)),
-            config,
-        }
+/// 校验熔断器配置：半开试探数必须大于 0，失败率和慢调用率阈值在 (0, 100] 之间
+pub fn validate_config(config: &CircuitBreakerConfig) -> Result<(), AppError> {
+    let mut errors = Vec::new();
+    if config.half_open_max_calls == 0 {
+        errors.push("circuit_breaker.half_open_max_calls: must be greater than 0".to_string());
     }
-
-    pub async fn is_open(&self) -> bool {
-        let last_failure = self.last_failure_time.read().await;
-        if let Some(last_time) = *last_failure {
-            let failure_count = *self.failure_count.read().await;
-            if failure_count >= self.config.failure_threshold {
-                // 检查是否超过超时时间
-                if last_time.elapsed() < self.config.timeout {
-                    return true; // 熔断器仍然打开
-                } else {
-                    // 超时后尝试半开状态
-                    drop(last_failure);
-                    *self.failure_count.write().await = 0;
-                    *self.last_failure_time.write().await = None;
-                }
-            }
+    for (field, value) in [
+        ("failure_rate_threshold", config.failure_rate_threshold),
+        ("slow_call_rate_threshold", config.slow_call_rate_threshold),
+    ] {
+        if !(value > 0.0 && value <= 100.0) {
+            errors.push(format!(
+                "circuit_breaker.{}: must be greater than 0 and at most 100",
+                field
+            ));
         }
-        false
     }
 
-    pub async fn record_success(&self) {
-        *self.failure_count.write().await = 0;
-        *self.last_failure_time.write().await = None;
+    if !errors.is_empty() {
+        return Err(AppError::internal(format!(
+            "Invalid circuit_breaker config:\n  - {}",
+            errors.join("\n  - ")
+        )));
     }
+    Ok(())
+}
 
-    pub async fn record_failure(&self) {
-        let mut count = self.failure_count.write().await;
-        *count += 1;
-        *self.last_failure_time.write().await = Some(Instant::now());
-    }
+/// 熔断器管理器（按名称懒创建，每个上游实例一个）
+#[derive(Clone)]
+pub struct CircuitBreakerManager {
+    breakers: Arc<RwLock<HashMap<String, Arc<CircuitBreaker>>>>,
+    config: Arc<CircuitBreakerConfig>,
+    events: broadcast::Sender<CircuitBreakerEvent>,
 }
 
 impl CircuitBreakerManager {
-    pub fn new(failure_threshold: u32, timeout_seconds: u64) -> Self {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        let (events, _) = broadcast::channel(256);
         Self {
             breakers: Arc::new(RwLock::new(HashMap::new())),
-            config: CircuitBreakerConfig {
-                failure_threshold,
-                timeout: Duration::from_secs(timeout_seconds),
-            },
+            config: Arc::new(config),
+            events,
         }
     }
 
-    /// 获取或创建服务的熔断器
-    pub async fn get_or_create(&self, service_name: &str) -> Arc<SimpleCircuitBreaker> {
-        let mut breakers = self.breakers.write().await;
+    /// 订阅状态变化事件
+    pub fn subscribe(&self) -> broadcast::Receiver<CircuitBreakerEvent> {
+        self.events.subscribe()
+    }
+
+    /// 获取或创建熔断器
+    pub async fn get_or_create(&self, name: &str) -> Arc<CircuitBreaker> {
+        if let Some(breaker) = self.breakers.read().await.get(name) {
+            return breaker.clone();
+        }
 
-        breakers
-            .entry(service_name.to_string())
-            .or_insert_with(|| Arc::new(SimpleCircuitBreaker::new(self.config.clone())))
+        self.breakers
+            .write()
+            .await
+            .entry(name.to_string())
+            .or_insert_with(|| {
+                Arc::new(CircuitBreaker::new(
+                    name.to_string(),
+                    self.config.clone(),
+                    self.events.clone(),
+                ))
+            })
             .clone()
     }
 
+    /// 当前状态（尚未创建的视为关闭）
+    pub async fn state(&self, name: &str) -> BreakerState {
+        match self.breakers.read().await.get(name) {
+            Some(breaker) => breaker.state(),
+            None => BreakerState::Closed,
+        }
+    }
+
+    /// 当前是否会拒绝新请求（打开且未到期，或半开且试探名额已满）
+    pub async fn is_open(&self, name: &str) -> bool {
+        match self.breakers.read().await.get(name) {
+            Some(breaker) => !breaker.would_admit(),
+            None => false,
+        }
+    }
+
+    /// 移除不在 `names` 中的熔断器（对应实例已从实例池移除），同时清理其状态指标
+    pub async fn retain(&self, names: &HashSet<String>) {
+        self.breakers.write().await.retain(|name, _| {
+            let keep = names.contains(name);
+            if !keep {
+                tracing::info!("Circuit breaker removed: {}", name);
+                metrics::remove_breaker_state(name);
+            }
+            keep
+        });
+    }
+
+    /// 上游状态码是否应计为失败
+    pub fn is_failure_status(&self, status: u16) -> bool {
+        self.config.failure_status_codes.contains(&status)
+    }
+
     /// 执行带熔断保护的操作
+    ///
+    /// `is_failure` 用于判断成功返回的结果是否应计为失败（如上游返回 503），
+    /// 此时结果仍原样返回给调用方
     pub async fn call<F, Fut, T, E>(
         &self,
-        service_name: &str,
+        name: &str,
         f: F,
+        is_failure: impl Fn(&T) -> bool,
     ) -> Result<T, CircuitBreakerError>
     where
         F: FnOnce() -> Fut,
         Fut: std::future::Future<Output = Result<T, E>>,
         E: std::error::Error + Send + Sync + 'static,
     {
-        let breaker = self.get_or_create(service_name).await;
+        let breaker = self.get_or_create(name).await;
 
-        // 检查熔断器状态
-        if breaker.is_open().await {
-            tracing::warn!("Circuit breaker open for service: {}", service_name);
+        let Some(permit) = breaker.try_acquire() else {
+            tracing::warn!("Circuit breaker open for: {}", name);
             return Err(CircuitBreakerError::Open);
-        }
+        };
 
-        // 执行调用
-        match f().await {
-            Ok(result) => {
-                breaker.record_success().await;
-                Ok(result)
+        let start = Instant::now();
+        let result = f().await;
+        let elapsed = start.elapsed();
+
+        match result {
+            Ok(value) => {
+                permit.record(!is_failure(&value), elapsed);
+                Ok(value)
             }
             Err(e) => {
-                breaker.record_failure().await;
+                permit.record(false, elapsed);
                 tracing::error!("Service call failed: {}", e);
                 Err(CircuitBreakerError::ServiceError(e.to_string()))
             }
@@ -120,6 +173,308 @@ impl CircuitBreakerManager {
     }
 }
 
+/// 三态熔断器，所有状态放在同一把锁内，保证状态切换的原子性
+pub struct CircuitBreaker {
+    name: String,
+    config: Arc<CircuitBreakerConfig>,
+    events: broadcast::Sender<CircuitBreakerEvent>,
+    inner: Mutex<Inner>,
+}
+
+struct Inner {
+    state: State,
+    window: SlidingWindow,
+}
+
+enum State {
+    Closed,
+    Open { until: Instant },
+    HalfOpen { in_flight: u32, successes: u32 },
+}
+
+impl State {
+    fn half_open() -> Self {
+        Self::HalfOpen {
+            in_flight: 0,
+            successes: 0,
+        }
+    }
+
+    fn kind(&self) -> BreakerState {
+        match self {
+            Self::Closed => BreakerState::Closed,
+            Self::Open { .. } => BreakerState::Open,
+            Self::HalfOpen { .. } => BreakerState::HalfOpen,
+        }
+    }
+}
+
+impl CircuitBreaker {
+    fn new(
+        name: String,
+        config: Arc<CircuitBreakerConfig>,
+        events: broadcast::Sender<CircuitBreakerEvent>,
+    ) -> Self {
+        let window = SlidingWindow::new(config.window_seconds);
+        metrics::set_breaker_state(&name, BreakerState::Closed);
+        Self {
+            name,
+            config,
+            events,
+            inner: Mutex::new(Inner {
+                state: State::Closed,
+                window,
+            }),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn state(&self) -> BreakerState {
+        self.lock().state.kind()
+    }
+
+    fn would_admit(&self) -> bool {
+        match self.lock().state {
+            State::Closed => true,
+            State::Open { until } => Instant::now() >= until,
+            State::HalfOpen { in_flight, .. } => in_flight < self.config.half_open_max_calls,
+        }
+    }
+
+    /// 申请一次调用许可
+    fn try_acquire(self: &Arc<Self>) -> Option<Permit> {
+        let mut inner = self.lock();
+
+        if let State::Open { until } = inner.state {
+            if Instant::now() < until {
+                return None;
+            }
+            self.transition(&mut inner, State::half_open(), "open duration elapsed");
+        }
+
+        let half_open = match &mut inner.state {
+            State::Closed => false,
+            State::HalfOpen { in_flight, .. } => {
+                if *in_flight >= self.config.half_open_max_calls {
+                    return None;
+                }
+                *in_flight += 1;
+                true
+            }
+            State::Open { .. } => unreachable!("open state handled above"),
+        };
+
+        Some(Permit {
+            breaker: self.clone(),
+            half_open,
+            recorded: false,
+        })
+    }
+
+    fn on_result(&self, half_open: bool, success: bool, elapsed: Duration) {
+        let mut guard = self.lock();
+        let inner = &mut *guard;
+        let slow = elapsed >= Duration::from_millis(self.config.slow_call_duration_ms);
+        let open = || State::Open {
+            until: Instant::now() + Duration::from_secs(self.config.open_seconds),
+        };
+
+        let next = match &mut inner.state {
+            State::Closed => {
+                inner.window.record(!success, slow);
+                self.trip_reason(&inner.window)
+                    .map(|reason| (open(), reason))
+            }
+            State::HalfOpen {
+                in_flight,
+                successes,
+            } if half_open => {
+                *in_flight = in_flight.saturating_sub(1);
+                if !success {
+                    Some((open(), "trial request failed".to_string()))
+                } else {
+                    *successes += 1;
+                    (*successes >= self.config.half_open_max_calls).then(|| {
+                        inner.window.reset();
+                        (State::Closed, "trial requests succeeded".to_string())
+                    })
+                }
+            }
+            // 状态已变化（如半开期间重新打开），旧许可的结果不再计入
+            _ => None,
+        };
+
+        if let Some((to, reason)) = next {
+            self.transition(inner, to, &reason);
+        }
+    }
+
+    /// 许可未记录结果就被丢弃（请求被取消），归还半开名额
+    fn on_cancel(&self, half_open: bool) {
+        if !half_open {
+            return;
+        }
+        if let State::HalfOpen { in_flight, .. } = &mut self.lock().state {
+            *in_flight = in_flight.saturating_sub(1);
+        }
+    }
+
+    fn trip_reason(&self, window: &SlidingWindow) -> Option<String> {
+        let (calls, failures, slow) = window.totals();
+        if calls == 0 || calls < self.config.minimum_calls as u64 {
+            return None;
+        }
+
+        let failure_rate = failures as f64 * 100.0 / calls as f64;
+        if failure_rate >= self.config.failure_rate_threshold {
+            return Some(format!(
+                "failure rate {:.1}% over {} calls",
+                failure_rate, calls
+            ));
+        }
+
+        let slow_rate = slow as f64 * 100.0 / calls as f64;
+        if self.config.slow_call_rate_threshold < 100.0
+            && slow_rate >= self.config.slow_call_rate_threshold
+        {
+            return Some(format!(
+                "slow call rate {:.1}% over {} calls",
+                slow_rate, calls
+            ));
+        }
+        None
+    }
+
+    fn transition(&self, inner: &mut Inner, to: State, reason: &str) {
+        let from = inner.state.kind();
+        inner.state = to;
+        metrics::set_breaker_state(&self.name, inner.state.kind());
+        let event = CircuitBreakerEvent {
+            name: self.name.clone(),
+            from,
+            to: inner.state.kind(),
+            reason: reason.to_string(),
+        };
+        // 没有订阅者时发送失败，忽略即可
+        let _ = self.events.send(event);
+    }
+}
+
+/// 一次调用许可，记录结果或被丢弃时释放
+struct Permit {
+    breaker: Arc<CircuitBreaker>,
+    half_open: bool,
+    recorded: bool,
+}
+
+impl Permit {
+    fn record(mut self, success: bool, elapsed: Duration) {
+        self.recorded = true;
+        self.breaker.on_result(self.half_open, success, elapsed);
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        if !self.recorded {
+            self.breaker.on_cancel(self.half_open);
+        }
+    }
+}
+
+/// 按秒分桶的滚动窗口
+struct SlidingWindow {
+    epoch: Instant,
+    buckets: Vec<Bucket>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Bucket {
+    second: u64,
+    calls: u64,
+    failures: u64,
+    slow: u64,
+}
+
+impl SlidingWindow {
+    fn new(window_seconds: u64) -> Self {
+        Self {
+            epoch: Instant::now(),
+            buckets: vec![Bucket::default(); window_seconds.max(1) as usize],
+        }
+    }
+
+    fn now_second(&self) -> u64 {
+        self.epoch.elapsed().as_secs()
+    }
+
+    fn record(&mut self, failed: bool, slow: bool) {
+        let second = self.now_second();
+        let len = self.buckets.len() as u64;
+        let bucket = &mut self.buckets[(second % len) as usize];
+        if bucket.second != second {
+            *bucket = Bucket {
+                second,
+                ..Bucket::default()
+            };
+        }
+        bucket.calls += 1;
+        bucket.failures += failed as u64;
+        bucket.slow += slow as u64;
+    }
+
+    /// 窗口内 (调用数, 失败数, 慢调用数)
+    fn totals(&self) -> (u64, u64, u64) {
+        let now = self.now_second();
+        let len = self.buckets.len() as u64;
+        self.buckets
+            .iter()
+            .filter(|b| b.calls > 0 && now - b.second < len)
+            .fold((0, 0, 0), |(c, f, s), b| {
+                (c + b.calls, f + b.failures, s + b.slow)
+            })
+    }
+
+    fn reset(&mut self) {
+        self.buckets.fill(Bucket::default());
+    }
+}
+
+/// 记录熔断器状态变化日志
+pub fn spawn_event_logger(mut events: broadcast::Receiver<CircuitBreakerEvent>) {
+    tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(event) => match event.to {
+                    BreakerState::Open => tracing::warn!(
+                        "Circuit breaker {} {:?} -> {:?}: {}",
+                        event.name,
+                        event.from,
+                        event.to,
+                        event.reason
+                    ),
+                    _ => tracing::info!(
+                        "Circuit breaker {} {:?} -> {:?}: {}",
+                        event.name,
+                        event.from,
+                        event.to,
+                        event.reason
+                    ),
+                },
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Circuit breaker event logger lagged, skipped {}", skipped)
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
 #[derive(Debug)]
 pub enum CircuitBreakerError {
     Open,
@@ -136,3 +491,175 @@ impl std::fmt::Display for CircuitBreakerError {
 }
 
 impl std::error::Error for CircuitBreakerError {}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashSet,
+        time::{Duration, Instant},
+    };
+
+    use super::{
+        BreakerState, CircuitBreakerError, CircuitBreakerManager, SlidingWindow, validate_config,
+    };
+    use crate::config::application::CircuitBreakerConfig;
+
+    fn manager(open_seconds: u64) -> CircuitBreakerManager {
+        CircuitBreakerManager::new(CircuitBreakerConfig {
+            minimum_calls: 4,
+            failure_rate_threshold: 50.0,
+            open_seconds,
+            half_open_max_calls: 2,
+            ..CircuitBreakerConfig::default()
+        })
+    }
+
+    /// 模拟一次上游调用，返回熔断器是否放行
+    async fn call(manager: &CircuitBreakerManager, success: bool) -> bool {
+        let result = manager
+            .call(
+                "user@http://a",
+                || async move {
+                    if success {
+                        Ok(200)
+                    } else {
+                        Err(std::io::Error::other("connection refused"))
+                    }
+                },
+                |status: &u16| manager.is_failure_status(*status),
+            )
+            .await;
+        !matches!(result, Err(CircuitBreakerError::Open))
+    }
+
+    #[tokio::test]
+    async fn opens_when_failure_rate_reaches_threshold() {
+        let manager = manager(30);
+        assert!(call(&manager, true).await);
+        assert!(call(&manager, false).await);
+        assert!(call(&manager, true).await);
+        // 调用数未达到 minimum_calls 时不计算失败率
+        assert_eq!(manager.state("user@http://a").await, BreakerState::Closed);
+
+        // 上游返回 503 同样计为失败：4 次调用中 2 次失败，达到 50%
+        let result = manager
+            .call(
+                "user@http://a",
+                || async { Ok::<_, std::io::Error>(503) },
+                |status: &u16| manager.is_failure_status(*status),
+            )
+            .await;
+        assert_eq!(result.unwrap(), 503);
+        assert_eq!(manager.state("user@http://a").await, BreakerState::Open);
+        assert!(manager.is_open("user@http://a").await);
+
+        // 打开期间直接拒绝，不调用上游
+        assert!(!call(&manager, true).await);
+    }
+
+    #[tokio::test]
+    async fn half_open_trials_close_or_reopen() {
+        let manager = manager(0);
+        for success in [true, false, true, false] {
+            call(&manager, success).await;
+        }
+        assert_eq!(manager.state("user@http://a").await, BreakerState::Open);
+
+        // 打开时长结束后进入半开，试探请求全部成功才关闭
+        assert!(call(&manager, true).await);
+        assert_eq!(manager.state("user@http://a").await, BreakerState::HalfOpen);
+        assert!(call(&manager, true).await);
+        assert_eq!(manager.state("user@http://a").await, BreakerState::Closed);
+
+        // 关闭后窗口重新计数，再次触发后试探失败立即重新打开
+        for success in [false, false, true, true] {
+            call(&manager, success).await;
+        }
+        assert_eq!(manager.state("user@http://a").await, BreakerState::Open);
+        assert!(call(&manager, false).await);
+        assert_eq!(manager.state("user@http://a").await, BreakerState::Open);
+    }
+
+    #[tokio::test]
+    async fn limits_half_open_trial_requests() {
+        let manager = manager(0);
+        for success in [false, false, false, false] {
+            call(&manager, success).await;
+        }
+
+        let breaker = manager.get_or_create("user@http://a").await;
+        let first = breaker.try_acquire().unwrap();
+        let _second = breaker.try_acquire().unwrap();
+        assert_eq!(breaker.state(), BreakerState::HalfOpen);
+        assert!(breaker.try_acquire().is_none());
+
+        // 被取消的试探请求归还名额
+        drop(first);
+        assert!(breaker.try_acquire().is_some());
+    }
+
+    #[test]
+    fn sliding_window_drops_expired_buckets() {
+        let mut window = SlidingWindow::new(2);
+        window.record(true, false);
+        window.record(false, true);
+        assert_eq!(window.totals(), (2, 1, 1));
+
+        // 1 秒后仍在窗口内，新调用落入下一个桶
+        window.epoch = Instant::now() - Duration::from_secs(1);
+        window.record(false, false);
+        assert_eq!(window.totals(), (3, 1, 1));
+
+        // 超过窗口长度的桶不再计入，复用时清零
+        window.epoch = Instant::now() - Duration::from_secs(2);
+        assert_eq!(window.totals(), (1, 0, 0));
+        window.record(true, false);
+        assert_eq!(window.totals(), (2, 1, 0));
+
+        window.reset();
+        assert_eq!(window.totals(), (0, 0, 0));
+    }
+
+    #[tokio::test]
+    async fn prunes_breakers_of_removed_endpoints() {
+        let manager = CircuitBreakerManager::new(CircuitBreakerConfig::default());
+        let kept = manager.get_or_create("user@http://a").await;
+        manager.get_or_create("user@http://b").await;
+
+        manager
+            .retain(&HashSet::from(["user@http://a".to_string()]))
+            .await;
+
+        let breakers = manager.breakers.read().await;
+        assert_eq!(breakers.len(), 1);
+        // 仍在使用的实例保留原有熔断器
+        assert!(std::sync::Arc::ptr_eq(&breakers["user@http://a"], &kept));
+        drop(breakers);
+        assert_eq!(manager.state("user@http://b").await, BreakerState::Closed);
+    }
+
+    #[test]
+    fn rejects_invalid_thresholds() {
+        assert!(validate_config(&CircuitBreakerConfig::default()).is_ok());
+
+        let err = validate_config(&CircuitBreakerConfig {
+            half_open_max_calls: 0,
+            failure_rate_threshold: 0.0,
+            slow_call_rate_threshold: 150.0,
+            ..CircuitBreakerConfig::default()
+        })
+        .unwrap_err()
+        .to_string();
+        for field in [
+            "half_open_max_calls",
+            "failure_rate_threshold",
+            "slow_call_rate_threshold",
+        ] {
+            assert!(
+                err.contains(&format!("circuit_breaker.{}", field)),
+                "{}",
+                err
+            );
+        }
+    }
+}
@@ -0,0 +1,333 @@
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use common_core::AppError;
+use std::net::{IpAddr, SocketAddr};
+
+use crate::{AppState, config::application::ClientIpConfig};
+
+const FORWARDED: &str = "forwarded";
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+const X_REAL_IP: &str = "x-real-ip";
+
+/// 解析出的客户端 IP（请求扩展），供限流、日志等使用
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp(pub IpAddr);
+
+/// 按可信代理列表解析客户端 IP
+#[derive(Debug, Clone, Default)]
+pub struct ClientIpResolver {
+    trusted: Vec<IpNet>,
+}
+
+/// 解析结果：客户端 IP 以及从客户端到网关的代理链（均为可信代理转发的地址）
+#[derive(Debug, PartialEq, Eq)]
+struct Resolved {
+    client: IpAddr,
+    /// 转发给上游的 X-Forwarded-For，第一个为客户端
+    chain: Vec<IpAddr>,
+}
+
+impl ClientIpResolver {
+    /// 编译可信代理列表，配置有误时列出所有问题
+    pub fn new(config: &ClientIpConfig) -> Result<Self, AppError> {
+        let mut trusted = Vec::new();
+        let mut errors = Vec::new();
+        for (i, cidr) in config.trusted_proxies.iter().enumerate() {
+            match IpNet::parse(cidr) {
+                Some(net) => trusted.push(net),
+                None => errors.push(format!(
+                    "client_ip.trusted_proxies[{}]: `{}` is not a valid IP or CIDR",
+                    i, cidr
+                )),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(AppError::internal(format!(
+                "Invalid client_ip config:\n  - {}",
+                errors.join("\n  - ")
+            )));
+        }
+        Ok(Self { trusted })
+    }
+
+    fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.trusted.iter().any(|net| net.contains(ip))
+    }
+
+    /// 直连地址不可信时忽略所有转发头；否则从右向左跳过可信代理，第一个不可信地址即客户端
+    ///
+    /// 转发头优先级：`Forwarded` > `X-Forwarded-For` > `X-Real-IP`
+    fn resolve(&self, peer: IpAddr, headers: &HeaderMap) -> Resolved {
+        let peer = peer.to_canonical();
+        if !self.is_trusted(peer) {
+            return Resolved {
+                client: peer,
+                chain: vec![peer],
+            };
+        }
+
+        let hops = if headers.contains_key(FORWARDED) {
+            forwarded_for(headers)
+        } else if headers.contains_key(X_FORWARDED_FOR) {
+            x_forwarded_for(headers)
+        } else {
+            headers
+                .get(X_REAL_IP)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| vec![parse_node(v)])
+                .unwrap_or_default()
+        };
+
+        // 从直连的可信代理开始向左回溯；遇到无法解析的地址时停止，以最近的可信代理为准
+        let mut chain = vec![peer];
+        for hop in hops.into_iter().rev() {
+            let Some(ip) = hop else { break };
+            chain.push(ip);
+            if !self.is_trusted(ip) {
+                break;
+            }
+        }
+        chain.reverse();
+
+        Resolved {
+            client: chain[0],
+            chain,
+        }
+    }
+}
+
+/// 客户端 IP 中间件：解析真实客户端 IP，并重写发往上游的转发头
+///
+/// 客户端自带的 `Forwarded`/`X-Forwarded-For`/`X-Real-IP` 不会原样透传
+pub async fn client_ip_middleware(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let resolved = state
+        .runtime()
+        .client_ip
+        .resolve(addr.ip(), request.headers());
+
+    let headers = request.headers_mut();
+    headers.remove(FORWARDED);
+    let chain = resolved
+        .chain
+        .iter()
+        .map(IpAddr::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    if let Ok(value) = HeaderValue::from_str(&chain) {
+        headers.insert(X_FORWARDED_FOR, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&resolved.client.to_string()) {
+        headers.insert(X_REAL_IP, value);
+    }
+
+    request.extensions_mut().insert(ClientIp(resolved.client));
+    next.run(request).await
+}
+
+/// 解析 RFC 7239 `Forwarded` 头中的 `for=` 参数（按出现顺序）
+fn forwarded_for(headers: &HeaderMap) -> Vec<Option<IpAddr>> {
+    headers
+        .get_all(FORWARDED)
+        .iter()
+        .flat_map(|v| v.to_str().ok().unwrap_or("").split(','))
+        .map(|element| {
+            element
+                .split(';')
+                .filter_map(|pair| pair.split_once('='))
+                .find(|(key, _)| key.trim().eq_ignore_ascii_case("for"))
+                .and_then(|(_, value)| parse_node(value))
+        })
+        .collect()
+}
+
+fn x_forwarded_for(headers: &HeaderMap) -> Vec<Option<IpAddr>> {
+    headers
+        .get_all(X_FORWARDED_FOR)
+        .iter()
+        .flat_map(|v| v.to_str().ok().unwrap_or("").split(','))
+        .map(parse_node)
+        .collect()
+}
+
+/// 解析单个节点：`1.2.3.4`、`1.2.3.4:80`、`"[2001:db8::1]:4711"`、`2001:db8::1`；
+/// `unknown` 和混淆标识（`_hidden`）返回 `None`
+fn parse_node(value: &str) -> Option<IpAddr> {
+    let value = value.trim().trim_matches('"');
+    if let Ok(ip) = value.parse::<IpAddr>() {
+        return Some(ip.to_canonical());
+    }
+    if let Ok(addr) = value.parse::<SocketAddr>() {
+        return Some(addr.ip().to_canonical());
+    }
+    // 不带端口的 `[v6]`
+    value
+        .strip_prefix('[')
+        .and_then(|v| v.strip_suffix(']'))
+        .and_then(|v| v.parse::<IpAddr>().ok())
+        .map(|ip| ip.to_canonical())
+}
+
+/// IP 网段（`10.0.0.0/8`，单个地址视为 /32 或 /128）
+#[derive(Debug, Clone, Copy)]
+struct IpNet {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl IpNet {
+    fn parse(value: &str) -> Option<Self> {
+        let (addr, prefix) = match value.trim().split_once('/') {
+            Some((addr, prefix)) => (addr.parse::<IpAddr>().ok()?, Some(prefix.parse().ok()?)),
+            None => (value.trim().parse::<IpAddr>().ok()?, None),
+        };
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(max);
+        (prefix <= max).then_some(Self { addr, prefix })
+    }
+
+    fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{HeaderMap, HeaderValue};
+    use std::net::IpAddr;
+
+    use super::{ClientIpResolver, IpNet, Resolved, parse_node};
+    use crate::config::application::ClientIpConfig;
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    fn resolver(trusted: &[&str]) -> ClientIpResolver {
+        ClientIpResolver::new(&ClientIpConfig {
+            trusted_proxies: trusted.iter().map(|t| t.to_string()).collect(),
+        })
+        .unwrap()
+    }
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    #[test]
+    fn parses_forwarded_nodes() {
+        assert_eq!(parse_node(" 1.2.3.4 "), Some(ip("1.2.3.4")));
+        assert_eq!(parse_node("1.2.3.4:80"), Some(ip("1.2.3.4")));
+        assert_eq!(
+            parse_node("\"[2001:db8::1]:4711\""),
+            Some(ip("2001:db8::1"))
+        );
+        assert_eq!(parse_node("[2001:db8::1]"), Some(ip("2001:db8::1")));
+        assert_eq!(parse_node("2001:db8::1"), Some(ip("2001:db8::1")));
+        // IPv4 映射的 IPv6 地址按 IPv4 处理
+        assert_eq!(parse_node("::ffff:10.0.0.1"), Some(ip("10.0.0.1")));
+        assert_eq!(parse_node("unknown"), None);
+        assert_eq!(parse_node("_hidden"), None);
+    }
+
+    #[test]
+    fn matches_cidr_ranges() {
+        let net = IpNet::parse("10.0.0.0/8").unwrap();
+        assert!(net.contains(ip("10.255.0.1")));
+        assert!(!net.contains(ip("11.0.0.1")));
+        assert!(!net.contains(ip("::1")));
+
+        let host = IpNet::parse("192.168.1.10").unwrap();
+        assert!(host.contains(ip("192.168.1.10")));
+        assert!(!host.contains(ip("192.168.1.11")));
+
+        let v6 = IpNet::parse("fd00::/8").unwrap();
+        assert!(v6.contains(ip("fd12::1")));
+        assert!(!v6.contains(ip("fe80::1")));
+
+        assert!(IpNet::parse("0.0.0.0/0").unwrap().contains(ip("8.8.8.8")));
+        assert!(IpNet::parse("10.0.0.0/33").is_none());
+        assert!(IpNet::parse("not-an-ip").is_none());
+
+        let err = ClientIpResolver::new(&ClientIpConfig {
+            trusted_proxies: vec!["10.0.0.0/8".into(), "10.0.0.0/40".into()],
+        })
+        .unwrap_err();
+        assert!(err.to_string().contains("client_ip.trusted_proxies[1]"));
+    }
+
+    #[test]
+    fn trusts_forwarding_headers_only_from_trusted_proxies() {
+        let resolver = resolver(&["10.0.0.0/8"]);
+        let spoofed = headers(&[("x-forwarded-for", "1.1.1.1")]);
+
+        // 直连地址不可信：忽略客户端自带的转发头
+        assert_eq!(
+            resolver.resolve(ip("203.0.113.7"), &spoofed),
+            Resolved {
+                client: ip("203.0.113.7"),
+                chain: vec![ip("203.0.113.7")],
+            }
+        );
+
+        // 经过可信代理：从右向左跳过可信代理，第一个不可信地址即客户端
+        let forwarded = headers(&[("x-forwarded-for", "1.1.1.1, 198.51.100.1, 10.0.0.2")]);
+        assert_eq!(
+            resolver.resolve(ip("10.0.0.1"), &forwarded),
+            Resolved {
+                client: ip("198.51.100.1"),
+                chain: vec![ip("198.51.100.1"), ip("10.0.0.2"), ip("10.0.0.1")],
+            }
+        );
+
+        // Forwarded 优先于 X-Forwarded-For
+        let both = headers(&[
+            ("forwarded", "for=\"[2001:db8::1]:4711\";proto=https"),
+            ("x-forwarded-for", "1.1.1.1"),
+        ]);
+        assert_eq!(
+            resolver.resolve(ip("10.0.0.1"), &both).client,
+            ip("2001:db8::1")
+        );
+
+        // 无法解析的地址停止回溯，以最近的可信代理为准
+        let hidden = headers(&[("forwarded", "for=1.1.1.1, for=_hidden")]);
+        assert_eq!(
+            resolver.resolve(ip("10.0.0.1"), &hidden).client,
+            ip("10.0.0.1")
+        );
+
+        let real_ip = headers(&[("x-real-ip", "198.51.100.9")]);
+        assert_eq!(
+            resolver.resolve(ip("10.0.0.1"), &real_ip).client,
+            ip("198.51.100.9")
+        );
+    }
+}
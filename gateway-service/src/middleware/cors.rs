@@ -0,0 +1,108 @@
+use axum::{
+    extract::{Request, State},
+    http::{HeaderName, HeaderValue, Method},
+    middleware::Next,
+    response::Response,
+};
+use common_core::AppError;
+use tower::{Layer, ServiceExt};
+use tower_http::cors::{Any, CorsLayer};
+
+use crate::{AppState, config::application::CorsConfig};
+
+/// CORS 中间件：每个请求使用当前配置快照中的 CORS 规则，配置热更新后立即生效
+pub async fn cors_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let cors = state.runtime().cors.clone();
+    match cors.layer(next).oneshot(request).await {
+        Ok(response) => response,
+        Err(never) => match never {},
+    }
+}
+
+/// 按配置构建 CORS 规则
+pub fn build_cors_layer(cors_config: &CorsConfig) -> Result<CorsLayer, AppError> {
+    let mut cors_layer = CorsLayer::new();
+
+    // 验证：当 allow_credentials=true 时，不能使用通配符 "*"
+    let has_wildcard = cors_config.allowed_origins.iter().any(|o| o == "*");
+    if has_wildcard && cors_config.allow_credentials {
+        tracing::warn!(
+            "⚠️  CORS 配置警告: allow_credentials=true 时不能使用 allowed_origins=\"*\"\n\
+             浏览器会拒绝此配置。请设置 allow_credentials=false 或指定具体的域名列表。"
+        );
+        return Err(AppError::internal(
+            "Invalid CORS config: cannot use wildcard origin with credentials",
+        ));
+    }
+
+    // 配置允许的源
+    if has_wildcard {
+        cors_layer = cors_layer.allow_origin(Any);
+        tracing::info!("CORS: 允许所有源 (*)");
+    } else {
+        let origins: Result<Vec<HeaderValue>, _> = cors_config
+            .allowed_origins
+            .iter()
+            .map(|origin| origin.parse())
+            .collect();
+        cors_layer = cors_layer.allow_origin(
+            origins.map_err(|e| AppError::internal(format!("Invalid CORS origin: {}", e)))?,
+        );
+        tracing::info!("CORS: 允许的源 = {:?}", cors_config.allowed_origins);
+    }
+
+    // 配置允许的方法
+    let methods: Result<Vec<Method>, _> = cors_config
+        .allowed_methods
+        .iter()
+        .map(|m| m.parse())
+        .collect();
+    cors_layer = cors_layer.allow_methods(
+        methods.map_err(|e| AppError::internal(format!("Invalid HTTP method: {}", e)))?,
+    );
+
+    // 配置允许的请求头
+    if cors_config.allowed_headers.contains(&"*".to_string()) {
+        cors_layer = cors_layer.allow_headers(Any);
+    } else {
+        let headers: Result<Vec<HeaderName>, _> = cors_config
+            .allowed_headers
+            .iter()
+            .map(|h| h.parse())
+            .collect();
+        cors_layer = cors_layer.allow_headers(
+            headers.map_err(|e| AppError::internal(format!("Invalid CORS header: {}", e)))?,
+        );
+    }
+
+    // 配置暴露的响应头
+    let expose_headers: Result<Vec<HeaderName>, _> = cors_config
+        .exposed_headers
+        .iter()
+        .map(|h| h.parse())
+        .collect();
+    cors_layer = cors_layer.expose_headers(
+        expose_headers.map_err(|e| AppError::internal(format!("Invalid expose header: {}", e)))?,
+    );
+
+    // 配置凭证
+    if cors_config.allow_credentials {
+        cors_layer = cors_layer.allow_credentials(true);
+        tracing::info!("CORS: 允许凭证 (cookies/auth headers)");
+    }
+
+    // 配置预检请求缓存时间
+    cors_layer = cors_layer.max_age(std::time::Duration::from_secs(cors_config.max_age));
+
+    tracing::info!(
+        "✅ CORS 配置完成: methods={:?}, max_age={}s",
+        cors_config.allowed_methods,
+        cors_config.max_age
+    );
+
+    Ok(cors_layer)
+}
@@ -0,0 +1,25 @@
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use common_metrics::RouteLabel;
+
+use crate::AppState;
+
+/// 按网关路由（路由前缀）标注请求指标，而不是统一的 `/api/{*path}`
+///
+/// 包括被鉴权、限流拒绝的请求；未匹配任何路由的请求保持默认标签
+pub async fn route_label(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let label = state
+        .runtime()
+        .route_table
+        .find(request.uri().path())
+        .map(|route| RouteLabel(route.prefix.clone()));
+
+    let mut response = next.run(request).await;
+    if let Some(label) = label {
+        response.extensions_mut().insert(label);
+    }
+    response
+}
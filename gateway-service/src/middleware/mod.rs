@@ -1,4 +1,7 @@
 pub mod auth;
 pub mod circuit_breaker;
+pub mod client_ip;
+pub mod cors;
+pub mod metrics;
 pub mod rate_limit;
 pub mod tracing;
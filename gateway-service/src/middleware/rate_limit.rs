@@ -1,45 +1,398 @@
 use axum::{
-    extract::{ConnectInfo, Request},
-    http::StatusCode,
+    Extension,
+    extract::{Request, State},
+    http::{HeaderMap, HeaderValue, header},
     middleware::Next,
     response::{IntoResponse, Response},
 };
-use governor::{Quota, RateLimiter, clock::DefaultClock, state::keyed::DefaultKeyedStateStore};
+use common_core::AppError;
+use common_redis::{RedisClient, Script};
+use common_web::error::ApiError;
 use std::{
-    net::{IpAddr, SocketAddr},
-    num::NonZeroU32,
-    sync::Arc,
+    collections::HashMap,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
 };
 
-/// 限流器（按 IP 限流）
-pub type AppRateLimiter = Arc<RateLimiter<IpAddr, DefaultKeyedStateStore<IpAddr>, DefaultClock>>;
+use crate::{
+    AppState,
+    config::application::{RateLimitConfig, RateLimitKey, RateLimitQuota},
+    metrics,
+    middleware::{auth::AuthenticatedUser, client_ip::ClientIp},
+    routing::validate_quota,
+};
+
+/// Redis 出错后改用本地限流的时长，期间不再访问 Redis
+const REDIS_RETRY_AFTER: Duration = Duration::from_secs(5);
+
+/// 本地计数表每处理多少次请求清理一次过期 key
+const LOCAL_SWEEP_INTERVAL: u64 = 4096;
+
+/// GCRA（与本地实现相同的算法），使用 Redis 服务器时间，多个网关实例共享同一时钟
+///
+/// KEYS[1] = 计数 key；ARGV[1] = 令牌间隔（微秒）；ARGV[2] = 突发量
+/// 返回 {是否放行, 剩余次数, 距离配额完全恢复的微秒数, 需等待的微秒数}
+const GCRA_SCRIPT: &str = r#"
+local interval = tonumber(ARGV[1])
+local burst = tonumber(ARGV[2])
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000000 + tonumber(time[2])
+local tat = tonumber(redis.call('GET', KEYS[1]) or now)
+if tat < now then
+  tat = now
+end
+local new_tat = tat + interval
+local allow_at = new_tat - interval * burst
+if now < allow_at then
+  return {0, 0, tat - now, allow_at - now}
+end
+redis.call('SET', KEYS[1], new_tat, 'PX', math.ceil((new_tat - now) / 1000))
+return {1, math.floor((now - allow_at) / interval), new_tat - now, 0}
+"#;
+
+/// 一次限流判定的结果
+#[derive(Debug, Clone, Copy)]
+pub struct Decision {
+    pub allowed: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    /// 配额完全恢复还需的时间
+    pub reset: Duration,
+    /// 被拒绝时需要等待的时间
+    pub retry_after: Duration,
+}
+
+/// 网关限流器：优先使用 Redis 共享计数，Redis 不可用时降级为本地计数
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    redis: Option<RedisClient>,
+    script: Script,
+    /// Redis 出错后在该时间点之前直接使用本地计数
+    redis_down_until: Mutex<Option<Instant>>,
+    local: LocalLimiter,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Result<Self, AppError> {
+        validate_quota(&config.default)
+            .map_err(|e| AppError::internal(format!("Invalid rate_limit config: {}", e)))?;
+
+        let redis = match &config.redis {
+            Some(redis_config) => {
+                tracing::info!("Rate limit backed by Redis: {}", redis_config.url_safe());
+                Some(RedisClient::new_lazy(
+                    redis_config.clone(),
+                    Duration::from_millis(config.redis_timeout_ms),
+                )?)
+            }
+            None => {
+                tracing::info!("Rate limit uses in-process counters (no Redis configured)");
+                None
+            }
+        };
+
+        Ok(Self {
+            config,
+            redis,
+            script: Script::new(GCRA_SCRIPT),
+            redis_down_until: Mutex::new(None),
+            local: LocalLimiter::default(),
+        })
+    }
+
+    /// 登记 Redis 连接池指标（热更新重建限流器后重新登记，替换旧连接池）
+    pub fn register_pool_metrics(&self) {
+        if let Some(redis) = &self.redis {
+            redis.register_pool_metrics("rate_limit_redis");
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    pub fn default_quota(&self) -> &RateLimitQuota {
+        &self.config.default
+    }
+
+    /// 请求携带的已发放 API Key；未知的 Key 不单独计数，避免每次换一个值绕过限流
+    pub fn api_key<'a>(&self, headers: &'a HeaderMap) -> Option<&'a str> {
+        headers
+            .get(&self.config.api_key_header)
+            .and_then(|v| v.to_str().ok())
+            .filter(|v| self.config.api_keys.contains(*v))
+    }
+
+    /// 对 `key` 消耗一次配额
+    pub async fn check(&self, key: &str, quota: &RateLimitQuota) -> Decision {
+        let interval = 1_000_000 / u64::from(quota.requests_per_second).max(1);
+        let burst = u64::from(quota.burst_size);
+
+        if let Some(redis) = self.redis.as_ref().filter(|_| self.redis_available()) {
+            let key = format!("{}:{}", self.config.key_prefix, key);
+            match redis
+                .run_script::<_, _, Vec<i64>>(&self.script, &[key], &[interval, burst])
+                .await
+            {
+                Ok(reply) if reply.len() == 4 => {
+                    self.mark_redis_up();
+                    return Decision::from_reply(&reply, quota.burst_size);
+                }
+                Ok(reply) => self.mark_redis_down(&format!("unexpected reply {:?}", reply)),
+                Err(e) => self.mark_redis_down(&e.to_string()),
+            }
+        }
+
+        self.local.check(key, interval, burst, quota.burst_size)
+    }
+
+    /// 就绪检查：探测共享计数使用的 Redis，未配置 Redis 时视为可用
+    pub async fn ping_redis(&self) -> Result<(), AppError> {
+        match &self.redis {
+            Some(redis) => redis.ping().await,
+            None => Ok(()),
+        }
+    }
+
+    fn redis_available(&self) -> bool {
+        let down_until = self.redis_down_until.lock().unwrap();
+        down_until.is_none_or(|until| Instant::now() >= until)
+    }
+
+    fn mark_redis_up(&self) {
+        let mut down_until = self.redis_down_until.lock().unwrap();
+        if down_until.take().is_some() {
+            tracing::info!("Rate limit Redis recovered, using shared counters again");
+        }
+    }
+
+    fn mark_redis_down(&self, error: &str) {
+        let mut down_until = self.redis_down_until.lock().unwrap();
+        if down_until.is_none() {
+            tracing::warn!(
+                "Rate limit Redis unavailable, falling back to local counters: {}",
+                error
+            );
+        }
+        *down_until = Some(Instant::now() + REDIS_RETRY_AFTER);
+    }
+}
+
+impl Decision {
+    fn from_reply(reply: &[i64], limit: u32) -> Self {
+        let micros = |v: i64| Duration::from_micros(v.max(0) as u64);
+        Self {
+            allowed: reply[0] == 1,
+            limit,
+            remaining: reply[1].clamp(0, i64::from(limit)) as u32,
+            reset: micros(reply[2]),
+            retry_after: micros(reply[3]),
+        }
+    }
+}
 
-/// 创建限流器（按 IP）
-pub fn create_rate_limiter(requests_per_second: u32, burst_size: u32) -> AppRateLimiter {
-    let quota = Quota::per_second(NonZeroU32::new(requests_per_second).unwrap())
-        .allow_burst(NonZeroU32::new(burst_size).unwrap());
+/// 本地 GCRA 计数（Redis 未配置或不可用时使用）
+struct LocalLimiter {
+    epoch: Instant,
+    /// key -> 理论到达时间（相对 `epoch` 的微秒数）
+    tats: Mutex<HashMap<String, u64>>,
+    calls: AtomicU64,
+}
 
-    Arc::new(RateLimiter::keyed(quota))
+impl Default for LocalLimiter {
+    fn default() -> Self {
+        Self {
+            epoch: Instant::now(),
+            tats: Mutex::new(HashMap::new()),
+            calls: AtomicU64::new(0),
+        }
+    }
 }
 
-/// 限流中间件（按 IP 限流）
+impl LocalLimiter {
+    fn check(&self, key: &str, interval: u64, burst: u64, limit: u32) -> Decision {
+        let now = self.epoch.elapsed().as_micros() as u64;
+        let mut tats = self.tats.lock().unwrap();
+
+        if self
+            .calls
+            .fetch_add(1, Ordering::Relaxed)
+            .is_multiple_of(LOCAL_SWEEP_INTERVAL)
+        {
+            tats.retain(|_, tat| *tat > now);
+        }
+
+        let tat = tats.get(key).copied().unwrap_or(now).max(now);
+        let new_tat = tat + interval;
+        // 与脚本中的 allow_at = new_tat - interval * burst 等价，这里移项避免无符号数下溢
+        let horizon = now + interval * burst;
+
+        if new_tat > horizon {
+            return Decision {
+                allowed: false,
+                limit,
+                remaining: 0,
+                reset: Duration::from_micros(tat - now),
+                retry_after: Duration::from_micros(new_tat - horizon),
+            };
+        }
+
+        tats.insert(key.to_string(), new_tat);
+        Decision {
+            allowed: true,
+            limit,
+            remaining: ((horizon - new_tat) / interval).min(u64::from(limit)) as u32,
+            reset: Duration::from_micros(new_tat - now),
+            retry_after: Duration::ZERO,
+        }
+    }
+}
+
+/// 限流中间件：按路由配额（未配置时用默认配额）和计数维度限流，并返回 `RateLimit-*` 响应头
 pub async fn rate_limit_middleware(
-    ConnectInfo(addr): ConnectInfo<SocketAddr>,
-    limiter: axum::extract::Extension<AppRateLimiter>,
+    State(state): State<AppState>,
+    Extension(ClientIp(ip)): Extension<ClientIp>,
     request: Request,
     next: Next,
 ) -> Result<Response, Response> {
-    let ip = addr.ip();
+    let runtime = state.runtime();
+    let limiter = &runtime.rate_limiter;
+    if !limiter.is_enabled() {
+        return Ok(next.run(request).await);
+    }
+
+    // 路由级配额按路由前缀单独计数，默认配额全局共用
+    let (scope, quota) = match runtime
+        .route_table
+        .find(request.uri().path())
+        .and_then(|route| {
+            route
+                .rate_limit
+                .as_ref()
+                .map(|q| (route.prefix.as_str(), q))
+        }) {
+        Some((prefix, quota)) => (prefix, quota),
+        None => ("global", limiter.default_quota()),
+    };
 
-    // 按 IP 检查是否超过限流
-    if limiter.check_key(&ip).is_err() {
-        tracing::warn!("Rate limit exceeded for IP: {}", ip);
-        return Err((
-            StatusCode::TOO_MANY_REQUESTS,
+    let subject = match quota.key {
+        RateLimitKey::Ip => None,
+        RateLimitKey::User => request
+            .extensions()
+            .get::<AuthenticatedUser>()
+            .map(|user| format!("user:{}", user.0)),
+        RateLimitKey::ApiKey => limiter
+            .api_key(request.headers())
+            .map(|v| format!("key:{}", v)),
+    }
+    .unwrap_or_else(|| format!("ip:{}", ip));
+
+    let decision = limiter
+        .check(&format!("{}:{}", scope, subject), quota)
+        .await;
+
+    if !decision.allowed {
+        tracing::warn!("Rate limit exceeded: scope={}, {}", scope, subject);
+        metrics::record_rate_limit_rejection(scope, &quota.key);
+        let mut response = ApiError(AppError::rate_limited(
             "Too many requests, please try again later",
-        )
-            .into_response());
+        ))
+        .into_response();
+        insert_headers(response.headers_mut(), &decision);
+        response.headers_mut().insert(
+            header::RETRY_AFTER,
+            HeaderValue::from(ceil_secs(decision.retry_after)),
+        );
+        return Err(response);
+    }
+
+    let mut response = next.run(request).await;
+    insert_headers(response.headers_mut(), &decision);
+    Ok(response)
+}
+
+/// 写入 IETF `RateLimit-*` 响应头
+fn insert_headers(headers: &mut HeaderMap, decision: &Decision) {
+    headers.insert("ratelimit-limit", HeaderValue::from(decision.limit));
+    headers.insert("ratelimit-remaining", HeaderValue::from(decision.remaining));
+    headers.insert(
+        "ratelimit-reset",
+        HeaderValue::from(ceil_secs(decision.reset)),
+    );
+}
+
+/// 向上取整到秒，最少 1 秒（避免客户端立即重试）
+fn ceil_secs(duration: Duration) -> u64 {
+    duration.as_micros().div_ceil(1_000_000).max(1) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{HeaderMap, HeaderValue};
+    use std::time::Duration;
+
+    use super::{LocalLimiter, RateLimiter};
+    use crate::config::application::{RateLimitConfig, RateLimitKey, RateLimitQuota};
+
+    fn limiter(api_keys: &[&str]) -> RateLimiter {
+        RateLimiter::new(RateLimitConfig {
+            enabled: true,
+            default: RateLimitQuota {
+                requests_per_second: 10,
+                burst_size: 5,
+                key: RateLimitKey::ApiKey,
+            },
+            redis: None,
+            key_prefix: "test".to_string(),
+            redis_timeout_ms: 200,
+            api_key_header: "x-api-key".to_string(),
+            api_keys: api_keys.iter().map(|k| k.to_string()).collect(),
+        })
+        .unwrap()
     }
 
-    Ok(next.run(request).await)
+    #[test]
+    fn only_issued_api_keys_get_their_own_bucket() {
+        let limiter = limiter(&["partner-key"]);
+        let mut headers = HeaderMap::new();
+        assert_eq!(limiter.api_key(&headers), None);
+
+        headers.insert("x-api-key", HeaderValue::from_static("partner-key"));
+        assert_eq!(limiter.api_key(&headers), Some("partner-key"));
+
+        // 随意伪造的 Key 回落到按 IP 计数
+        headers.insert("x-api-key", HeaderValue::from_static("random-1"));
+        assert_eq!(limiter.api_key(&headers), None);
+    }
+
+    #[test]
+    fn local_limiter_allows_burst_then_denies() {
+        // 每秒 1 个请求，突发 3 个
+        let interval = 1_000_000;
+        let mut limiter = LocalLimiter::default();
+
+        for remaining in [2, 1, 0] {
+            let decision = limiter.check("ip:1", interval, 3, 3);
+            assert!(decision.allowed);
+            assert_eq!(decision.remaining, remaining);
+        }
+        let decision = limiter.check("ip:1", interval, 3, 3);
+        assert!(!decision.allowed);
+        assert_eq!(decision.remaining, 0);
+        // 下一个请求约 1 秒后放行，配额约 3 秒后完全恢复
+        assert!(decision.retry_after <= Duration::from_secs(1));
+        assert!(decision.retry_after > Duration::from_millis(900));
+        assert!(decision.reset > Duration::from_millis(2900));
+
+        // 不同 key 单独计数
+        assert!(limiter.check("ip:2", interval, 3, 3).allowed);
+
+        // 1 秒后恢复一个名额
+        limiter.epoch -= Duration::from_secs(1);
+        assert!(limiter.check("ip:1", interval, 3, 3).allowed);
+        assert!(!limiter.check("ip:1", interval, 3, 3).allowed);
+    }
 }
@@ -1,18 +1,26 @@
 use axum::{extract::Request, middleware::Next, response::Response};
 use std::time::Instant;
 
+use super::client_ip::ClientIp;
+
 /// 请求追踪中间件（增强版）
 pub async fn request_tracing(request: Request, next: Next) -> Response {
     let method = request.method().clone();
     let uri = request.uri().clone();
     let path = uri.path().to_string();
     let query = uri.query().unwrap_or("").to_string();
+    let client_ip = request
+        .extensions()
+        .get::<ClientIp>()
+        .map(|ip| ip.0.to_string())
+        .unwrap_or_default();
     let start = Instant::now();
 
     tracing::info!(
         method = %method,
         path = %path,
         query = %query,
+        client_ip = %client_ip,
         "→ Incoming request"
     );
 
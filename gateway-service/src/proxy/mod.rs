@@ -1,147 +1,470 @@
 use axum::{
-    body::Body,
+    body::{Body, Bytes, HttpBody},
     extract::{Request, State},
-    http::{HeaderMap, Method, StatusCode},
+    http::{HeaderMap, HeaderName, HeaderValue, Method, header},
     response::{IntoResponse, Response},
 };
+use common_core::AppError;
+use common_web::{deadline::REQUEST_TIMEOUT_HEADER, error::ApiError};
+use futures_util::StreamExt;
+use http_body_util::{LengthLimitError, Limited};
 use reqwest::Client;
-use std::time::Duration;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tracing::{Instrument, field};
 
-use crate::{AppState, config::application::ServiceConfig};
+use crate::{
+    AppState,
+    balancer::{Endpoint, InFlightGuard},
+    metrics,
+    middleware::circuit_breaker::CircuitBreakerError,
+    routing::Route,
+};
 
 /// 代理转发请求到后端服务
+///
+/// 请求体和响应体都以流的形式透传，不在网关内缓冲，也不做任何编码转换
 pub async fn proxy_request(
     State(state): State<AppState>,
     request: Request,
 ) -> Result<Response, Response> {
-    let method = request.method().clone();
-    let uri = request.uri().clone();
-    let headers = request.headers().clone();
-    let body = axum::body::to_bytes(request.into_body(), usize::MAX)
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::BAD_REQUEST,
-                format!("Failed to read body: {}", e),
-            )
-                .into_response()
-        })?;
+    let (parts, body) = request.into_parts();
+    let method = parts.method;
+    let uri = parts.uri;
+    let headers = parts.headers;
 
-    // 根据路径前缀确定目标服务
+    // 按最长前缀匹配路由
     let path = uri.path();
-    let service = state
-        .app_config
-        .services
-        .values()
-        .find(|s| path.starts_with(&s.path_prefix))
-        .ok_or_else(|| {
-            (
-                StatusCode::NOT_FOUND,
-                format!("No service found for path: {}", path),
-            )
-                .into_response()
-        })?;
+    // 整个请求（含重试）使用同一份配置快照
+    let runtime = state.runtime();
+    let route = runtime.route_table.find(path).ok_or_else(|| {
+        ApiError(AppError::not_found(format!(
+            "No service found for path: {}",
+            path
+        )))
+        .into_response()
+    })?;
 
-    // 构建目标 URL（去掉路径前缀），保留查询字符串
-    let target_path = path.strip_prefix(&service.path_prefix).unwrap_or(path);
-    let target_url = if let Some(query) = uri.query() {
-        format!("{}{}?{}", service.url, target_path, query)
-    } else {
-        format!("{}{}", service.url, target_path)
+    if !route.allows(&method) {
+        let mut response = ApiError(AppError::method_not_allowed(format!(
+            "Method {} not allowed for path: {}",
+            method, path
+        )))
+        .into_response();
+        if let Ok(allow) = HeaderValue::from_str(&route.allow_header()) {
+            response.headers_mut().insert(header::ALLOW, allow);
+        }
+        return Err(response);
+    }
+
+    // 已声明长度的请求体超限直接拒绝；chunked 请求体在转发过程中由 Limited 截断
+    let max_body_size = route.max_body_size;
+    if content_length(&headers).is_some_and(|len| len > max_body_size as u64) {
+        return Err(payload_too_large(max_body_size));
+    }
+    let body = Body::new(Limited::new(body, max_body_size));
+
+    // 可重试的请求需要重放请求体：只缓存已知长度的小请求体，其余保持流式、只尝试一次
+    let retry = &runtime.retry;
+    retry.record_request(&route.service);
+    let mut request_body = match replayable_len(&headers, &body) {
+        Some(len)
+            if retry.is_eligible(&method, &headers) && len <= retry.max_buffered_body() as u64 =>
+        {
+            // 实际长度超过声明的 Content-Length 时由 Limited 截断，同样返回 413
+            let bytes = axum::body::to_bytes(body, retry.max_buffered_body())
+                .await
+                .map_err(|e| {
+                    if is_length_limit(&e) {
+                        payload_too_large(max_body_size)
+                    } else {
+                        ApiError(AppError::bad_request(format!("Failed to read body: {}", e)))
+                            .into_response()
+                    }
+                })?;
+            RequestBody::Replayable(bytes)
+        }
+        _ => RequestBody::Streaming(Some(body)),
     };
 
-    // 使用熔断器保护调用
-    let http_client = state.http_client.clone();
-    let headers_clone = headers.clone();
-    let target_url_clone = target_url.clone();
-    let service_clone = service.clone();
-    let body_clone = body.clone();
-
-    let result = state
-        .circuit_breaker
-        .call(&service.path_prefix, || async move {
-            forward_request(
-                &http_client,
-                &method,
-                &target_url_clone,
-                &headers_clone,
-                &body_clone,
-                &service_clone,
-            )
+    let target_path = route.target_path(path);
+    let breaker = &state.circuit_breaker;
+    let mut tried: Vec<Arc<Endpoint>> = Vec::new();
+    let mut attempt = 1;
+
+    loop {
+        // 选择上游实例（不健康或熔断器打开的实例视为已摘除，重试时优先换一个实例）
+        let endpoint = select_endpoint(&state, route, &headers, &tried)
             .await
-        })
-        .await;
+            .ok_or_else(|| {
+                tracing::warn!("No available upstream for service: {}", route.service);
+                service_unavailable()
+            })?;
+
+        // 构建目标 URL（按路由改写路径），保留查询字符串
+        let target_url = if let Some(query) = uri.query() {
+            format!("{}{}?{}", endpoint.url, target_path, query)
+        } else {
+            format!("{}{}", endpoint.url, target_path)
+        };
+
+        // 使用熔断器保护调用（每个实例独立熔断，每次尝试都计入统计）
+        let body = request_body.next();
+        let in_flight = endpoint.track();
+        let started = Instant::now();
+        let result = breaker
+            .call(
+                &endpoint.breaker_key,
+                || {
+                    forward_request(
+                        &state.http_client,
+                        method.clone(),
+                        &target_url,
+                        &headers,
+                        body,
+                        route,
+                        in_flight,
+                    )
+                },
+                // 上游返回 502/503/504 等状态码同样计为失败
+                |response: &Response| breaker.is_failure_status(response.status().as_u16()),
+            )
+            .await;
 
+        match &result {
+            Ok(response) => metrics::observe_upstream(
+                &route.service,
+                &endpoint.url,
+                Some(response.status().as_u16()),
+                started.elapsed(),
+            ),
+            Err(CircuitBreakerError::ServiceError(_)) => {
+                metrics::observe_upstream(&route.service, &endpoint.url, None, started.elapsed())
+            }
+            // 熔断器拒绝，没有实际调用上游
+            Err(CircuitBreakerError::Open) => {}
+        }
+
+        let retryable = match &result {
+            Ok(response) => retry.should_retry_status(response.status().as_u16()),
+            Err(_) => true,
+        };
+        if !retryable
+            || !request_body.is_replayable()
+            || attempt >= retry.max_attempts()
+            || !retry.try_acquire_retry(&route.service)
+        {
+            return Ok(final_response(result));
+        }
+
+        tracing::warn!(
+            "Retrying {} {} (attempt {} failed on {})",
+            method,
+            path,
+            attempt,
+            endpoint.url
+        );
+        tried.push(endpoint);
+        tokio::time::sleep(retry.backoff(attempt)).await;
+        attempt += 1;
+    }
+}
+
+/// 转发用的请求体
+enum RequestBody {
+    /// 已缓存，可以重放
+    Replayable(Bytes),
+    /// 流式透传，只能发送一次
+    Streaming(Option<Body>),
+}
+
+impl RequestBody {
+    fn next(&mut self) -> Body {
+        match self {
+            Self::Replayable(bytes) => Body::from(bytes.clone()),
+            Self::Streaming(body) => body.take().unwrap_or_else(Body::empty),
+        }
+    }
+
+    fn is_replayable(&self) -> bool {
+        matches!(self, Self::Replayable(_))
+    }
+}
+
+/// 把最后一次尝试的结果转换为响应（熔断打开返回 503，后端错误返回 502）
+///
+/// 后端错误的细节只记录日志，客户端只拿到通用提示
+fn final_response(result: Result<Response, CircuitBreakerError>) -> Response {
     match result {
-        Ok(response) => Ok(response),
-        Err(crate::middleware::circuit_breaker::CircuitBreakerError::Open) => Err((
-            StatusCode::SERVICE_UNAVAILABLE,
-            "Service temporarily unavailable",
-        )
-            .into_response()),
-        Err(crate::middleware::circuit_breaker::CircuitBreakerError::ServiceError(e)) => {
+        Ok(response) => response,
+        Err(CircuitBreakerError::Open) => service_unavailable(),
+        Err(CircuitBreakerError::ServiceError(e)) => {
             tracing::error!("Service call failed: {:?}", e);
-            Err((StatusCode::BAD_GATEWAY, format!("Backend error: {}", e)).into_response())
+            ApiError(AppError::bad_gateway("Upstream service error")).into_response()
+        }
+    }
+}
+
+fn service_unavailable() -> Response {
+    ApiError(AppError::service_unavailable(
+        "Service temporarily unavailable",
+    ))
+    .into_response()
+}
+
+/// 按路由的负载均衡策略选择可用实例，已尝试过的实例仅在没有其他可用实例时才会再次选中
+async fn select_endpoint(
+    state: &AppState,
+    route: &Route,
+    headers: &HeaderMap,
+    tried: &[Arc<Endpoint>],
+) -> Option<Arc<Endpoint>> {
+    // 同一次选择使用同一份实例快照，服务发现替换实例列表时下标依然对应
+    let members = route.upstream.members();
+    let endpoints = members.endpoints();
+    let mut available = Vec::with_capacity(endpoints.len());
+    for endpoint in endpoints {
+        available.push(
+            endpoint.is_healthy() && !state.circuit_breaker.is_open(&endpoint.breaker_key).await,
+        );
+    }
+    let untried: Vec<bool> = available
+        .iter()
+        .zip(endpoints)
+        .map(|(ok, endpoint)| *ok && !tried.iter().any(|t| Arc::ptr_eq(t, endpoint)))
+        .collect();
+
+    // 一致性哈希按用户 ID 分配（由 JWT 中间件注入）
+    let user_id = headers.get("x-user-id").and_then(|v| v.to_str().ok());
+    if untried.contains(&true) {
+        route.upstream.select(&members, &untried, user_id)
+    } else {
+        route.upstream.select(&members, &available, user_id)
+    }
+}
+
+/// 转发失败的原因
+#[derive(Debug)]
+enum ForwardError {
+    /// 连接或发送请求失败
+    Request(reqwest::Error),
+    /// 超时前没有收到上游的响应头
+    Timeout(Duration),
+}
+
+impl std::fmt::Display for ForwardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Request(e) => write!(f, "{}", e),
+            Self::Timeout(timeout) => {
+                write!(f, "No response headers within {}ms", timeout.as_millis())
+            }
         }
     }
 }
 
+impl std::error::Error for ForwardError {}
+
 /// 转发请求到后端服务
+///
+/// 路由超时只约束建立连接、发送请求到收到响应头；响应体按流透传，不受该超时限制
+/// （下载、SSE 等长响应不会被中途截断）
 async fn forward_request(
     client: &Client,
-    method: &Method,
+    method: Method,
     target_url: &str,
     headers: &HeaderMap,
-    body: &[u8],
-    service: &ServiceConfig,
-) -> Result<Response, reqwest::Error> {
-    // 根据 HTTP 方法构建请求
-    let mut request_builder = match *method {
-        Method::GET => client.get(target_url),
-        Method::POST => client.post(target_url),
-        Method::PUT => client.put(target_url),
-        Method::DELETE => client.delete(target_url),
-        Method::PATCH => client.patch(target_url),
-        Method::HEAD => client.head(target_url),
-        Method::OPTIONS => client.request(Method::OPTIONS, target_url),
-        _ => client.request(method.clone(), target_url),
-    };
-
-    request_builder = request_builder.timeout(Duration::from_secs(service.timeout_seconds));
+    body: Body,
+    route: &Route,
+    in_flight: InFlightGuard,
+) -> Result<Response, ForwardError> {
+    // 每次尝试一个 client span，上游服务的 span 挂在它下面
+    let span = tracing::info_span!(
+        "upstream",
+        otel.name = %format!("{} {}", method, route.service),
+        otel.kind = "client",
+        url.full = %target_url,
+        http.response.status_code = field::Empty,
+    );
 
     // 转发必要的请求头
+    let mut forward_headers = HeaderMap::with_capacity(headers.len());
     for (key, value) in headers.iter() {
-        if should_forward_header(key.as_str()) {
-            request_builder = request_builder.header(key, value);
+        if should_forward_header(headers, key) {
+            forward_headers.append(key, value.clone());
         }
     }
+    // 用网关的 trace 上下文替换客户端传入的 traceparent
+    common_tracing::inject_context(&span, &mut forward_headers);
+    // 告知上游本次尝试的超时，上游调用下游时据此缩短超时（覆盖客户端传入的值）
+    forward_headers.insert(
+        REQUEST_TIMEOUT_HEADER,
+        HeaderValue::from(route.timeout.as_millis() as u64),
+    );
 
-    // 添加请求体（如果有）
-    if !body.is_empty() {
-        request_builder = request_builder.body(body.to_vec());
-    }
+    // 流式转发请求体
+    let request_builder = client
+        .request(method, target_url)
+        .headers(forward_headers)
+        .body(reqwest::Body::wrap_stream(body.into_data_stream()));
 
-    let backend_response = request_builder.send().await?;
+    // 请求体超限属于客户端错误，直接返回 413，不计入熔断失败
+    let send = request_builder.send().instrument(span.clone());
+    let backend_response = match tokio::time::timeout(route.timeout, send).await {
+        Ok(Ok(response)) => response,
+        Ok(Err(e)) if is_length_limit(&e) => return Ok(payload_too_large(route.max_body_size)),
+        Ok(Err(e)) => return Err(ForwardError::Request(e)),
+        Err(_) => return Err(ForwardError::Timeout(route.timeout)),
+    };
 
-    // 构建响应
+    // 构建响应：状态码 + 过滤后的响应头 + 原样透传的字节流
     let status = backend_response.status();
-    let mut response_builder = Response::builder().status(status);
-
-    // 转发响应头
+    span.record("http.response.status_code", status.as_u16());
+    let mut response_headers = HeaderMap::with_capacity(backend_response.headers().len());
     for (key, value) in backend_response.headers().iter() {
-        if should_forward_header(key.as_str()) {
-            response_builder = response_builder.header(key, value);
+        if should_forward_header(backend_response.headers(), key) {
+            response_headers.append(key, value.clone());
         }
     }
 
-    let body = backend_response.text().await?;
-    Ok(response_builder.body(Body::from(body)).unwrap())
+    // 响应体传输完成（或客户端断开）后才释放进行中计数
+    let stream = backend_response.bytes_stream().map(move |chunk| {
+        let _ = &in_flight;
+        chunk
+    });
+    let mut response = Response::new(Body::from_stream(stream));
+    *response.status_mut() = status;
+    *response.headers_mut() = response_headers;
+    Ok(response)
 }
 
 /// 判断是否应该转发该请求头
-fn should_forward_header(key: &str) -> bool {
-    !matches!(
-        key.to_lowercase().as_str(),
-        "host" | "connection" | "transfer-encoding" | "content-length"
-    )
+///
+/// 过滤逐跳（hop-by-hop）头以及 `Connection` 中声明的头；
+/// `Content-Length` 保留，chunked 传输由下一跳重新协商
+fn should_forward_header(headers: &HeaderMap, key: &HeaderName) -> bool {
+    let is_hop_by_hop = matches!(
+        key.as_str(),
+        "host"
+            | "connection"
+            | "keep-alive"
+            | "proxy-authenticate"
+            | "proxy-authorization"
+            | "te"
+            | "trailer"
+            | "transfer-encoding"
+            | "upgrade"
+    );
+
+    let listed_in_connection = headers
+        .get_all(header::CONNECTION)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|name| name.trim().eq_ignore_ascii_case(key.as_str()));
+
+    !is_hop_by_hop && !listed_in_connection
+}
+
+/// 可缓存重放的请求体长度：chunked 请求无法预知长度，返回 `None`；
+/// 没有 Content-Length 时以请求体给出的确切长度为准，仍未知则返回 `None`（流式转发、不重试）
+fn replayable_len(headers: &HeaderMap, body: &Body) -> Option<u64> {
+    if headers.contains_key(header::TRANSFER_ENCODING) {
+        return None;
+    }
+    content_length(headers).or_else(|| body.size_hint().exact())
+}
+
+fn content_length(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())
+}
+
+/// 错误是否由请求体超限（`Limited` 截断）导致
+fn is_length_limit(err: &(dyn std::error::Error + 'static)) -> bool {
+    let mut source = Some(err);
+    while let Some(e) = source {
+        if e.is::<LengthLimitError>() {
+            return true;
+        }
+        source = e.source();
+    }
+    false
+}
+
+fn payload_too_large(max_body_size: usize) -> Response {
+    ApiError(AppError::payload_too_large(format!(
+        "Request body exceeds the {} byte limit",
+        max_body_size
+    )))
+    .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{HeaderMap, HeaderValue, StatusCode, header},
+    };
+    use common_core::error::code;
+    use futures_util::stream;
+    use http_body_util::Limited;
+
+    use super::{is_length_limit, payload_too_large, replayable_len};
+
+    #[test]
+    fn replays_only_bodies_of_known_length() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CONTENT_LENGTH, HeaderValue::from_static("5"));
+        assert_eq!(replayable_len(&headers, &Body::from("hello")), Some(5));
+
+        // 没有长度信息时以请求体的确切长度为准
+        assert_eq!(replayable_len(&HeaderMap::new(), &Body::empty()), Some(0));
+        assert_eq!(
+            replayable_len(&HeaderMap::new(), &Body::from("hi")),
+            Some(2)
+        );
+
+        // 长度未知的流不缓存
+        let streaming = Body::from_stream(stream::iter([Ok::<_, std::io::Error>("hi")]));
+        assert_eq!(replayable_len(&HeaderMap::new(), &streaming), None);
+
+        let mut chunked = HeaderMap::new();
+        chunked.insert(
+            header::TRANSFER_ENCODING,
+            HeaderValue::from_static("chunked"),
+        );
+        assert_eq!(replayable_len(&chunked, &Body::from("hi")), None);
+    }
+
+    #[tokio::test]
+    async fn detects_body_over_limit() {
+        // 请求体超过 Limited 上限
+        let body = Body::new(Limited::new(Body::from(vec![0u8; 16]), 8));
+        let err = axum::body::to_bytes(body, 1024).await.unwrap_err();
+        assert!(is_length_limit(&err));
+
+        // 超过缓冲上限
+        let err = axum::body::to_bytes(Body::from(vec![0u8; 16]), 8)
+            .await
+            .unwrap_err();
+        assert!(is_length_limit(&err));
+    }
+
+    #[tokio::test]
+    async fn gateway_errors_use_the_error_envelope() {
+        let response = payload_too_large(8);
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let body = axum::body::to_bytes(response.into_body(), 1024)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["code"], code::PAYLOAD_TOO_LARGE);
+        assert_eq!(body["message"], "Request body exceeds the 8 byte limit");
+    }
 }
@@ -0,0 +1,203 @@
+use common_config::ConfigLoader;
+use common_web::runner::Lifecycle;
+use std::{path::PathBuf, time::Duration};
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+
+use crate::{AppState, config::application::AppConfig, startup::build_runtime};
+
+/// 配置文件检查间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 启动配置热更新任务：定期比较配置文件内容，内容变化或收到 SIGHUP 时重新加载
+///
+/// 基础文件和 profile 文件一起比较；按内容而不是修改时间比较，
+/// 编辑器整体替换文件、Kubernetes ConfigMap 切换软链接等情况都能识别；收到停机通知后退出
+pub fn spawn_config_watcher(
+    state: AppState,
+    loader: ConfigLoader,
+    lifecycle: Lifecycle,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut last = snapshot(&loader).await.ok();
+        let mut ticker = tokio::time::interval(POLL_INTERVAL);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut hangup = hangup_signal();
+
+        if let Ok(files) = loader.files() {
+            tracing::info!("Watching gateway config for changes: {:?}", files);
+        }
+
+        loop {
+            // SIGHUP 时即使内容未变也重新加载
+            let forced = tokio::select! {
+                _ = ticker.tick() => false,
+                _ = wait_hangup(&mut hangup) => true,
+                _ = lifecycle.shutdown() => break,
+            };
+
+            let content = match snapshot(&loader).await {
+                Ok(content) => content,
+                Err(e) => {
+                    if forced {
+                        tracing::error!("Failed to read gateway config: {}", e);
+                    }
+                    continue;
+                }
+            };
+            if !forced && last.as_ref() == Some(&content) {
+                continue;
+            }
+            last = Some(content);
+
+            if forced {
+                tracing::info!("Received SIGHUP, reloading gateway config");
+            }
+            reload(&state, &loader).await;
+        }
+    })
+}
+
+/// 当前参与合并的所有配置文件内容（profile 文件新增或删除也视为变化）
+async fn snapshot(loader: &ConfigLoader) -> Result<Vec<(PathBuf, Vec<u8>)>, String> {
+    let mut contents = Vec::new();
+    for file in loader.files().map_err(|e| e.to_string())? {
+        let content = tokio::fs::read(&file)
+            .await
+            .map_err(|e| format!("{}: {}", file.display(), e))?;
+        contents.push((file, content));
+    }
+    Ok(contents)
+}
+
+/// 解析并编译新配置，成功后原子替换；失败时保留当前配置
+async fn reload(state: &AppState, loader: &ConfigLoader) {
+    let result = loader
+        .load::<AppConfig>()
+        .and_then(|config| build_runtime(config, Some(&state.runtime()), state.discovery.as_ref()));
+
+    match result {
+        Ok(runtime) => {
+            let level = runtime.app_config.logs.level.clone();
+            let level_changed = state.runtime().app_config.logs.level != level;
+            runtime.rate_limiter.register_pool_metrics();
+            state.replace_runtime(runtime);
+            state.prune_circuit_breakers().await;
+            // 配置文件中的日志级别变化时一并生效（覆盖通过管理接口设置的级别）
+            if level_changed && let Err(e) = common_tracing::set_log_level(&level) {
+                tracing::error!("{}", e);
+            }
+            tracing::info!("Gateway config reloaded");
+        }
+        Err(e) => {
+            tracing::error!(
+                "Rejected invalid gateway config, keeping the current one: {}",
+                e
+            );
+        }
+    }
+}
+
+#[cfg(unix)]
+type HangupSignal = Option<tokio::signal::unix::Signal>;
+#[cfg(not(unix))]
+type HangupSignal = ();
+
+#[cfg(unix)]
+fn hangup_signal() -> HangupSignal {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    signal(SignalKind::hangup())
+        .map_err(|e| tracing::warn!("Failed to listen for SIGHUP: {}", e))
+        .ok()
+}
+
+#[cfg(not(unix))]
+fn hangup_signal() -> HangupSignal {}
+
+#[cfg(unix)]
+async fn wait_hangup(hangup: &mut HangupSignal) {
+    match hangup {
+        Some(signal) => {
+            signal.recv().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_hangup(_: &mut HangupSignal) {
+    std::future::pending().await
+}
+
+#[cfg(test)]
+mod tests {
+    use common_config::ConfigLoader;
+    use reqwest::Client;
+    use std::{path::PathBuf, sync::Arc, time::Duration};
+
+    use super::reload;
+    use crate::{
+        AppState, config::application::AppConfig,
+        middleware::circuit_breaker::CircuitBreakerManager, startup::build_runtime,
+    };
+
+    /// 仓库中的网关配置，密钥替换为固定值
+    fn base_config() -> String {
+        std::fs::read_to_string("application.yaml")
+            .unwrap()
+            .replace("${JWT_SECRET}", "test-jwt-secret")
+            .replace("${INTERNAL_IDENTITY_SECRET}", "test-identity-secret")
+    }
+
+    fn write(path: &PathBuf, content: &str) {
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[tokio::test]
+    async fn keeps_current_runtime_when_new_config_is_invalid() {
+        let dir = std::env::temp_dir().join(format!("gateway-reload-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("application.yaml");
+        write(&path, &base_config());
+
+        let loader = ConfigLoader::new(&path);
+        let config: AppConfig = loader.load().unwrap();
+        let state = AppState::new(
+            Client::new(),
+            CircuitBreakerManager::new(config.circuit_breaker.clone()),
+            None,
+            build_runtime(config, None, None).unwrap(),
+        );
+        let current = state.runtime();
+
+        // YAML 语法错误、字段缺失、路由校验失败都保留当前配置
+        for invalid in [
+            "services: [oops".to_string(),
+            base_config().replace("jwt:", "jwt_renamed:"),
+            base_config().replace("path_prefix: \"/api/user\"", "path_prefix: \"api/user\""),
+        ] {
+            assert_ne!(invalid, base_config());
+            write(&path, &invalid);
+            reload(&state, &loader).await;
+            assert!(Arc::ptr_eq(&state.runtime(), &current));
+        }
+
+        // 有效配置整体替换
+        write(
+            &path,
+            &base_config().replace(
+                "path_prefix: \"/api/user\"\n    timeout_seconds: 10",
+                "path_prefix: \"/api/user\"\n    timeout_seconds: 20",
+            ),
+        );
+        reload(&state, &loader).await;
+        let runtime = state.runtime();
+        assert!(!Arc::ptr_eq(&runtime, &current));
+        assert_eq!(
+            runtime.route_table.find("/api/user/1").unwrap().timeout,
+            Duration::from_secs(20)
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,210 @@
+use axum::http::{HeaderMap, Method};
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::config::application::RetryConfig;
+
+/// 幂等键请求头，携带该头的非幂等请求也允许重试
+pub const IDEMPOTENCY_KEY: &str = "idempotency-key";
+
+/// 预算最多积累多少秒的保底额度，避免长时间空闲后突发大量重试
+const BUDGET_BURST_SECONDS: f64 = 10.0;
+
+/// 重试策略（每个服务一份重试预算）
+pub struct RetryPolicy {
+    config: RetryConfig,
+    budgets: HashMap<String, RetryBudget>,
+}
+
+impl RetryPolicy {
+    pub fn new<'a>(config: RetryConfig, services: impl IntoIterator<Item = &'a String>) -> Self {
+        let budgets = services
+            .into_iter()
+            .map(|service| (service.clone(), RetryBudget::new(&config)))
+            .collect();
+        Self { config, budgets }
+    }
+
+    /// 请求是否允许重试：幂等方法，或携带幂等键
+    pub fn is_eligible(&self, method: &Method, headers: &HeaderMap) -> bool {
+        if !self.config.enabled || self.config.max_attempts <= 1 {
+            return false;
+        }
+        let idempotent = matches!(
+            *method,
+            Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS
+        );
+        idempotent || headers.contains_key(IDEMPOTENCY_KEY)
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.config.max_attempts
+    }
+
+    pub fn max_buffered_body(&self) -> usize {
+        self.config.max_buffered_body
+    }
+
+    pub fn should_retry_status(&self, status: u16) -> bool {
+        self.config.retry_on_status.contains(&status)
+    }
+
+    /// 记录一次正常请求，为服务积累重试额度
+    pub fn record_request(&self, service: &str) {
+        if let Some(budget) = self.budgets.get(service) {
+            budget.deposit();
+        }
+    }
+
+    /// 尝试消耗一次重试额度，预算耗尽时返回 false
+    pub fn try_acquire_retry(&self, service: &str) -> bool {
+        self.budgets
+            .get(service)
+            .is_some_and(|budget| budget.try_withdraw())
+    }
+
+    /// 第 `attempt` 次失败后的等待时间（指数退避 + 全抖动）
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exp = self
+            .config
+            .base_delay_ms
+            .saturating_mul(1u64 << attempt.saturating_sub(1).min(16));
+        let cap = exp.min(self.config.max_delay_ms);
+        Duration::from_millis(rand::random_range(0..=cap))
+    }
+}
+
+/// 令牌桶式重试预算：正常请求按比例存入，重试时取出 1 个
+struct RetryBudget {
+    ratio: f64,
+    min_per_second: f64,
+    capacity: f64,
+    state: Mutex<BudgetState>,
+}
+
+struct BudgetState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RetryBudget {
+    fn new(config: &RetryConfig) -> Self {
+        let capacity = (config.budget_min_per_second * BUDGET_BURST_SECONDS).max(1.0);
+        Self {
+            ratio: config.budget_ratio,
+            min_per_second: config.budget_min_per_second,
+            capacity,
+            state: Mutex::new(BudgetState {
+                tokens: capacity,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BudgetState> {
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let elapsed = state.last_refill.elapsed().as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.min_per_second).min(self.capacity);
+        state.last_refill = Instant::now();
+        state
+    }
+
+    fn deposit(&self) {
+        let mut state = self.lock();
+        state.tokens = (state.tokens + self.ratio).min(self.capacity);
+    }
+
+    fn try_withdraw(&self) -> bool {
+        let mut state = self.lock();
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{HeaderMap, HeaderValue, Method};
+    use std::time::{Duration, Instant};
+
+    use super::{IDEMPOTENCY_KEY, RetryPolicy};
+    use crate::config::application::RetryConfig;
+
+    fn policy(config: RetryConfig) -> RetryPolicy {
+        RetryPolicy::new(config, &["user".to_string()])
+    }
+
+    #[test]
+    fn retries_only_idempotent_requests() {
+        let policy = policy(RetryConfig::default());
+        let mut headers = HeaderMap::new();
+        assert!(policy.is_eligible(&Method::GET, &headers));
+        assert!(policy.is_eligible(&Method::DELETE, &headers));
+        assert!(!policy.is_eligible(&Method::POST, &headers));
+
+        headers.insert(IDEMPOTENCY_KEY, HeaderValue::from_static("order-1"));
+        assert!(policy.is_eligible(&Method::POST, &headers));
+
+        let disabled = RetryPolicy::new(
+            RetryConfig {
+                enabled: false,
+                ..RetryConfig::default()
+            },
+            &[],
+        );
+        assert!(!disabled.is_eligible(&Method::GET, &HeaderMap::new()));
+    }
+
+    #[test]
+    fn backoff_grows_exponentially_up_to_the_cap() {
+        let policy = policy(RetryConfig {
+            base_delay_ms: 50,
+            max_delay_ms: 300,
+            ..RetryConfig::default()
+        });
+
+        // 全抖动：延迟在 [0, min(base * 2^(n-1), max)] 之间
+        for (attempt, cap) in [(1, 50), (2, 100), (3, 200), (4, 300), (40, 300)] {
+            let max = (0..200).map(|_| policy.backoff(attempt)).max().unwrap();
+            assert!(max <= Duration::from_millis(cap), "attempt {}", attempt);
+            assert!(max > Duration::from_millis(cap / 2), "attempt {}", attempt);
+        }
+    }
+
+    #[test]
+    fn retry_budget_runs_out_and_refills() {
+        let policy = policy(RetryConfig {
+            budget_ratio: 0.5,
+            budget_min_per_second: 0.1,
+            ..RetryConfig::default()
+        });
+
+        // 初始额度为 1（保底额度 10 秒的积累，至少 1 次）
+        assert!(policy.try_acquire_retry("user"));
+        assert!(!policy.try_acquire_retry("user"));
+
+        // 每个正常请求存入 0.5 次
+        policy.record_request("user");
+        assert!(!policy.try_acquire_retry("user"));
+        policy.record_request("user");
+        assert!(policy.try_acquire_retry("user"));
+        assert!(!policy.try_acquire_retry("user"));
+
+        // 空闲时按保底速率恢复
+        policy.budgets["user"].state.lock().unwrap().last_refill =
+            Instant::now() - Duration::from_secs(10);
+        assert!(policy.try_acquire_retry("user"));
+
+        // 未知服务没有预算
+        assert!(!policy.try_acquire_retry("article"));
+    }
+}
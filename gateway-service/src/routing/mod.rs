@@ -0,0 +1,518 @@
+use axum::http::Method;
+use common_core::AppError;
+use reqwest::Url;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+
+use crate::{
+    balancer::UpstreamPool,
+    config::application::{RateLimitQuota, ServiceConfig},
+};
+
+/// 编译后的单条路由
+#[derive(Debug, Clone)]
+pub struct Route {
+    /// 所属服务名（同时作为熔断器 key）
+    pub service: String,
+    /// 上游实例池（同一服务的路由共享）
+    pub upstream: Arc<UpstreamPool>,
+    /// 规范化后的路径前缀，不带末尾 `/`
+    pub prefix: String,
+    /// 转发时替换前缀的路径
+    rewrite: String,
+    /// 允许的方法，为空表示不限制
+    methods: Vec<Method>,
+    pub timeout: Duration,
+    pub max_body_size: usize,
+    pub auth: Option<bool>,
+    /// 限流配额，`None` 时使用全局默认配额
+    pub rate_limit: Option<RateLimitQuota>,
+}
+
+impl Route {
+    /// 是否允许该方法
+    pub fn allows(&self, method: &Method) -> bool {
+        self.methods.is_empty() || self.methods.contains(method)
+    }
+
+    /// `Allow` 响应头的值
+    pub fn allow_header(&self) -> String {
+        self.methods
+            .iter()
+            .map(Method::as_str)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// 计算转发到上游的路径（不含查询字符串）
+    pub fn target_path(&self, path: &str) -> String {
+        let rest = if self.prefix == "/" {
+            path
+        } else {
+            path.strip_prefix(&self.prefix).unwrap_or(path)
+        };
+
+        match (self.rewrite.as_str(), rest) {
+            ("", "") => "/".to_string(),
+            ("", rest) => rest.to_string(),
+            (rewrite, rest) => format!("{}{}", rewrite, rest),
+        }
+    }
+
+    /// 按路径段匹配：`/api/user` 匹配 `/api/user` 和 `/api/user/x`，不匹配 `/api/userX`
+    fn matches(&self, path: &str) -> bool {
+        if self.prefix == "/" {
+            return true;
+        }
+        path.strip_prefix(&self.prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    }
+}
+
+/// 网关路由表（最长前缀优先）
+#[derive(Debug, Clone, Default)]
+pub struct RouteTable {
+    routes: Vec<Route>,
+    /// 服务名 -> 上游实例池
+    upstreams: BTreeMap<String, Arc<UpstreamPool>>,
+}
+
+impl RouteTable {
+    /// 从服务配置编译路由表，配置有误时列出所有问题
+    pub fn compile(services: &HashMap<String, ServiceConfig>) -> Result<Self, AppError> {
+        let mut routes = Vec::new();
+        let mut errors = Vec::new();
+
+        // 按服务名排序，保证错误信息稳定
+        let mut names: Vec<&String> = services.keys().collect();
+        names.sort();
+
+        for name in names {
+            let service = &services[name];
+            let ctx = format!("services.{}", name);
+
+            let upstream = match &service.discovery {
+                Some(discovery) => check(
+                    &mut errors,
+                    &ctx,
+                    "discovery",
+                    validate_discovery(discovery, &service.upstreams),
+                )
+                .map(|()| {
+                    Arc::new(UpstreamPool::discovered(
+                        name,
+                        discovery,
+                        service.load_balance,
+                    ))
+                }),
+                None => check(
+                    &mut errors,
+                    &ctx,
+                    "upstreams",
+                    parse_upstreams(&service.upstreams),
+                )
+                .map(|urls| Arc::new(UpstreamPool::new(name, urls, service.load_balance))),
+            };
+            let service_prefix = check(
+                &mut errors,
+                &ctx,
+                "path_prefix",
+                normalize_prefix(&service.path_prefix),
+            );
+            let service_rewrite = check(
+                &mut errors,
+                &ctx,
+                "rewrite",
+                normalize_rewrite(service.rewrite.as_deref()),
+            )
+            .unwrap_or_default();
+            let service_methods = check(
+                &mut errors,
+                &ctx,
+                "methods",
+                parse_methods(&service.methods),
+            )
+            .unwrap_or_default();
+            if service.timeout_seconds == 0 {
+                errors.push(format!("{}.timeout_seconds: must be greater than 0", ctx));
+            }
+            if let Some(quota) = &service.rate_limit {
+                check(&mut errors, &ctx, "rate_limit", validate_quota(quota));
+            }
+            // 地址或前缀无效时无法继续校验子路由
+            let (Some(upstream), Some(service_prefix)) = (upstream, service_prefix) else {
+                continue;
+            };
+
+            for (i, sub) in service.routes.iter().enumerate() {
+                let sub_ctx = format!("{}.routes[{}]", ctx, i);
+                let Some(prefix) = check(
+                    &mut errors,
+                    &sub_ctx,
+                    "path_prefix",
+                    normalize_prefix(&sub.path_prefix),
+                ) else {
+                    continue;
+                };
+                let Some(relative) = strip_segment_prefix(&prefix, &service_prefix) else {
+                    errors.push(format!(
+                        "{}.path_prefix: `{}` is not under the service prefix `{}`",
+                        sub_ctx, prefix, service_prefix
+                    ));
+                    continue;
+                };
+
+                // 未配置 rewrite 时沿用服务级改写，保持子路径不变
+                let rewrite = match &sub.rewrite {
+                    Some(_) => check(
+                        &mut errors,
+                        &sub_ctx,
+                        "rewrite",
+                        normalize_rewrite(sub.rewrite.as_deref()),
+                    )
+                    .unwrap_or_default(),
+                    None => format!("{}{}", service_rewrite, relative),
+                };
+                let methods = if sub.methods.is_empty() {
+                    service_methods.clone()
+                } else {
+                    check(
+                        &mut errors,
+                        &sub_ctx,
+                        "methods",
+                        parse_methods(&sub.methods),
+                    )
+                    .unwrap_or_default()
+                };
+                let timeout_seconds = sub.timeout_seconds.unwrap_or(service.timeout_seconds);
+                if timeout_seconds == 0 {
+                    errors.push(format!(
+                        "{}.timeout_seconds: must be greater than 0",
+                        sub_ctx
+                    ));
+                }
+                if let Some(quota) = &sub.rate_limit {
+                    check(&mut errors, &sub_ctx, "rate_limit", validate_quota(quota));
+                }
+
+                routes.push((
+                    sub_ctx,
+                    Route {
+                        service: name.clone(),
+                        upstream: upstream.clone(),
+                        prefix,
+                        rewrite,
+                        methods,
+                        timeout: Duration::from_secs(timeout_seconds),
+                        max_body_size: sub.max_body_size.unwrap_or(service.max_body_size),
+                        auth: sub.auth.or(service.auth),
+                        rate_limit: sub.rate_limit.clone().or(service.rate_limit.clone()),
+                    },
+                ));
+            }
+
+            routes.push((
+                ctx,
+                Route {
+                    service: name.clone(),
+                    upstream,
+                    prefix: service_prefix,
+                    rewrite: service_rewrite,
+                    methods: service_methods,
+                    timeout: Duration::from_secs(service.timeout_seconds),
+                    max_body_size: service.max_body_size,
+                    auth: service.auth,
+                    rate_limit: service.rate_limit.clone(),
+                },
+            ));
+        }
+
+        // 同一前缀只能属于一条路由
+        let mut owners: HashMap<&str, &str> = HashMap::new();
+        for (ctx, route) in &routes {
+            if let Some(owner) = owners.insert(&route.prefix, ctx) {
+                errors.push(format!(
+                    "path_prefix `{}` is declared by both `{}` and `{}`",
+                    route.prefix, owner, ctx
+                ));
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(AppError::internal(format!(
+                "Invalid gateway route config:\n  - {}",
+                errors.join("\n  - ")
+            )));
+        }
+
+        // 前缀越长越具体，优先匹配
+        let mut routes: Vec<Route> = routes.into_iter().map(|(_, route)| route).collect();
+        routes.sort_by_key(|route| std::cmp::Reverse(route.prefix.len()));
+        let upstreams = routes
+            .iter()
+            .map(|route| (route.service.clone(), route.upstream.clone()))
+            .collect();
+        Ok(Self { routes, upstreams })
+    }
+
+    /// 沿用旧路由表中未变化的实例池（配置热更新时保留健康状态、进行中计数和已发现的实例）
+    pub fn reuse_upstreams(&mut self, previous: &RouteTable) {
+        for (service, pool) in self.upstreams.iter_mut() {
+            if let Some(old) = previous.upstreams.get(service)
+                && old.same_upstreams(pool)
+            {
+                *pool = old.clone();
+            }
+        }
+        for route in &mut self.routes {
+            route.upstream = self.upstreams[&route.service].clone();
+        }
+    }
+
+    /// 所有服务的上游实例池（按服务名排序）
+    pub fn upstreams(&self) -> &BTreeMap<String, Arc<UpstreamPool>> {
+        &self.upstreams
+    }
+
+    /// 查找最具体的匹配路由
+    pub fn find(&self, path: &str) -> Option<&Route> {
+        self.routes.iter().find(|route| route.matches(path))
+    }
+}
+
+fn check<T>(
+    errors: &mut Vec<String>,
+    ctx: &str,
+    field: &str,
+    result: Result<T, String>,
+) -> Option<T> {
+    result
+        .map_err(|e| errors.push(format!("{}.{}: {}", ctx, field, e)))
+        .ok()
+}
+
+fn parse_upstreams(urls: &[String]) -> Result<Vec<String>, String> {
+    if urls.is_empty() {
+        return Err("at least one upstream is required".to_string());
+    }
+    let mut seen = HashSet::new();
+    urls.iter()
+        .map(|url| {
+            let url = parse_upstream(url)?;
+            if !seen.insert(url.clone()) {
+                return Err(format!("`{}` is listed more than once", url));
+            }
+            Ok(url)
+        })
+        .collect()
+}
+
+fn validate_discovery(discovery: &str, upstreams: &[String]) -> Result<(), String> {
+    if discovery.trim().is_empty() {
+        return Err("must not be empty".to_string());
+    }
+    if !upstreams.is_empty() {
+        return Err("cannot be combined with upstreams".to_string());
+    }
+    Ok(())
+}
+
+fn parse_upstream(url: &str) -> Result<String, String> {
+    let parsed = Url::parse(url).map_err(|e| format!("`{}` is not a valid URL ({})", url, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!("`{}` must use http or https", url));
+    }
+    if parsed.query().is_some() || parsed.fragment().is_some() {
+        return Err(format!("`{}` must not contain a query or fragment", url));
+    }
+    Ok(url.trim_end_matches('/').to_string())
+}
+
+/// 前缀必须以 `/` 开头，去掉末尾 `/`，不允许空段、`.`/`..` 和通配符
+fn normalize_prefix(prefix: &str) -> Result<String, String> {
+    if !prefix.starts_with('/') {
+        return Err(format!("`{}` must start with `/`", prefix));
+    }
+    let trimmed = prefix.trim_end_matches('/');
+    if trimmed.is_empty() {
+        return Ok("/".to_string());
+    }
+    let invalid_segment = trimmed[1..]
+        .split('/')
+        .any(|seg| seg.is_empty() || seg == "." || seg == "..");
+    if invalid_segment || trimmed.contains(['?', '#', '*', '{', '}']) {
+        return Err(format!("`{}` is not a valid path prefix", prefix));
+    }
+    Ok(trimmed.to_string())
+}
+
+/// 改写路径为空或以 `/` 开头的路径，去掉末尾 `/`
+fn normalize_rewrite(rewrite: Option<&str>) -> Result<String, String> {
+    match rewrite {
+        None => Ok(String::new()),
+        Some(r) => normalize_prefix(r).map(|p| if p == "/" { String::new() } else { p }),
+    }
+}
+
+/// 限流按微秒计算令牌间隔，速率超过每秒 1_000_000 时间隔为 0
+const MAX_REQUESTS_PER_SECOND: u32 = 1_000_000;
+
+/// 配额的速率和突发量都必须大于 0，速率不能超过 [`MAX_REQUESTS_PER_SECOND`]
+pub fn validate_quota(quota: &RateLimitQuota) -> Result<(), String> {
+    if quota.requests_per_second == 0 || quota.burst_size == 0 {
+        return Err("requests_per_second and burst_size must be greater than 0".to_string());
+    }
+    if quota.requests_per_second > MAX_REQUESTS_PER_SECOND {
+        return Err(format!(
+            "requests_per_second must not exceed {}",
+            MAX_REQUESTS_PER_SECOND
+        ));
+    }
+    Ok(())
+}
+
+fn parse_methods(methods: &[String]) -> Result<Vec<Method>, String> {
+    methods
+        .iter()
+        .map(|m| {
+            Method::from_bytes(m.to_ascii_uppercase().as_bytes())
+                .map_err(|_| format!("`{}` is not a valid HTTP method", m))
+        })
+        .collect()
+}
+
+/// 按路径段去掉父前缀，返回剩余部分（以 `/` 开头或为空）
+fn strip_segment_prefix<'a>(path: &'a str, parent: &str) -> Option<&'a str> {
+    if parent == "/" {
+        return Some(path);
+    }
+    path.strip_prefix(parent)
+        .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::Method;
+    use std::collections::HashMap;
+
+    use super::RouteTable;
+    use crate::config::application::ServiceConfig;
+
+    fn services(yaml: &str) -> HashMap<String, ServiceConfig> {
+        serde_yml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn reports_every_invalid_field() {
+        let err = RouteTable::compile(&services(
+            r#"
+            article:
+              upstreams: ["ftp://127.0.0.1:8002"]
+              path_prefix: api/article
+              timeout_seconds: 0
+            user:
+              upstreams: ["http://127.0.0.1:8001"]
+              discovery: user-service
+              path_prefix: /api/user
+              timeout_seconds: 5
+              methods: ["GET", "NOT A METHOD"]
+              routes:
+                - path_prefix: /api/article/x
+            web3:
+              upstreams: ["http://127.0.0.1:8003", "http://127.0.0.1:8003/"]
+              path_prefix: /api/web3
+              timeout_seconds: 5
+              rate_limit:
+                requests_per_second: 2000000
+                burst_size: 10
+              routes:
+                - path_prefix: /api/web3x
+            "#,
+        ))
+        .unwrap_err()
+        .to_string();
+
+        for expected in [
+            "services.article.upstreams: `ftp://127.0.0.1:8002` must use http or https",
+            "services.article.path_prefix: `api/article` must start with `/`",
+            "services.article.timeout_seconds: must be greater than 0",
+            "services.user.discovery: cannot be combined with upstreams",
+            "services.user.methods: `NOT A METHOD` is not a valid HTTP method",
+            "services.web3.upstreams: `http://127.0.0.1:8003` is listed more than once",
+            "services.web3.rate_limit: requests_per_second must not exceed 1000000",
+        ] {
+            assert!(err.contains(expected), "missing `{}` in {}", expected, err);
+        }
+
+        let err = RouteTable::compile(&services(
+            r#"
+            user:
+              upstreams: ["http://127.0.0.1:8001"]
+              path_prefix: /api/user
+              timeout_seconds: 5
+              routes:
+                - path_prefix: /api/username
+            auth:
+              upstreams: ["http://127.0.0.1:8000"]
+              path_prefix: /api/user/
+              timeout_seconds: 5
+            "#,
+        ))
+        .unwrap_err()
+        .to_string();
+
+        assert!(err.contains(
+            "services.user.routes[0].path_prefix: `/api/username` is not under the service prefix `/api/user`"
+        ));
+        assert!(err.contains("path_prefix `/api/user` is declared by both"));
+    }
+
+    #[test]
+    fn matches_whole_path_segments() {
+        let table = RouteTable::compile(&services(
+            r#"
+            user:
+              upstreams: ["http://127.0.0.1:8001"]
+              path_prefix: /api/user
+              timeout_seconds: 5
+              routes:
+                - path_prefix: /api/user/avatar
+                  rewrite: /upload
+                  methods: [post]
+            username:
+              upstreams: ["http://127.0.0.1:8002"]
+              path_prefix: /api/username
+              rewrite: /v1
+              timeout_seconds: 5
+            "#,
+        ))
+        .unwrap();
+
+        let service = |path: &str| table.find(path).map(|route| route.service.as_str());
+        assert_eq!(service("/api/user"), Some("user"));
+        assert_eq!(service("/api/user/x"), Some("user"));
+        assert_eq!(service("/api/username"), Some("username"));
+        assert_eq!(service("/api/username/x"), Some("username"));
+        assert_eq!(service("/api/userx"), None);
+        assert_eq!(service("/other"), None);
+
+        // 更具体的子路由优先，并按子路由的规则改写路径和限制方法
+        let avatar = table.find("/api/user/avatar/1").unwrap();
+        assert_eq!(avatar.prefix, "/api/user/avatar");
+        assert_eq!(avatar.target_path("/api/user/avatar/1"), "/upload/1");
+        assert!(avatar.allows(&Method::POST));
+        assert!(!avatar.allows(&Method::GET));
+        assert_eq!(avatar.allow_header(), "POST");
+
+        let user = table.find("/api/user/1").unwrap();
+        assert_eq!(user.target_path("/api/user/1"), "/1");
+        assert_eq!(user.target_path("/api/user"), "/");
+        assert!(user.allows(&Method::DELETE));
+
+        let username = table.find("/api/username/x").unwrap();
+        assert_eq!(username.target_path("/api/username/x"), "/v1/x");
+    }
+}
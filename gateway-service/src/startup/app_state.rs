@@ -1,7 +1,18 @@
+use arc_swap::ArcSwap;
 use reqwest::Client;
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
+use tower_http::cors::CorsLayer;
 
-use crate::{config::application::AppConfig, middleware::circuit_breaker::CircuitBreakerManager};
+use crate::{
+    config::application::AppConfig,
+    discovery::Discovery,
+    middleware::{
+        circuit_breaker::CircuitBreakerManager, client_ip::ClientIpResolver,
+        rate_limit::RateLimiter,
+    },
+    retry::RetryPolicy,
+    routing::RouteTable,
+};
 
 /// 网关应用状态
 #[derive(Clone)]
@@ -12,6 +23,77 @@ pub struct AppState {
     /// 熔断器管理器
     pub circuit_breaker: CircuitBreakerManager,
 
+    /// 服务发现（配置了 `registry` 时）
+    pub discovery: Option<Discovery>,
+
+    /// 当前生效的配置快照（热更新时整体替换）
+    runtime: Arc<ArcSwap<Runtime>>,
+}
+
+/// 由配置文件编译出的运行时配置
+///
+/// 每个请求开始时取一次快照，处理过程中始终使用同一份；
+/// 热更新只影响新请求，进行中的请求继续使用旧快照直至完成
+pub struct Runtime {
+    /// 路由表（由 services 配置编译）
+    pub route_table: RouteTable,
+
+    /// 重试策略与各服务的重试预算
+    pub retry: RetryPolicy,
+
+    /// 限流器（Redis 共享计数，不可用时降级为本地计数）
+    pub rate_limiter: RateLimiter,
+
+    /// 客户端 IP 解析（可信代理列表）
+    pub client_ip: ClientIpResolver,
+
+    /// CORS 规则
+    pub cors: CorsLayer,
+
     /// 配置
-    pub app_config: Arc<AppConfig>,
+    pub app_config: AppConfig,
+}
+
+impl AppState {
+    pub fn new(
+        http_client: Client,
+        circuit_breaker: CircuitBreakerManager,
+        discovery: Option<Discovery>,
+        runtime: Runtime,
+    ) -> Self {
+        Self {
+            http_client,
+            circuit_breaker,
+            discovery,
+            runtime: Arc::new(ArcSwap::from_pointee(runtime)),
+        }
+    }
+
+    /// 当前配置快照
+    pub fn runtime(&self) -> Arc<Runtime> {
+        self.runtime.load_full()
+    }
+
+    /// 原子替换配置快照
+    pub fn replace_runtime(&self, runtime: Runtime) {
+        self.runtime.store(Arc::new(runtime));
+    }
+
+    /// 清理已移除实例的熔断器：热更新或服务发现替换实例列表后调用，保留仍在使用的实例的状态
+    pub async fn prune_circuit_breakers(&self) {
+        let names: HashSet<String> = self
+            .runtime()
+            .route_table
+            .upstreams()
+            .values()
+            .flat_map(|pool| {
+                pool.members()
+                    .endpoints()
+                    .iter()
+                    .map(|endpoint| endpoint.breaker_key.clone())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        self.circuit_breaker.retain(&names).await;
+    }
 }
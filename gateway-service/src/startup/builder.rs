@@ -1,16 +1,26 @@
+use common_config::ConfigLoader;
 use common_core::AppError;
+use common_registry::RegistryClient;
 use reqwest::Client;
-use std::sync::Arc;
 
 use crate::{
-    config::application::AppConfig, middleware::circuit_breaker::CircuitBreakerManager,
+    config::application::AppConfig,
+    discovery::Discovery,
+    middleware::{
+        circuit_breaker::{self, CircuitBreakerManager, spawn_event_logger},
+        client_ip::ClientIpResolver,
+        cors::build_cors_layer,
+        rate_limit::RateLimiter,
+    },
+    retry::RetryPolicy,
+    routing::RouteTable,
 };
 
-use super::AppState;
+use super::{AppState, app_state::Runtime};
 
 /// 加载应用配置
-pub fn init_app_config() -> Result<AppConfig, AppError> {
-    AppConfig::from_default_yaml()
+pub fn init_app_config(loader: &ConfigLoader) -> Result<AppConfig, AppError> {
+    loader.load()
 }
 
 /// 初始化应用状态
@@ -22,15 +32,82 @@ pub async fn init_app_state(app_config: AppConfig) -> Result<AppState, AppError>
         .build()
         .map_err(|e| AppError::internal(format!("Failed to create HTTP client: {}", e)))?;
 
-    // 熔断器管理器
-    let circuit_breaker = CircuitBreakerManager::new(
-        app_config.circuit_breaker.failure_threshold,
-        app_config.circuit_breaker.timeout_seconds,
-    );
+    // 熔断器管理器（按上游实例统计，配置热更新时保留状态）
+    let circuit_breaker = CircuitBreakerManager::new(app_config.circuit_breaker.clone());
+    spawn_event_logger(circuit_breaker.subscribe());
 
-    Ok(AppState {
+    // 服务发现（注册中心配置不参与热更新）
+    let discovery = app_config
+        .registry
+        .as_ref()
+        .map(RegistryClient::new)
+        .transpose()?
+        .map(Discovery::new);
+
+    // 配置快照（配置有误时拒绝启动），服务发现的实例池在开始接收请求前先解析一次
+    let runtime = build_runtime(app_config, None, discovery.as_ref())?;
+    runtime.rate_limiter.register_pool_metrics();
+    if let Some(discovery) = &discovery {
+        discovery.refresh(&runtime.route_table).await;
+    }
+
+    Ok(AppState::new(
         http_client,
         circuit_breaker,
-        app_config: Arc::new(app_config),
+        discovery,
+        runtime,
+    ))
+}
+
+/// 编译配置快照，任一部分有误都返回错误
+///
+/// 传入 `previous` 时，上游地址和负载均衡策略未变的服务沿用原实例池，保留健康检查结果和进行中计数；
+/// 新建的服务发现实例池先用 `discovery` 缓存的解析结果填充
+pub fn build_runtime(
+    app_config: AppConfig,
+    previous: Option<&Runtime>,
+    discovery: Option<&Discovery>,
+) -> Result<Runtime, AppError> {
+    // 路由表
+    let mut route_table = RouteTable::compile(&app_config.services)?;
+    if let Some(previous) = previous {
+        route_table.reuse_upstreams(&previous.route_table);
+    }
+    match discovery {
+        Some(discovery) => discovery.apply(&route_table),
+        None => {
+            if let Some((service, _)) = route_table
+                .upstreams()
+                .iter()
+                .find(|(_, pool)| pool.discovery().is_some())
+            {
+                return Err(AppError::internal(format!(
+                    "services.{}.discovery requires `registry` to be configured at startup",
+                    service
+                )));
+            }
+        }
+    }
+    let retry = RetryPolicy::new(app_config.retry.clone(), route_table.upstreams().keys());
+
+    // 熔断器配置需重启生效，热更新时同样校验，避免文件中留下无效值
+    circuit_breaker::validate_config(&app_config.circuit_breaker)?;
+
+    // 客户端 IP 解析
+    let client_ip = ClientIpResolver::new(&app_config.client_ip)?;
+
+    // 限流器
+    let rate_limiter = RateLimiter::new(app_config.rate_limit.clone())?;
+
+    // CORS
+    let cors = build_cors_layer(&app_config.cors)?;
+
+    Ok(Runtime {
+        route_table,
+        retry,
+        rate_limiter,
+        client_ip,
+        cors,
+        app_config,
     })
 }
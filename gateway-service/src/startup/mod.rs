@@ -3,5 +3,5 @@ mod builder;
 mod server;
 
 pub use app_state::AppState;
-pub use builder::{init_app_config, init_app_state};
-pub use server::start_http_server;
+pub use builder::{build_runtime, init_app_config, init_app_state};
+pub use server::{health_checks, start_http_server};
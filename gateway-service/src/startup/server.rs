@@ -1,16 +1,20 @@
 use axum::{
-    Router,
-    http::{HeaderName, HeaderValue, Method},
-    middleware,
+    Router, middleware,
     routing::{any, get},
 };
 use common_core::AppError;
-use tower_http::cors::{Any, CorsLayer};
+use common_metrics::{http_metrics, metrics_handler};
+use common_tracing::{http_trace, log_level_router};
+use common_web::{health::HealthChecks, request_id::request_id, runner::Lifecycle};
 
 use crate::{
+    health::{check_upstreams, health_report},
     middleware::{
-        auth::jwt_auth,
-        rate_limit::{create_rate_limiter, rate_limit_middleware},
+        auth::{jwt_auth, strip_internal_headers},
+        client_ip::client_ip_middleware,
+        cors::cors_middleware,
+        metrics::route_label,
+        rate_limit::rate_limit_middleware,
         tracing::request_tracing,
     },
     proxy::proxy_request,
@@ -18,114 +22,93 @@ use crate::{
 
 use super::AppState;
 
-/// 启动 HTTP 服务器
-pub async fn start_http_server(app_state: AppState, bind_addr: String) -> Result<(), AppError> {
-    // 创建限流器
-    let rate_limiter = create_rate_limiter(
-        app_state.app_config.rate_limit.requests_per_second,
-        app_state.app_config.rate_limit.burst_size,
+/// 就绪检查：上游实例（任一服务没有可用实例）和限流 Redis（不可用时降级为本地计数）都只报告为降级，
+/// 每个实例的详细状态见 `/health/upstreams`
+pub fn health_checks(app_state: &AppState, lifecycle: Lifecycle) -> HealthChecks {
+    let runtime = app_state.runtime();
+    let state = app_state.clone();
+    // 每次检查都使用最新的路由表，热更新或服务发现变更后检查新的实例
+    let health = HealthChecks::new(lifecycle, &runtime.app_config.server.health).optional(
+        "upstreams",
+        move || {
+            let state = state.clone();
+            async move { check_upstreams(&state).await }
+        },
     );
-
-    // 配置 CORS
-    let cors_config = &app_state.app_config.cors;
-    let mut cors_layer = CorsLayer::new();
-
-    // 验证：当 allow_credentials=true 时，不能使用通配符 "*"
-    let has_wildcard = cors_config.allowed_origins.iter().any(|o| o == "*");
-    if has_wildcard && cors_config.allow_credentials {
-        tracing::warn!(
-            "⚠️  CORS 配置警告: allow_credentials=true 时不能使用 allowed_origins=\"*\"\n\
-             浏览器会拒绝此配置。请设置 allow_credentials=false 或指定具体的域名列表。"
-        );
-        return Err(AppError::internal(
-            "Invalid CORS config: cannot use wildcard origin with credentials",
-        ));
-    }
-
-    // 配置允许的源
-    if has_wildcard {
-        cors_layer = cors_layer.allow_origin(Any);
-        tracing::info!("CORS: 允许所有源 (*)");
-    } else {
-        let origins: Result<Vec<HeaderValue>, _> = cors_config
-            .allowed_origins
-            .iter()
-            .map(|origin| origin.parse())
-            .collect();
-        cors_layer = cors_layer.allow_origin(
-            origins.map_err(|e| AppError::internal(format!("Invalid CORS origin: {}", e)))?,
-        );
-        tracing::info!("CORS: 允许的源 = {:?}", cors_config.allowed_origins);
-    }
-
-    // 配置允许的方法
-    let methods: Result<Vec<Method>, _> = cors_config
-        .allowed_methods
-        .iter()
-        .map(|m| m.parse())
-        .collect();
-    cors_layer = cors_layer.allow_methods(
-        methods.map_err(|e| AppError::internal(format!("Invalid HTTP method: {}", e)))?,
-    );
-
-    // 配置允许的请求头
-    if cors_config.allowed_headers.contains(&"*".to_string()) {
-        cors_layer = cors_layer.allow_headers(Any);
-    } else {
-        let headers: Result<Vec<HeaderName>, _> = cors_config
-            .allowed_headers
-            .iter()
-            .map(|h| h.parse())
-            .collect();
-        cors_layer = cors_layer.allow_headers(
-            headers.map_err(|e| AppError::internal(format!("Invalid CORS header: {}", e)))?,
-        );
-    }
-
-    // 配置暴露的响应头
-    let expose_headers: Result<Vec<HeaderName>, _> = cors_config
-        .exposed_headers
-        .iter()
-        .map(|h| h.parse())
-        .collect();
-    cors_layer = cors_layer.expose_headers(
-        expose_headers.map_err(|e| AppError::internal(format!("Invalid expose header: {}", e)))?,
-    );
-
-    // 配置凭证
-    if cors_config.allow_credentials {
-        cors_layer = cors_layer.allow_credentials(true);
-        tracing::info!("CORS: 允许凭证 (cookies/auth headers)");
+    if runtime.app_config.rate_limit.redis.is_none() {
+        return health;
     }
+    let state = app_state.clone();
+    // 每次检查都使用最新的限流器，热更新更换 Redis 后检查新的连接
+    health.optional("redis", move || {
+        let state = state.clone();
+        async move { state.runtime().rate_limiter.ping_redis().await }
+    })
+}
 
-    // 配置预检请求缓存时间
-    cors_layer = cors_layer.max_age(std::time::Duration::from_secs(cors_config.max_age));
-
-    tracing::info!(
-        "✅ CORS 配置完成: methods={:?}, max_age={}s",
-        cors_config.allowed_methods,
-        cors_config.max_age
-    );
-
+/// 启动 HTTP 服务器，收到停机通知后处理完进行中的请求（包括正在转发的上游请求）再返回
+pub async fn start_http_server(
+    app_state: AppState,
+    bind_addr: String,
+    health: HealthChecks,
+    lifecycle: Lifecycle,
+) -> Result<(), AppError> {
     // 构建路由（统一应用中间件，通过白名单控制鉴权）
+    // 管理令牌只在启动时读取，热更新不影响
+    let log_admin_token = app_state.runtime().app_config.logs.admin_token.clone();
     let app = Router::new()
-        .route("/health", get(|| async { "Gateway OK" }))
+        // 存活 / 就绪探针
+        .merge(health.router())
+        // 上游报告：列出每个上游实例的状态
+        .route("/health/upstreams", get(health_report))
+        // Prometheus 指标
+        .route("/metrics", get(metrics_handler))
+        // 运行时调整日志级别
+        .merge(log_level_router(log_admin_token.as_deref()))
         // 所有 API 路由统一处理
         .route(
             "/api/{*path}",
             any(proxy_request)
+                // 限流（在 JWT 之后执行，才能按用户计数）
+                .layer(middleware::from_fn_with_state(
+                    app_state.clone(),
+                    rate_limit_middleware,
+                ))
                 // JWT 验证（白名单路径自动跳过）
                 .layer(middleware::from_fn_with_state(app_state.clone(), jwt_auth))
                 // 请求追踪
                 .layer(middleware::from_fn_with_state(
                     app_state.clone(),
                     request_tracing,
+                ))
+                // 按网关路由标注请求指标
+                .layer(middleware::from_fn_with_state(
+                    app_state.clone(),
+                    route_label,
                 )),
         )
         // 全局中间件（从下往上执行）
-        .layer(cors_layer) // 应用 CORS 配置
-        .layer(middleware::from_fn(rate_limit_middleware))
-        .layer(axum::Extension(rate_limiter))
+        // 应用 CORS 配置（随配置热更新）
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            cors_middleware,
+        ))
+        // 移除客户端伪造的内部请求头
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            strip_internal_headers,
+        ))
+        // 解析真实客户端 IP（最先执行，后续中间件和上游都使用解析结果）
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            client_ip_middleware,
+        ))
+        // 请求指标（统计包括被拒绝请求在内的全部请求）
+        .layer(middleware::from_fn(http_metrics))
+        // 分配请求 ID 并在响应头中返回（转发给上游，错误响应体中也会带上）
+        .layer(middleware::from_fn(request_id))
+        // 链路追踪（最外层，网关及上游的日志都带上同一个 trace id）
+        .layer(middleware::from_fn(http_trace))
         .with_state(app_state);
 
     let listener = tokio::net::TcpListener::bind(&bind_addr).await?;
@@ -135,6 +118,7 @@ pub async fn start_http_server(app_state: AppState, bind_addr: String) -> Result
         listener,
         app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
     )
+    .with_graceful_shutdown(lifecycle.shutdown())
     .await?;
 
     Ok(())
@@ -0,0 +1,24 @@
+-- 文章表（id 由 Snowflake 生成，软删除）
+-- status: 1 公开 / 2 私有 / 3 草稿
+CREATE TABLE IF NOT EXISTS articles (
+    id          BIGINT       NOT NULL,
+    uid         BIGINT       NOT NULL,
+    title       VARCHAR(255) NOT NULL,
+    description TEXT         NOT NULL DEFAULT '',
+    content     TEXT         NOT NULL,
+    status      INTEGER      NOT NULL DEFAULT 3,
+    likes       BIGINT       NOT NULL DEFAULT 0,
+    views       BIGINT       NOT NULL DEFAULT 0,
+    collects    BIGINT       NOT NULL DEFAULT 0,
+    cover_urls  TEXT[]       NOT NULL DEFAULT '{}',
+    created_at  TIMESTAMPTZ  NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at  TIMESTAMPTZ  NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    deleted_at  TIMESTAMPTZ,
+    CONSTRAINT articles_pkey PRIMARY KEY (id),
+    CONSTRAINT status_check CHECK (status IN (1, 2, 3))
+);
+
+CREATE INDEX IF NOT EXISTS idx_articles_uid ON articles (uid);
+CREATE INDEX IF NOT EXISTS idx_articles_deleted_at ON articles (deleted_at);
+CREATE INDEX IF NOT EXISTS idx_articles_status ON articles (status) WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_articles_feed ON articles (created_at DESC, id DESC) WHERE deleted_at IS NULL;
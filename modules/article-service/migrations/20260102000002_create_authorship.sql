@@ -0,0 +1,10 @@
+-- 作者统计（每个用户一行，由文章的发布、删除、浏览、点赞、收藏累加）
+CREATE TABLE IF NOT EXISTS authorship (
+    uid                   BIGINT  NOT NULL,
+    like_count            INTEGER NOT NULL DEFAULT 0,
+    fellow_count          INTEGER NOT NULL DEFAULT 0,
+    collect_count         INTEGER NOT NULL DEFAULT 0,
+    article_count_public  INTEGER NOT NULL DEFAULT 0,
+    article_count_private INTEGER NOT NULL DEFAULT 0,
+    CONSTRAINT authorship_pkey PRIMARY KEY (uid)
+);
@@ -1,7 +1,9 @@
 use serde::Deserialize;
+use validator::Validate;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate)]
 pub struct ArticleQuery {
+    #[validate(length(max = 100, message = "title_like must be at most 100 characters"))]
     pub title_like: Option<String>,
 }
 
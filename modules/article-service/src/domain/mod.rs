@@ -1,4 +1,4 @@
 pub mod bo;
 pub mod model;
 pub mod request;
-pub mod response;
\ No newline at end of file
+pub mod response;
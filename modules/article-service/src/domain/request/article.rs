@@ -1,10 +1,33 @@
 use serde::Deserialize;
+use validator::{Validate, ValidateUrl, ValidationError};
 
-#[derive(Deserialize)]
+/// 单篇文章最多封面数
+const MAX_COVER_URLS: u64 = 9;
+
+#[derive(Deserialize, Validate)]
 pub struct ArticleRequest {
     pub id: Option<i64>,
+    #[validate(length(min = 1, max = 200, message = "title must be 1-200 characters"))]
     pub title: Option<String>,
+    #[validate(length(max = 500, message = "description must be at most 500 characters"))]
     pub description: Option<String>,
+    #[validate(length(min = 1, max = 100000, message = "content must be 1-100000 characters"))]
     pub content: Option<String>,
+    #[validate(
+        length(max = MAX_COVER_URLS, message = "at most 9 cover urls"),
+        custom(function = "validate_cover_urls")
+    )]
     pub cover_urls: Option<Vec<String>>,
 }
+
+/// 封面必须是 http(s) 链接
+fn validate_cover_urls(urls: &[String]) -> Result<(), ValidationError> {
+    let all_valid = urls
+        .iter()
+        .all(|u| (u.starts_with("http://") || u.starts_with("https://")) && u.validate_url());
+    if all_valid {
+        Ok(())
+    } else {
+        Err(ValidationError::new("url").with_message("cover_urls must be http(s) URLs".into()))
+    }
+}
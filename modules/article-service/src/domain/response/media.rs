@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+use crate::services::media_service::StoredImage;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MediaUploadRes {
+    /// 可直接写入 `cover_urls` 或用户头像
+    pub url: String,
+    pub thumbnail_url: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    pub content_type: String,
+}
+
+impl From<StoredImage> for MediaUploadRes {
+    fn from(image: StoredImage) -> Self {
+        Self {
+            url: image.url,
+            thumbnail_url: image.thumbnail_url,
+            width: image.width,
+            height: image.height,
+            content_type: image.content_type.to_string(),
+        }
+    }
+}
@@ -1,2 +1,3 @@
 pub mod article;
 pub mod authorship;
+pub mod media;
@@ -1,15 +1,17 @@
 pub mod config;
 mod domain;
-mod grpc;
 mod repository;
 mod routes;
 mod services;
 mod startup;
-mod utils;
 
 use common_core::AppError;
+use common_db::migrate::is_migrate_command;
 use common_tracing::TracingService;
-use startup::{init_app_config, init_app_state, start_http_server};
+use common_web::runner::ServiceRunner;
+use startup::{
+    health_checks, init_app_config, init_app_state, migrate_database, start_http_server,
+};
 
 #[tokio::main]
 async fn main() -> Result<(), AppError> {
@@ -17,10 +19,16 @@ async fn main() -> Result<(), AppError> {
     let app_config = init_app_config()?;
     let service_name = app_config.server.name.clone();
     // 初始化日志
-    let _guard = TracingService::init(&app_config.logs);
+    let _guard = TracingService::init(&app_config.server.name, &app_config.logs);
+
+    // `<service> migrate`：只执行数据库迁移然后退出
+    if is_migrate_command() {
+        return migrate_database(&app_config).await;
+    }
     tracing::info!("🚀 {} Service starting...", service_name);
 
     let http_bind_addr = app_config.server.bind_addr.clone();
+    let mut runner = ServiceRunner::new(&service_name, &app_config.server.shutdown);
 
     // 2. 初始化应用（基础设施 + 业务服务）
     let app_state = match init_app_state(app_config).await {
@@ -31,12 +39,23 @@ async fn main() -> Result<(), AppError> {
         }
     };
 
-    // 3. 启动服务器
-    let http_server = start_http_server(app_state.clone(), http_bind_addr);
+    // 3. 注册到注册中心，停机时注销
+    let lifecycle = runner.lifecycle();
+    if let Some(registry) = &app_state.registry {
+        let instance = registry.config().instance(&http_bind_addr, None)?;
+        if let Some(registration) = registry.register(&service_name, instance, lifecycle.shutdown())
+        {
+            runner.add_background("Service registration", registration);
+        }
+    }
 
-    // 4. 等待服务器运行
-    let _ = tokio::try_join!(http_server)
-        .map_err(|e| AppError::internal(format!("Server error: {}", e)))?;
+    // 4. 启动服务器
+    let health = health_checks(&app_state, lifecycle.clone());
+    runner.spawn_server(
+        "HTTP",
+        start_http_server(app_state, http_bind_addr, health, lifecycle),
+    );
 
-    Ok(())
+    // 5. 运行到收到 SIGTERM/SIGINT，优雅停机后返回（日志守卫随之刷新）
+    runner.run().await
 }
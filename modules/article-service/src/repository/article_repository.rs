@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use common_core::AppError;
+use common_core::{AppError, domain::page::Cursor, error::DbResultExt};
 use sqlx::PgConnection;
 
 use crate::domain::{
@@ -23,6 +23,15 @@ pub trait ArticleRepository: Send + Sync {
         query: &ArticleQuery,
     ) -> Result<Vec<ArticleSummary>, AppError>;
 
+    /// 游标分页查询（按 created_at、id 倒序，返回 cursor 之后的 limit 条）
+    async fn find_feed(
+        &self,
+        executor: &mut PgConnection,
+        limit: i64,
+        cursor: Option<Cursor>,
+        query: &ArticleQuery,
+    ) -> Result<Vec<ArticleSummary>, AppError>;
+
     async fn count(
         &self,
         executor: &mut PgConnection,
@@ -91,7 +100,7 @@ impl ArticleRepository for ArticleRepositoryImpl {
         .bind(id)
         .fetch_optional(executor)
         .await
-        .map_err(|e| AppError::Db(format!("Failed to find article by id: {}", e)))
+        .db_context("Failed to find article by id")
     }
 
     async fn find_list(
@@ -120,7 +129,42 @@ impl ArticleRepository for ArticleRepositoryImpl {
             .build_query_as::<ArticleSummary>()
             .fetch_all(executor)
             .await
-            .map_err(|e| AppError::Db(format!("Failed to find article list: {}", e)))
+            .db_context("Failed to find article list")
+    }
+
+    async fn find_feed(
+        &self,
+        executor: &mut PgConnection,
+        limit: i64,
+        cursor: Option<Cursor>,
+        query: &ArticleQuery,
+    ) -> Result<Vec<ArticleSummary>, AppError> {
+        let mut query_builder = sqlx::QueryBuilder::new(
+            "SELECT id, uid, title, description, status, likes, views, collects, cover_urls, created_at, updated_at \
+         FROM articles WHERE deleted_at IS NULL",
+        );
+
+        if let Some(title) = &query.title_like {
+            query_builder.push(" AND title LIKE ");
+            query_builder.push_bind(format!("%{}%", title));
+        }
+
+        if let Some(cursor) = cursor {
+            query_builder.push(" AND (created_at, id) < (");
+            query_builder.push_bind(cursor.created_at);
+            query_builder.push(", ");
+            query_builder.push_bind(cursor.id);
+            query_builder.push(")");
+        }
+
+        query_builder.push(" ORDER BY created_at DESC, id DESC LIMIT ");
+        query_builder.push_bind(limit);
+
+        query_builder
+            .build_query_as::<ArticleSummary>()
+            .fetch_all(executor)
+            .await
+            .db_context("Failed to find article feed")
     }
 
     async fn count(
@@ -140,7 +184,7 @@ impl ArticleRepository for ArticleRepositoryImpl {
             .build_query_as()
             .fetch_one(executor)
             .await
-            .map_err(|e| AppError::Db(format!("Failed to count articles: {}", e)))?;
+            .db_context("Failed to count articles")?;
         Ok(count.0)
     }
 
@@ -173,7 +217,7 @@ impl ArticleRepository for ArticleRepositoryImpl {
         .bind(article.updated_at)
         .execute(executor)
         .await
-        .map_err(|e| AppError::Db(format!("Failed to insert article: {}", e)))?;
+        .db_context("Failed to insert article")?;
         Ok(())
     }
 
@@ -213,7 +257,7 @@ impl ArticleRepository for ArticleRepositoryImpl {
             .build()
             .execute(executor)
             .await
-            .map_err(|e| AppError::Db(format!("Failed to update article: {}", e)))?;
+            .db_context("Failed to update article")?;
         Ok(())
     }
 
@@ -222,7 +266,7 @@ impl ArticleRepository for ArticleRepositoryImpl {
             .bind(id)
             .execute(executor)
             .await
-            .map_err(|e| AppError::Db(format!("Failed to delete article: {}", e)))?;
+            .db_context("Failed to delete article")?;
         Ok(())
     }
 
@@ -239,7 +283,7 @@ impl ArticleRepository for ArticleRepositoryImpl {
         .bind(uid)
         .fetch_one(executor)
         .await
-        .map_err(|e| AppError::Db(format!("Failed to check article ownership: {}", e)))?;
+        .db_context("Failed to check article ownership")?;
         Ok(count.0 > 0)
     }
 
@@ -254,13 +298,13 @@ impl ArticleRepository for ArticleRepositoryImpl {
         } else {
             "UPDATE articles SET likes = GREATEST(0, likes + $1), updated_at = NOW() WHERE id = $2 AND deleted_at IS NULL"
         };
-        
+
         sqlx::query(sql)
             .bind(increment)
             .bind(id)
             .execute(executor)
             .await
-            .map_err(|e| AppError::Db(format!("Failed to update article likes: {}", e)))?;
+            .db_context("Failed to update article likes")?;
         Ok(())
     }
 
@@ -275,13 +319,13 @@ impl ArticleRepository for ArticleRepositoryImpl {
         } else {
             "UPDATE articles SET views = GREATEST(0, views + $1), updated_at = NOW() WHERE id = $2 AND deleted_at IS NULL"
         };
-        
+
         sqlx::query(sql)
             .bind(increment)
             .bind(id)
             .execute(executor)
             .await
-            .map_err(|e| AppError::Db(format!("Failed to update article views: {}", e)))?;
+            .db_context("Failed to update article views")?;
         Ok(())
     }
 
@@ -296,13 +340,13 @@ impl ArticleRepository for ArticleRepositoryImpl {
         } else {
             "UPDATE articles SET collects = GREATEST(0, collects + $1), updated_at = NOW() WHERE id = $2 AND deleted_at IS NULL"
         };
-        
+
         sqlx::query(sql)
             .bind(increment)
             .bind(id)
             .execute(executor)
             .await
-            .map_err(|e| AppError::Db(format!("Failed to update article collects: {}", e)))?;
+            .db_context("Failed to update article collects")?;
         Ok(())
     }
 }
@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use common_core::AppError;
+use common_core::{AppError, error::DbResultExt};
 use sqlx::PgConnection;
 
 use crate::domain::{bo::authorship_bo::AuthorshipBo, model::authorship::Authorship};
@@ -41,7 +41,7 @@ impl AuthorshipRepository for AuthorshipRepositoryImpl {
         .bind(uid)
         .fetch_optional(executor)
         .await
-        .map_err(|e| AppError::Db(format!("Failed to find authorship by uid: {}", e)))
+        .db_context("Failed to find authorship by uid")
     }
 
     async fn insert(
@@ -66,7 +66,7 @@ impl AuthorshipRepository for AuthorshipRepositoryImpl {
         .bind(authorship.article_count_private)
         .execute(executor)
         .await
-        .map_err(|e| AppError::Db(format!("Failed to insert authorship: {}", e)))?;
+        .db_context("Failed to insert authorship")?;
         Ok(())
     }
 
@@ -166,7 +166,7 @@ impl AuthorshipRepository for AuthorshipRepositoryImpl {
             .bind(authorship_bo.uid)
             .execute(executor)
             .await
-            .map_err(|e| AppError::Db(format!("Failed to upsert authorship: {}", e)))?;
+            .db_context("Failed to upsert authorship")?;
 
         Ok(())
     }
@@ -1,12 +1,16 @@
 use axum::{
     Json, Router,
-    extract::{Path, Query, State},
+    extract::{Path, State},
     http::HeaderMap,
     routing::{delete, get, post, put},
 };
-use common_core::domain::page::{Page, PageResult};
+use common_core::domain::page::{CursorPage, CursorResult, Page, PageResult};
 use common_core::error::AppError;
-use common_web::{domain::r::R, error::ApiError};
+use common_web::{
+    domain::r::R,
+    error::ApiError,
+    validation::{ValidatedJson, ValidatedQuery},
+};
 
 use crate::{
     domain::{
@@ -27,6 +31,7 @@ pub fn router() -> Router<AppState> {
         .route("/delete/{id}", delete(delete_article))
         .route("/detail/{id}", get(get_article_detail))
         .route("/list", get(get_article_list))
+        .route("/feed", get(get_article_feed))
         .route("/like/{id}", post(like_article))
         .route("/collect/{id}", post(collect_article))
 }
@@ -35,7 +40,7 @@ pub fn router() -> Router<AppState> {
 async fn create_article(
     State(state): State<AppState>,
     headers: HeaderMap,
-    Json(req): Json<ArticleRequest>,
+    ValidatedJson(req): ValidatedJson<ArticleRequest>,
 ) -> Result<Json<R<i64>>, ApiError> {
     let user_id = get_user_id_from_header(&headers)?;
     let article_detail_bo = ArticleDetailBo {
@@ -54,7 +59,7 @@ async fn create_article(
 async fn update_article(
     State(state): State<AppState>,
     headers: HeaderMap,
-    Json(req): Json<ArticleRequest>,
+    ValidatedJson(req): ValidatedJson<ArticleRequest>,
 ) -> Result<Json<R<()>>, ApiError> {
     let user_id = get_user_id_from_header(&headers)?;
     let article_detail_bo = ArticleDetailBo {
@@ -69,9 +74,9 @@ async fn update_article(
     // 校验所有权
     let id = article_detail_bo
         .id
-        .ok_or_else(|| ApiError(AppError::internal("Article ID is required")))?;
+        .ok_or_else(|| ApiError(AppError::bad_request("Article ID is required")))?;
     if !state.article_service.check_ownership(id, user_id).await? {
-        return Err(ApiError(AppError::internal("Permission denied")));
+        return Err(ApiError(AppError::forbidden("Permission denied")));
     }
 
     state.article_service.update(article_detail_bo).await?;
@@ -88,7 +93,7 @@ async fn delete_article(
 
     // 校验所有权
     if !state.article_service.check_ownership(id, user_id).await? {
-        return Err(ApiError(AppError::internal("Permission denied")));
+        return Err(ApiError(AppError::forbidden("Permission denied")));
     }
 
     state.article_service.delete(id).await?;
@@ -97,14 +102,14 @@ async fn delete_article(
 
 /// 获取文章详情
 async fn get_article_detail(
-    State(mut state): State<AppState>,
+    State(state): State<AppState>,
     Path(id): Path<i64>,
 ) -> Result<Json<R<ArticleDetailRes>>, ApiError> {
     // 使用service层的view_article方法，包含浏览统计
     let article = state.article_service.view_article(id).await?;
     match article {
         Some(a) => {
-            let authorship_res = fetch_authorship_res(&mut state, a.uid).await?;
+            let authorship_res = fetch_authorship_res(&state, a.uid).await?;
             let res = ArticleDetailRes {
                 id: a.id.to_string(),
                 authorship: Some(authorship_res),
@@ -122,15 +127,15 @@ async fn get_article_detail(
             };
             Ok(Json(R::ok(res)))
         }
-        None => Err(ApiError(AppError::db("Article not found"))),
+        None => Err(ApiError(AppError::not_found("Article not found"))),
     }
 }
 
 /// 获取文章列表
 async fn get_article_list(
-    State(mut state): State<AppState>,
-    Query(article_query): Query<ArticleQuery>,
-    Query(page): Query<Page>,
+    State(state): State<AppState>,
+    ValidatedQuery(article_query): ValidatedQuery<ArticleQuery>,
+    ValidatedQuery(page): ValidatedQuery<Page>,
 ) -> Result<Json<R<PageResult<ArticleSummaryRes>>>, ApiError> {
     let result = state
         .article_service
@@ -140,7 +145,7 @@ async fn get_article_list(
     // 获取每篇文章的作者信息
     let mut summary_list: Vec<ArticleSummaryRes> = Vec::new();
     for item in result.list.into_iter() {
-        let authorship_res = fetch_authorship_res(&mut state, item.uid).await?;
+        let authorship_res = fetch_authorship_res(&state, item.uid).await?;
         let mut summary_res: ArticleSummaryRes = item.into();
         summary_res.authorship = Some(authorship_res);
         summary_list.push(summary_res);
@@ -154,25 +159,53 @@ async fn get_article_list(
     })))
 }
 
+/// 获取文章信息流（游标分页，适用于无限滚动）
+async fn get_article_feed(
+    State(state): State<AppState>,
+    ValidatedQuery(article_query): ValidatedQuery<ArticleQuery>,
+    ValidatedQuery(page): ValidatedQuery<CursorPage>,
+) -> Result<Json<R<CursorResult<ArticleSummaryRes>>>, ApiError> {
+    let result = state
+        .article_service
+        .get_article_feed(article_query, page)
+        .await?;
+
+    let mut summary_list: Vec<ArticleSummaryRes> = Vec::new();
+    for item in result.list.into_iter() {
+        let authorship_res = fetch_authorship_res(&state, item.uid).await?;
+        let mut summary_res: ArticleSummaryRes = item.into();
+        summary_res.authorship = Some(authorship_res);
+        summary_list.push(summary_res);
+    }
+
+    Ok(Json(R::ok(CursorResult {
+        list: summary_list,
+        next_cursor: result.next_cursor,
+        page_size: result.page_size,
+    })))
+}
+
 /// 辅助函数：从 header 获取用户 ID
-fn get_user_id_from_header(headers: &HeaderMap) -> Result<i64, ApiError> {
+pub(crate) fn get_user_id_from_header(headers: &HeaderMap) -> Result<i64, ApiError> {
     headers
         .get("x-user-id")
         .and_then(|h| h.to_str().ok())
         .and_then(|s| s.parse::<i64>().ok())
-        .ok_or_else(|| ApiError(AppError::internal("User not authenticated")))
+        .ok_or_else(|| ApiError(AppError::unauthorized("User not authenticated")))
 }
 
 /// 辅助函数：获取作者信息响应对象
-async fn fetch_authorship_res(state: &mut AppState, uid: i64) -> Result<AuthorshipRes, ApiError> {
+async fn fetch_authorship_res(state: &AppState, uid: i64) -> Result<AuthorshipRes, ApiError> {
     let authorship = state.authorship_service.get_authorship(uid).await?;
 
-    // 调用 gRPC 获取用户信息
-    let (username, avatar_url) = state
-        .user_grpc_client
-        .get_user_info(authorship.uid)
-        .await
-        .unwrap_or_else(|_| ("Unknown".to_string(), String::new()));
+    // 调用 gRPC 获取用户信息，用户服务不可用时作者名显示为 Unknown
+    let (username, avatar_url) = match state.user_client.get_user(authorship.uid).await {
+        Ok(user) => (user.username, user.avatar_url.unwrap_or_default()),
+        Err(e) => {
+            tracing::warn!("Failed to fetch author {}: {}", authorship.uid, e);
+            ("Unknown".to_string(), String::new())
+        }
+    };
 
     Ok(AuthorshipRes {
         id: authorship.uid.to_string(),
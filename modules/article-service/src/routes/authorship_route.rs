@@ -13,17 +13,19 @@ pub fn router() -> Router<AppState> {
 
 /// 获取作者信息
 async fn get_authorship_detail(
-    State(mut state): State<AppState>,
+    State(state): State<AppState>,
     Path(uid): Path<i64>,
 ) -> Result<Json<R<AuthorshipRes>>, ApiError> {
     let authorship = state.authorship_service.get_authorship(uid).await?;
 
-    // 调用 gRPC 获取用户信息
-    let (username, avatar_url) = state
-        .user_grpc_client
-        .get_user_info(authorship.uid)
-        .await
-        .unwrap_or_else(|_| ("Unknown".to_string(), String::new()));
+    // 调用 gRPC 获取用户信息，用户服务不可用时作者名显示为 Unknown
+    let (username, avatar_url) = match state.user_client.get_user(authorship.uid).await {
+        Ok(user) => (user.username, user.avatar_url.unwrap_or_default()),
+        Err(e) => {
+            tracing::warn!("Failed to fetch author {}: {}", authorship.uid, e);
+            ("Unknown".to_string(), String::new())
+        }
+    };
 
     Ok(Json(R::ok(AuthorshipRes {
         id: authorship.uid.to_string(),
@@ -0,0 +1,97 @@
+use axum::{
+    Json, Router,
+    extract::{DefaultBodyLimit, Multipart, Path, State},
+    http::{HeaderMap, header},
+    response::IntoResponse,
+    routing::{get, post},
+};
+use bytes::Bytes;
+use common_core::error::AppError;
+use common_web::{domain::r::R, error::ApiError};
+
+use crate::{
+    domain::response::media::MediaUploadRes, routes::article_route::get_user_id_from_header,
+    services::media_service::MediaKind, startup::AppState,
+};
+
+/// multipart 边界、字段头等额外开销
+const MULTIPART_OVERHEAD: usize = 64 * 1024;
+
+pub fn router(max_upload_size: usize) -> Router<AppState> {
+    Router::new()
+        .route("/cover", post(upload_cover))
+        .route("/avatar", post(upload_avatar))
+        .layer(DefaultBodyLimit::max(max_upload_size + MULTIPART_OVERHEAD))
+        .route("/files/{*key}", get(get_file))
+}
+
+/// 上传文章封面，返回的 url 可写入 `cover_urls`
+async fn upload_cover(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    multipart: Multipart,
+) -> Result<Json<R<MediaUploadRes>>, ApiError> {
+    upload(state, headers, multipart, MediaKind::Cover).await
+}
+
+/// 上传用户头像，返回的 url 可通过 user-service `/avatar` 保存
+async fn upload_avatar(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    multipart: Multipart,
+) -> Result<Json<R<MediaUploadRes>>, ApiError> {
+    upload(state, headers, multipart, MediaKind::Avatar).await
+}
+
+async fn upload(
+    state: AppState,
+    headers: HeaderMap,
+    multipart: Multipart,
+    kind: MediaKind,
+) -> Result<Json<R<MediaUploadRes>>, ApiError> {
+    let user_id = get_user_id_from_header(&headers)?;
+    let data = read_file_field(multipart).await?;
+    let image = state
+        .media_service
+        .upload_image(user_id, kind, data)
+        .await?;
+    Ok(Json(R::ok(image.into())))
+}
+
+/// 读取表单中的 `file` 字段
+async fn read_file_field(mut multipart: Multipart) -> Result<Bytes, ApiError> {
+    while let Some(field) = multipart.next_field().await.map_err(invalid_multipart)? {
+        if field.name() == Some("file") {
+            return field.bytes().await.map_err(invalid_multipart);
+        }
+    }
+    Err(ApiError(AppError::bad_request("Missing `file` field")))
+}
+
+fn invalid_multipart(e: axum::extract::multipart::MultipartError) -> ApiError {
+    ApiError(AppError::bad_request(format!(
+        "Invalid multipart body: {}",
+        e.body_text()
+    )))
+}
+
+/// 读取本地存储的文件（S3 后端时直接访问 bucket 地址）
+async fn get_file(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let (data, content_type) = state
+        .media_service
+        .get_file(&key)
+        .await?
+        .ok_or_else(|| ApiError(AppError::not_found("File not found")))?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type),
+            (header::CACHE_CONTROL, "public, max-age=31536000, immutable"),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff"),
+        ],
+        data,
+    ))
+}
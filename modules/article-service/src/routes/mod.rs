@@ -1,2 +1,3 @@
 pub mod article_route;
 pub mod authorship_route;
+pub mod media_route;
@@ -4,8 +4,9 @@ use async_trait::async_trait;
 use chrono::Utc;
 use common_core::{
     AppError,
-    domain::page::{Page, PageResult},
+    domain::page::{Cursor, CursorPage, CursorResult, Page, PageResult},
 };
+use common_db::with_transaction;
 use common_redis::RedisClient;
 use snowflake::SnowflakeIdGenerator;
 use sqlx::PgPool;
@@ -21,7 +22,6 @@ use crate::{
         article_repository::{ArticleRepository, ArticleRepositoryImpl},
         authorship_repository::{AuthorshipRepository, AuthorshipRepositoryImpl},
     },
-    with_transaction,
 };
 
 static ARTICLE_REPO: ArticleRepositoryImpl = ArticleRepositoryImpl;
@@ -41,6 +41,13 @@ pub trait ArticleService: Send + Sync {
         page: Page,
     ) -> Result<PageResult<ArticleSummary>, AppError>;
 
+    /// 获取文章信息流（游标分页，不统计总数）
+    async fn get_article_feed(
+        &self,
+        query: ArticleQuery,
+        page: CursorPage,
+    ) -> Result<CursorResult<ArticleSummary>, AppError>;
+
     /// 插入文章
     async fn insert(&self, article_detail_bo: ArticleDetailBo) -> Result<i64, AppError>;
 
@@ -74,11 +81,7 @@ impl ArticleService for ArticleServiceImpl {
         &self,
         article_id: i64,
     ) -> Result<Option<ArticleDetail>, AppError> {
-        let mut conn = self
-            .db_pool
-            .acquire()
-            .await
-            .map_err(|e| AppError::db(e.to_string()))?;
+        let mut conn = self.db_pool.acquire().await?;
         ARTICLE_REPO.find_by_id(&mut conn, article_id).await
     }
 
@@ -87,11 +90,7 @@ impl ArticleService for ArticleServiceImpl {
         query: ArticleQuery,
         page: Page,
     ) -> Result<PageResult<ArticleSummary>, AppError> {
-        let mut conn = self
-            .db_pool
-            .acquire()
-            .await
-            .map_err(|e| AppError::db(e.to_string()))?;
+        let mut conn = self.db_pool.acquire().await?;
 
         // 分页参数
         let limit = page.page_size;
@@ -117,6 +116,27 @@ impl ArticleService for ArticleServiceImpl {
         })
     }
 
+    async fn get_article_feed(
+        &self,
+        query: ArticleQuery,
+        page: CursorPage,
+    ) -> Result<CursorResult<ArticleSummary>, AppError> {
+        let cursor = page.decode_cursor()?;
+        let mut conn = self.db_pool.acquire().await?;
+
+        // 多查一条用于判断是否还有下一页
+        let items = ARTICLE_REPO
+            .find_feed(&mut conn, page.page_size + 1, cursor, &query)
+            .await?;
+
+        Ok(CursorResult::from_overfetch(items, page.page_size, |a| {
+            Cursor {
+                created_at: a.created_at,
+                id: a.id,
+            }
+        }))
+    }
+
     async fn insert(&self, article_detail_bo: ArticleDetailBo) -> Result<i64, AppError> {
         let article_id = self.id_generator.write().await.real_time_generate();
         let now = Utc::now();
@@ -126,13 +146,13 @@ impl ArticleService for ArticleServiceImpl {
             uid: article_detail_bo.uid,
             title: article_detail_bo
                 .title
-                .ok_or(AppError::internal("Title is required"))?,
+                .ok_or(AppError::bad_request("Title is required"))?,
             description: article_detail_bo
                 .description
-                .ok_or(AppError::internal("Description is required"))?,
+                .ok_or(AppError::bad_request("Description is required"))?,
             content: article_detail_bo
                 .content
-                .ok_or(AppError::internal("Content is required"))?,
+                .ok_or(AppError::bad_request("Content is required"))?,
             status: ArticleStatus::Draft,
             likes: 0,
             views: 0,
@@ -164,11 +184,7 @@ impl ArticleService for ArticleServiceImpl {
     }
 
     async fn update(&self, article_detail_bo: ArticleDetailBo) -> Result<(), AppError> {
-        let mut conn = self
-            .db_pool
-            .acquire()
-            .await
-            .map_err(|e| AppError::db(e.to_string()))?;
+        let mut conn = self.db_pool.acquire().await?;
 
         // 只有当有需要更新的字段时才执行更新
         if article_detail_bo.title.is_none()
@@ -182,7 +198,7 @@ impl ArticleService for ArticleServiceImpl {
         // 确保 ID 存在
         let id = article_detail_bo
             .id
-            .ok_or(AppError::db("Article ID is required for update"))?;
+            .ok_or(AppError::bad_request("Article ID is required for update"))?;
 
         ARTICLE_REPO
             .update(
@@ -253,7 +269,7 @@ impl ArticleService for ArticleServiceImpl {
         let article = self
             .get_article_details(article_id)
             .await?
-            .ok_or_else(|| AppError::db("Article not found"))?;
+            .ok_or_else(|| AppError::not_found("Article not found"))?;
 
         // 使用事务同时更新文章点赞数和作者统计
         with_transaction!(&self.db_pool, |tx| async {
@@ -278,7 +294,7 @@ impl ArticleService for ArticleServiceImpl {
         let article = self
             .get_article_details(article_id)
             .await?
-            .ok_or_else(|| AppError::db("Article not found"))?;
+            .ok_or_else(|| AppError::not_found("Article not found"))?;
 
         // 使用事务同时更新文章收藏数和作者统计
         with_transaction!(&self.db_pool, |tx| async {
@@ -299,11 +315,7 @@ impl ArticleService for ArticleServiceImpl {
     }
 
     async fn check_ownership(&self, article_id: i64, uid: i64) -> Result<bool, AppError> {
-        let mut conn = self
-            .db_pool
-            .acquire()
-            .await
-            .map_err(|e| AppError::db(e.to_string()))?;
+        let mut conn = self.db_pool.acquire().await?;
         ARTICLE_REPO.is_owner(&mut conn, article_id, uid).await
     }
 }
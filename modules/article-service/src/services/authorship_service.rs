@@ -40,11 +40,7 @@ impl AuthorshipService for AuthorshipServiceImpl {
 impl AuthorshipServiceImpl {
     /// 私有方法：确保作者信息存在的内部工具方法
     async fn _ensure_authorship(&self, uid: i64) -> Result<Authorship, AppError> {
-        let mut conn = self
-            .db_pool
-            .acquire()
-            .await
-            .map_err(|e| AppError::db(e.to_string()))?;
+        let mut conn = self.db_pool.acquire().await?;
 
         // 先尝试查找
         if let Some(authorship) = AUTHORSHIP_REPO.find_by_id(&mut conn, uid).await? {
@@ -0,0 +1,347 @@
+use std::{io::Cursor, sync::Arc};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use common_core::AppError;
+use common_storage::BlobStore;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader, Limits, imageops::FilterType};
+use snowflake::SnowflakeIdGenerator;
+use tokio::sync::RwLock;
+
+use crate::config::application::Media;
+
+/// 上传用途，决定存储路径和处理方式
+#[derive(Debug, Clone, Copy)]
+pub enum MediaKind {
+    /// 文章封面：保留原图尺寸，额外生成缩略图
+    Cover,
+    /// 用户头像：裁剪为正方形
+    Avatar,
+}
+
+impl MediaKind {
+    fn prefix(self) -> &'static str {
+        match self {
+            Self::Cover => "covers",
+            Self::Avatar => "avatars",
+        }
+    }
+}
+
+/// 已存储的图片
+#[derive(Debug, Clone)]
+pub struct StoredImage {
+    pub url: String,
+    pub thumbnail_url: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    pub content_type: &'static str,
+}
+
+/// 图片上传服务
+#[async_trait]
+pub trait MediaService: Send + Sync {
+    /// 校验、清洗（去除 EXIF）并保存图片
+    async fn upload_image(
+        &self,
+        uid: i64,
+        kind: MediaKind,
+        data: Bytes,
+    ) -> Result<StoredImage, AppError>;
+
+    /// 读取已保存的文件，返回 (内容, Content-Type)
+    async fn get_file(&self, key: &str) -> Result<Option<(Bytes, &'static str)>, AppError>;
+}
+
+pub struct MediaServiceImpl {
+    pub blob_store: Arc<dyn BlobStore>,
+    pub id_generator: Arc<RwLock<SnowflakeIdGenerator>>,
+    pub media_config: Media,
+}
+
+#[async_trait]
+impl MediaService for MediaServiceImpl {
+    async fn upload_image(
+        &self,
+        uid: i64,
+        kind: MediaKind,
+        data: Bytes,
+    ) -> Result<StoredImage, AppError> {
+        if data.is_empty() {
+            return Err(AppError::bad_request("File is empty"));
+        }
+        if data.len() > self.media_config.max_upload_size {
+            return Err(AppError::bad_request(format!(
+                "File exceeds the {} byte limit",
+                self.media_config.max_upload_size
+            )));
+        }
+
+        // 解码 / 编码是 CPU 密集操作，放到阻塞线程池
+        let config = self.media_config.clone();
+        let processed = tokio::task::spawn_blocking(move || process_image(&data, kind, &config))
+            .await
+            .map_err(|e| AppError::internal(format!("Image processing task failed: {}", e)))??;
+
+        let id = self.id_generator.write().await.real_time_generate();
+        let ext = extension(processed.format);
+        let content_type = content_type(processed.format);
+        let key = format!("{}/{}/{}.{}", kind.prefix(), uid, id, ext);
+
+        self.blob_store
+            .put(&key, processed.image, content_type)
+            .await?;
+
+        let thumbnail_url = match processed.thumbnail {
+            Some(thumbnail) => {
+                let thumb_key = format!("{}/{}/{}_thumb.{}", kind.prefix(), uid, id, ext);
+                self.blob_store
+                    .put(&thumb_key, thumbnail, content_type)
+                    .await?;
+                Some(self.blob_store.public_url(&thumb_key))
+            }
+            None => None,
+        };
+
+        Ok(StoredImage {
+            url: self.blob_store.public_url(&key),
+            thumbnail_url,
+            width: processed.width,
+            height: processed.height,
+            content_type,
+        })
+    }
+
+    async fn get_file(&self, key: &str) -> Result<Option<(Bytes, &'static str)>, AppError> {
+        let content_type = key
+            .rsplit_once('.')
+            .and_then(|(_, ext)| ImageFormat::from_extension(ext))
+            .filter(|format| is_allowed(*format))
+            .map(content_type)
+            .ok_or_else(|| AppError::not_found("File not found"))?;
+
+        Ok(self
+            .blob_store
+            .get(key)
+            .await?
+            .map(|data| (data, content_type)))
+    }
+}
+
+struct ProcessedImage {
+    format: ImageFormat,
+    image: Bytes,
+    thumbnail: Option<Bytes>,
+    width: u32,
+    height: u32,
+}
+
+/// 按文件头识别格式，拒绝白名单以外的类型，然后重新编码
+///
+/// 重新编码只写入像素数据，EXIF（含 GPS 等隐私信息）随之丢弃；
+/// 丢弃前先按 EXIF 方向旋转，避免图片“躺倒”
+fn process_image(data: &[u8], kind: MediaKind, config: &Media) -> Result<ProcessedImage, AppError> {
+    let format = image::guess_format(data)
+        .ok()
+        .filter(|format| is_allowed(*format))
+        .ok_or_else(|| AppError::bad_request("Only JPEG, PNG and WebP images are allowed"))?;
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(config.max_dimension);
+    limits.max_image_height = Some(config.max_dimension);
+
+    let mut reader = ImageReader::with_format(Cursor::new(data), format);
+    reader.limits(limits);
+    let mut decoder = reader.into_decoder().map_err(invalid_image)?;
+    let orientation = decoder.orientation().map_err(invalid_image)?;
+    let mut img = DynamicImage::from_decoder(decoder).map_err(invalid_image)?;
+    img.apply_orientation(orientation);
+
+    let (img, thumbnail) = match kind {
+        MediaKind::Cover => {
+            let thumbnail = img.thumbnail(config.thumbnail_size, config.thumbnail_size);
+            (img, Some(encode(&thumbnail, format)?))
+        }
+        MediaKind::Avatar => {
+            let size = config.thumbnail_size;
+            (img.resize_to_fill(size, size, FilterType::Lanczos3), None)
+        }
+    };
+
+    Ok(ProcessedImage {
+        format,
+        image: encode(&img, format)?,
+        thumbnail,
+        width: img.width(),
+        height: img.height(),
+    })
+}
+
+fn encode(img: &DynamicImage, format: ImageFormat) -> Result<Bytes, AppError> {
+    // JPEG 不支持透明通道，WebP 编码器只接受 8 位色深
+    let img = match format {
+        ImageFormat::Jpeg => DynamicImage::ImageRgb8(img.to_rgb8()),
+        ImageFormat::WebP => DynamicImage::ImageRgba8(img.to_rgba8()),
+        _ => img.clone(),
+    };
+
+    let mut buf = Cursor::new(Vec::new());
+    img.write_to(&mut buf, format)
+        .map_err(|e| AppError::internal(format!("Failed to encode image: {}", e)))?;
+    Ok(Bytes::from(buf.into_inner()))
+}
+
+fn invalid_image(e: image::ImageError) -> AppError {
+    AppError::bad_request(format!("Invalid image: {}", e))
+}
+
+fn is_allowed(format: ImageFormat) -> bool {
+    matches!(
+        format,
+        ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP
+    )
+}
+
+fn extension(format: ImageFormat) -> &'static str {
+    match format {
+        ImageFormat::Jpeg => "jpg",
+        ImageFormat::WebP => "webp",
+        _ => "png",
+    }
+}
+
+fn content_type(format: ImageFormat) -> &'static str {
+    match format {
+        ImageFormat::Jpeg => "image/jpeg",
+        ImageFormat::WebP => "image/webp",
+        _ => "image/png",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use bytes::Bytes;
+    use common_core::{AppError, error::code};
+    use common_storage::{LocalBlobStore, application::LocalStorage};
+    use image::{DynamicImage, ImageFormat};
+    use snowflake::SnowflakeIdGenerator;
+    use tokio::sync::RwLock;
+
+    use super::{MediaKind, MediaService, MediaServiceImpl, encode, process_image};
+    use crate::config::application::Media;
+
+    fn config() -> Media {
+        Media {
+            max_upload_size: 1024 * 1024,
+            max_dimension: 1000,
+            thumbnail_size: 50,
+        }
+    }
+
+    fn image(width: u32, height: u32, format: ImageFormat) -> Vec<u8> {
+        encode(&DynamicImage::new_rgb8(width, height), format)
+            .unwrap()
+            .to_vec()
+    }
+
+    /// 在 JPEG 文件头后插入 EXIF（方向 = 6，即需顺时针旋转 90°）和一段隐私数据
+    fn with_exif(jpeg: &[u8]) -> Vec<u8> {
+        let mut exif = b"Exif\0\0II*\0\x08\0\0\0".to_vec();
+        exif.extend_from_slice(&[1, 0, 0x12, 0x01, 3, 0, 1, 0, 0, 0, 6, 0, 0, 0, 0, 0, 0, 0]);
+        exif.extend_from_slice(b"GPS-SECRET");
+
+        let mut data = jpeg[..2].to_vec();
+        data.extend_from_slice(&[0xFF, 0xE1]);
+        data.extend_from_slice(&(exif.len() as u16 + 2).to_be_bytes());
+        data.extend_from_slice(&exif);
+        data.extend_from_slice(&jpeg[2..]);
+        data
+    }
+
+    fn contains(data: &[u8], needle: &[u8]) -> bool {
+        data.windows(needle.len()).any(|w| w == needle)
+    }
+
+    #[test]
+    fn strips_exif_after_reencoding() {
+        let data = with_exif(&image(40, 20, ImageFormat::Jpeg));
+        assert!(contains(&data, b"GPS-SECRET"));
+
+        let processed = process_image(&data, MediaKind::Cover, &config()).unwrap();
+        assert!(!contains(&processed.image, b"Exif"));
+        assert!(!contains(&processed.image, b"GPS-SECRET"));
+        // 丢弃 EXIF 前已按方向旋转
+        assert_eq!((processed.width, processed.height), (20, 40));
+    }
+
+    #[test]
+    fn rejects_unsupported_or_fake_types() {
+        let gif = b"GIF89a\x01\x00\x01\x00\x00\x00\x00;".as_slice();
+        let text = b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>".as_slice();
+        // PNG 文件头后面跟着的不是图片数据
+        let fake_png = b"\x89PNG\r\n\x1a\nnot really a png".as_slice();
+
+        for data in [gif, text, fake_png] {
+            let err = process_image(data, MediaKind::Cover, &config())
+                .err()
+                .unwrap();
+            assert_eq!(err.code(), code::BAD_REQUEST);
+        }
+    }
+
+    #[test]
+    fn rejects_oversized_dimensions() {
+        let data = image(100, 10, ImageFormat::Png);
+        let config = Media {
+            max_dimension: 50,
+            ..config()
+        };
+
+        let err = process_image(&data, MediaKind::Cover, &config)
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), code::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn rejects_oversized_files() {
+        let root = std::env::temp_dir().join(format!("media-test-{}", std::process::id()));
+        let service = MediaServiceImpl {
+            blob_store: Arc::new(LocalBlobStore::new(&LocalStorage {
+                root: root.to_string_lossy().into_owned(),
+                public_base_url: "http://localhost/media".into(),
+            })),
+            id_generator: Arc::new(RwLock::new(SnowflakeIdGenerator::new(1, 1))),
+            media_config: Media {
+                max_upload_size: 16,
+                ..config()
+            },
+        };
+
+        let err = service
+            .upload_image(1, MediaKind::Cover, Bytes::from(vec![0u8; 17]))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::BadRequest(ref s) if s.contains("16 byte limit")));
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn generates_thumbnails_at_configured_size() {
+        let data = image(200, 100, ImageFormat::Png);
+
+        // 封面保留原图，缩略图按最长边缩放
+        let cover = process_image(&data, MediaKind::Cover, &config()).unwrap();
+        assert_eq!((cover.width, cover.height), (200, 100));
+        let thumbnail = image::load_from_memory(&cover.thumbnail.unwrap()).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (50, 25));
+
+        // 头像裁剪为正方形，不另外生成缩略图
+        let avatar = process_image(&data, MediaKind::Avatar, &config()).unwrap();
+        assert_eq!((avatar.width, avatar.height), (50, 50));
+        assert!(avatar.thumbnail.is_none());
+    }
+}
@@ -1,2 +1,3 @@
 pub mod article_service;
 pub mod authorship_service;
+pub mod media_service;
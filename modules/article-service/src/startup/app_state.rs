@@ -1,4 +1,6 @@
+use common_grpc::UserClient;
 use common_redis::RedisClient;
+use common_registry::RegistryClient;
 use snowflake::SnowflakeIdGenerator;
 use sqlx::PgPool;
 use std::sync::Arc;
@@ -6,8 +8,10 @@ use tokio::sync::RwLock;
 
 use crate::{
     config::application::AppConfig,
-    grpc::user_client::UserServiceGrpcClient,
-    services::{article_service::ArticleService, authorship_service::AuthorshipService},
+    services::{
+        article_service::ArticleService, authorship_service::AuthorshipService,
+        media_service::MediaService,
+    },
 };
 
 /// 应用状态
@@ -18,9 +22,10 @@ pub struct AppState {
     // 业务服务
     pub article_service: Arc<dyn ArticleService>,
     pub authorship_service: Arc<dyn AuthorshipService>,
+    pub media_service: Arc<dyn MediaService>,
 
     // gRPC 客户端
-    pub user_grpc_client: UserServiceGrpcClient,
+    pub user_client: UserClient,
 
     // 基础设施组件
     #[allow(dead_code)]
@@ -29,6 +34,7 @@ pub struct AppState {
     pub db_pool: PgPool,
     #[allow(dead_code)]
     pub id_generator: Arc<RwLock<SnowflakeIdGenerator>>,
+    pub registry: Option<RegistryClient>,
 
     // 配置
     #[allow(dead_code)]
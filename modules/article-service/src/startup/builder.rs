@@ -1,15 +1,22 @@
 use common_core::AppError;
+use common_db::{
+    build_pool,
+    migrate::{Migrator, run_migrations},
+    register_pool_metrics,
+};
+use common_grpc::{ServiceToken, UserClient};
 use common_redis::RedisClient;
+use common_registry::RegistryClient;
+use common_storage::build_blob_store;
 use snowflake::SnowflakeIdGenerator;
-use sqlx::postgres::PgPoolOptions;
 use std::sync::Arc;
 
 use crate::{
     config::application::AppConfig,
-    grpc::user_client::UserServiceGrpcClient,
     services::{
         article_service::{ArticleService, ArticleServiceImpl},
         authorship_service::{AuthorshipService, AuthorshipServiceImpl},
+        media_service::{MediaService, MediaServiceImpl},
     },
 };
 
@@ -17,22 +24,32 @@ use super::AppState;
 
 /// 加载应用配置
 pub fn init_app_config() -> Result<AppConfig, AppError> {
-    AppConfig::from_default_yaml()
+    AppConfig::load()
+}
+
+/// 数据库迁移脚本（`modules/article-service/migrations`）
+fn migrator() -> Migrator {
+    sqlx::migrate!("./migrations")
+}
+
+/// 只执行数据库迁移（`article-service migrate` 子命令）
+pub async fn migrate_database(app_config: &AppConfig) -> Result<(), AppError> {
+    let db_pool = build_pool(&app_config.database).await?;
+    run_migrations(&db_pool, migrator(), &app_config.server.name).await
 }
 
 /// 初始化应用（基础设施 + 业务服务）
 pub async fn init_app_state(app_config: AppConfig) -> Result<AppState, AppError> {
     // 1. 初始化 Redis 客户端
     let redis_client = RedisClient::new(app_config.redis.clone()).await?;
+    redis_client.register_pool_metrics("redis");
 
-    // 2. 初始化数据库连接池
-    let db_url = app_config.database.connection_url();
-    let db_pool = PgPoolOptions::new()
-        .max_connections(app_config.database.max_connections)
-        .min_connections(app_config.database.min_connections)
-        .connect(&db_url)
-        .await
-        .map_err(|e| AppError::Db(format!("Failed to connect to database: {}", e)))?;
+    // 2. 初始化数据库连接池，按配置执行迁移
+    let db_pool = build_pool(&app_config.database).await?;
+    register_pool_metrics(&db_pool);
+    if app_config.database.migrate_on_startup {
+        run_migrations(&db_pool, migrator(), &app_config.server.name).await?;
+    }
 
     // 3. 初始化 ID 生成器
     let id_generator = Arc::new(tokio::sync::RwLock::new(SnowflakeIdGenerator::new(
@@ -40,9 +57,18 @@ pub async fn init_app_state(app_config: AppConfig) -> Result<AppState, AppError>
         app_config.snowflake.node_id,
     )));
 
-    // 4. 初始化 gRPC 客户端
-    let user_grpc_client =
-        UserServiceGrpcClient::new(app_config.services.user_service_grpc.clone()).await?;
+    // 4. 初始化注册中心客户端（启动后在 main 中注册本实例）与 gRPC 客户端
+    let registry = app_config
+        .registry
+        .as_ref()
+        .map(RegistryClient::new)
+        .transpose()?;
+    let user_client = UserClient::connect(
+        &app_config.services.user_service,
+        registry.as_ref(),
+        ServiceToken::new(&app_config.server.name, &app_config.identity),
+    )
+    .await?;
 
     // 5. 先初始化 AuthorshipService
     let authorship_service = Arc::new(AuthorshipServiceImpl {
@@ -58,13 +84,23 @@ pub async fn init_app_state(app_config: AppConfig) -> Result<AppState, AppError>
         id_generator: id_generator.clone(),
     }) as Arc<dyn ArticleService>;
 
+    // 7. 初始化对象存储与图片上传服务
+    let blob_store = build_blob_store(&app_config.storage)?;
+    let media_service = Arc::new(MediaServiceImpl {
+        blob_store,
+        id_generator: id_generator.clone(),
+        media_config: app_config.media.clone(),
+    }) as Arc<dyn MediaService>;
+
     Ok(AppState {
         article_service,
         authorship_service,
-        user_grpc_client,
+        media_service,
+        user_client,
         redis_client,
         db_pool,
         id_generator,
+        registry,
         app_config: Arc::new(app_config),
     })
 }
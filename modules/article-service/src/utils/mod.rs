@@ -1 +0,0 @@
-pub mod transaction;
\ No newline at end of file
@@ -1,42 +1,14 @@
+use common_config::ConfigLoader;
 use common_core::AppError;
+use common_db::application::Database;
 use common_redis::application::Redis;
+use common_registry::application::Registry;
+use common_tracing::application::Logs;
+use common_web::application::{Health, Shutdown};
 use serde::Deserialize;
-use std::fs;
 
-#[derive(Debug, Clone, Deserialize)]
-pub struct Database {
-    pub url: String,
-    pub username: String,
-    pub password: String,
-    pub max_connections: u32,
-    pub min_connections: u32,
-}
-
-impl Database {
-    pub fn connection_url(&self) -> String {
-        if !self.username.is_empty() && !self.password.is_empty() {
-            if let Some(at_pos) = self.url.rfind('@') {
-                let host_part = &self.url[at_pos..];
-                format!(
-                    "postgres://{}:{}{}",
-                    self.username, self.password, host_part
-                )
-            } else {
-                self.url.replace(
-                    "postgres://",
-                    &format!("postgres://{}:{}@", self.username, self.password),
-                )
-            }
-        } else {
-            self.url.clone()
-        }
-    }
-}
-
-#[derive(Debug, Clone, Deserialize)]
-pub struct Logs {
-    pub path: String,
-}
+/// 默认配置文件路径（相对工作区根目录）
+pub const DEFAULT_CONFIG_PATH: &str = "modules/demo-service/application.yaml";
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Snowflake {
@@ -48,6 +20,10 @@ pub struct Snowflake {
 pub struct Server {
     pub name: String,
     pub bind_addr: String,
+    #[serde(default)]
+    pub shutdown: Shutdown,
+    #[serde(default)]
+    pub health: Health,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -56,36 +32,15 @@ pub struct AppConfig {
     pub database: Database,
     pub snowflake: Snowflake,
     pub server: Server,
+    pub logs: Logs,
+    /// 服务注册，不配置时不注册（由调用方使用固定地址访问）
+    #[serde(default)]
+    pub registry: Option<Registry>,
 }
 
 impl AppConfig {
-    /// 从 YAML 文件加载配置
-    pub fn from_yaml(path: &str) -> Result<Self, AppError> {
-        let content = fs::read_to_string(path).map_err(|e| {
-            AppError::internal(format!("Failed to read config file {}: {}", path, e))
-        })?;
-
-        let config: AppConfig = serde_yml::from_str(&content)
-            .map_err(|e| AppError::internal(format!("Failed to parse config file: {}", e)))?;
-
-        Ok(config)
-    }
-
-    /// 从默认路径加载配置
-    pub fn from_default_yaml() -> Result<Self, AppError> {
-        // 尝试多个可能的路径
-        let possible_paths = [
-            "src/application.yaml",
-            "application.yaml",
-            "modules/article-service/application.yaml",
-        ];
-        for path in &possible_paths {
-            if let Ok(config) = Self::from_yaml(path) {
-                return Ok(config);
-            }
-        }
-        Err(AppError::internal(
-            "Failed to load config from any default path. Tried: src/application.yaml, modules/article-service/application.yaml, application.yaml",
-        ))
+    /// 加载配置（分层规则见 `common_config`）
+    pub fn load() -> Result<Self, AppError> {
+        ConfigLoader::new(DEFAULT_CONFIG_PATH).load()
     }
 }
@@ -1,6 +1,5 @@
 pub mod config;
 mod domain;
-mod grpc;
 mod repository;
 mod routes;
 mod services;
@@ -9,28 +8,22 @@ mod startup;
 pub use startup::AppState;
 
 use common_core::AppError;
-use startup::{init_app_config, init_app_state, start_http_server};
+use common_tracing::TracingService;
+use common_web::runner::ServiceRunner;
+use startup::{health_checks, init_app_config, init_app_state, start_http_server};
 
 #[tokio::main]
 async fn main() -> Result<(), AppError> {
     // 1. 加载配置
     let app_config = init_app_config()?;
     // 初始化日志（输出到文件和控制台）
-    let file_appender = tracing_appender::rolling::daily("", "");
-    let (non_blocking, _guard) = tracing_appender::non_blocking(file_appender);
-
-    use tracing_subscriber::fmt::writer::MakeWriterExt;
-    let stdout = std::io::stdout.and(non_blocking);
-
-    tracing_subscriber::fmt()
-        .with_target(false)
-        .with_writer(stdout)
-        .compact()
-        .init();
+    let _guard = TracingService::init(&app_config.server.name, &app_config.logs);
 
     tracing::info!("🚀 User Service starting...");
 
     let http_bind_addr = app_config.server.bind_addr.clone();
+    let service_name = app_config.server.name.clone();
+    let mut runner = ServiceRunner::new(&service_name, &app_config.server.shutdown);
 
     // 2. 初始化应用（基础设施 + 业务服务）
     let app_state = match init_app_state(app_config).await {
@@ -41,12 +34,23 @@ async fn main() -> Result<(), AppError> {
         }
     };
 
-    // 3. 启动服务器
-    let http_server = start_http_server(app_state.clone(), http_bind_addr);
+    // 3. 注册到注册中心，停机时注销
+    let lifecycle = runner.lifecycle();
+    if let Some(registry) = &app_state.registry {
+        let instance = registry.config().instance(&http_bind_addr, None)?;
+        if let Some(registration) = registry.register(&service_name, instance, lifecycle.shutdown())
+        {
+            runner.add_background("Service registration", registration);
+        }
+    }
 
-    // 4. 等待服务器运行
-    let _ = tokio::try_join!(http_server)
-        .map_err(|e| AppError::internal(format!("Server error: {}", e)))?;
+    // 4. 启动服务器
+    let health = health_checks(&app_state, lifecycle.clone());
+    runner.spawn_server(
+        "HTTP",
+        start_http_server(app_state, http_bind_addr, health, lifecycle),
+    );
 
-    Ok(())
+    // 5. 运行到收到 SIGTERM/SIGINT，优雅停机后返回（日志守卫随之刷新）
+    runner.run().await
 }
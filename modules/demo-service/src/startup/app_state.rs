@@ -1,4 +1,5 @@
 use common_redis::RedisClient;
+use common_registry::RegistryClient;
 use snowflake::SnowflakeIdGenerator;
 use sqlx::PgPool;
 use std::sync::Arc;
@@ -18,6 +19,7 @@ pub struct AppState {
     pub redis_client: RedisClient,
     pub db_pool: PgPool,
     pub id_generator: Arc<RwLock<SnowflakeIdGenerator>>,
+    pub registry: Option<RegistryClient>,
 
     // 配置
     pub app_config: Arc<AppConfig>,
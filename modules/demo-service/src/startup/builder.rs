@@ -1,7 +1,8 @@
 use common_core::AppError;
+use common_db::{build_pool, register_pool_metrics};
 use common_redis::RedisClient;
+use common_registry::RegistryClient;
 use snowflake::SnowflakeIdGenerator;
-use sqlx::postgres::PgPoolOptions;
 use std::sync::Arc;
 
 use crate::config::application::AppConfig;
@@ -10,22 +11,18 @@ use super::AppState;
 
 /// 加载应用配置
 pub fn init_app_config() -> Result<AppConfig, AppError> {
-    AppConfig::from_default_yaml()
+    AppConfig::load()
 }
 
 /// 初始化应用（基础设施 + 业务服务）
 pub async fn init_app_state(app_config: AppConfig) -> Result<AppState, AppError> {
     // 1. 初始化 Redis 客户端
     let redis_client = RedisClient::new(app_config.redis.clone()).await?;
+    redis_client.register_pool_metrics("redis");
 
     // 2. 初始化数据库连接池
-    let db_url = app_config.database.connection_url();
-    let db_pool = PgPoolOptions::new()
-        .max_connections(app_config.database.max_connections)
-        .min_connections(app_config.database.min_connections)
-        .connect(&db_url)
-        .await
-        .map_err(|e| AppError::Db(format!("Failed to connect to database: {}", e)))?;
+    let db_pool = build_pool(&app_config.database).await?;
+    register_pool_metrics(&db_pool);
 
     // 3. 初始化 ID 生成器
     let id_generator = Arc::new(tokio::sync::RwLock::new(SnowflakeIdGenerator::new(
@@ -33,7 +30,14 @@ pub async fn init_app_state(app_config: AppConfig) -> Result<AppState, AppError>
         app_config.snowflake.node_id,
     )));
 
-    // 4. 初始化业务服务
+    // 4. 初始化注册中心客户端（启动后在 main 中注册本实例）
+    let registry = app_config
+        .registry
+        .as_ref()
+        .map(RegistryClient::new)
+        .transpose()?;
+
+    // 5. 初始化业务服务
     // let user_service = Arc::new(UserServiceImpl {
     //     redis_client: redis_client.clone(),
     //     db_pool: db_pool.clone(),
@@ -44,6 +48,7 @@ pub async fn init_app_state(app_config: AppConfig) -> Result<AppState, AppError>
         redis_client,
         db_pool,
         id_generator,
+        registry,
         app_config: Arc::new(app_config),
     })
 }
@@ -1,25 +1,46 @@
-use axum::{Router, routing::get};
-use tokio::task::JoinHandle;
+use axum::{Router, middleware, routing::get};
+use common_core::AppError;
+use common_metrics::{http_metrics, metrics_handler};
+use common_web::{health::HealthChecks, runner::Lifecycle};
 
 use super::AppState;
 
-/// 启动 HTTP 服务器
-pub fn start_http_server(app_state: AppState, bind_addr: String) -> JoinHandle<()> {
-    tokio::spawn(async move {
-        let app = Router::new()
-            .route("/health", get(|| async { "ok" }))
-            // .merge(user_router::router())
-            .with_state(app_state);
+/// 就绪检查：数据库和 Redis 都是关键依赖
+pub fn health_checks(app_state: &AppState, lifecycle: Lifecycle) -> HealthChecks {
+    let db_pool = app_state.db_pool.clone();
+    let redis_client = app_state.redis_client.clone();
+    HealthChecks::new(lifecycle, &app_state.app_config.server.health)
+        .critical("postgres", move || {
+            let db_pool = db_pool.clone();
+            async move { common_db::ping(&db_pool).await }
+        })
+        .critical("redis", move || {
+            let redis_client = redis_client.clone();
+            async move { redis_client.ping().await }
+        })
+}
 
-        let listener = tokio::net::TcpListener::bind(&bind_addr)
-            .await
-            .expect("Failed to bind HTTP server");
+/// 启动 HTTP 服务器，收到停机通知后处理完进行中的请求再返回
+pub async fn start_http_server(
+    app_state: AppState,
+    bind_addr: String,
+    health: HealthChecks,
+    lifecycle: Lifecycle,
+) -> Result<(), AppError> {
+    let app = Router::new()
+        // 存活 / 就绪探针
+        .merge(health.router())
+        .route("/metrics", get(metrics_handler))
+        // .merge(user_router::router())
+        // 请求指标
+        .layer(middleware::from_fn(http_metrics))
+        .with_state(app_state);
 
-        println!(
-            "HTTP server listening on {}",
-            listener.local_addr().unwrap()
-        );
+    let listener = tokio::net::TcpListener::bind(&bind_addr).await?;
+    tracing::info!("HTTP server listening on {}", listener.local_addr()?);
 
-        axum::serve(listener, app).await.expect("HTTP server error");
-    })
+    axum::serve(listener, app)
+        .with_graceful_shutdown(lifecycle.shutdown())
+        .await?;
+    Ok(())
 }
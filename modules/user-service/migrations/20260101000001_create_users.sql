@@ -0,0 +1,13 @@
+-- 用户表（id 由 Snowflake 生成）
+CREATE TABLE IF NOT EXISTS users (
+    id            BIGINT       NOT NULL,
+    username      VARCHAR(50)  NOT NULL,
+    email         VARCHAR(255),
+    password_hash VARCHAR(255),
+    avatar_url    VARCHAR(512),
+    created_at    TIMESTAMPTZ  NOT NULL DEFAULT now(),
+    updated_at    TIMESTAMPTZ  NOT NULL DEFAULT now(),
+    CONSTRAINT users_pkey PRIMARY KEY (id),
+    CONSTRAINT users_username_key UNIQUE (username),
+    CONSTRAINT users_email_key UNIQUE (email)
+);
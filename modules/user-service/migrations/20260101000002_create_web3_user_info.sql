@@ -0,0 +1,14 @@
+-- 用户钱包地址（每条链一个地址）
+CREATE TABLE IF NOT EXISTS web3_user_info (
+    id         BIGINT       NOT NULL,
+    user_id    BIGINT       NOT NULL,
+    chain_id   BIGINT       NOT NULL,
+    address    VARCHAR(128) NOT NULL,
+    created_at TIMESTAMPTZ  NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ  NOT NULL DEFAULT now(),
+    CONSTRAINT user_web3_info_pkey PRIMARY KEY (id),
+    CONSTRAINT unique_user_chain UNIQUE (user_id, chain_id),
+    CONSTRAINT fk_user FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_web3_address ON web3_user_info (address);
@@ -0,0 +1,2 @@
+-- 由旧版 sql/ 脚本建出的 users 表没有 avatar_url（建表语句因 IF NOT EXISTS 不会执行），单独补列
+ALTER TABLE users ADD COLUMN IF NOT EXISTS avatar_url VARCHAR(512);
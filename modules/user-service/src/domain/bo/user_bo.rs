@@ -1,26 +1,35 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use validator::Validate;
 
-/// 用户传输模型
-#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+/// 用户传输模型（长度上限与 users 表一致）
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, Validate)]
 pub struct UserBo {
     pub id: Option<i64>,
+    #[validate(length(min = 1, max = 50, message = "username must be 1-50 characters"))]
     pub username: String,
+    #[validate(
+        email(message = "email must be a valid email address"),
+        length(max = 255, message = "email must be at most 255 characters")
+    )]
     pub email: Option<String>,
     pub password_hash: Option<String>,
 }
 
 /// Web3 用户信息传输模型
-#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, Validate)]
 pub struct Web3UserInfoBo {
     pub chain_id: i64,
+    #[validate(length(min = 1, max = 128, message = "address must be 1-128 characters"))]
     pub address: String,
 }
 
 /// 用户完整信息（包含 web3 信息）传输模型
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct UserInfoBo {
     #[serde(flatten)]
+    #[validate(nested)]
     pub user: UserBo,
+    #[validate(nested)]
     pub web3_info: Option<Web3UserInfoBo>,
 }
@@ -9,6 +9,7 @@ pub struct User {
     pub username: String,
     pub email: Option<String>,
     pub password_hash: Option<String>,
+    pub avatar_url: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
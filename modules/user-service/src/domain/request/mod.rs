@@ -1 +1 @@
-
+pub mod user;
@@ -0,0 +1,23 @@
+use serde::Deserialize;
+use validator::{Validate, ValidationError};
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateAvatarRequest {
+    /// 通常为 article-service `/media/avatar` 返回的地址
+    #[validate(
+        url(message = "avatar_url must be a valid URL"),
+        length(max = 512, message = "avatar_url must be at most 512 characters"),
+        custom(function = "validate_http_scheme")
+    )]
+    pub avatar_url: String,
+}
+
+/// 只允许 http(s) 链接，避免 `javascript:` 等协议
+fn validate_http_scheme(url: &str) -> Result<(), ValidationError> {
+    if url.starts_with("https://") || url.starts_with("http://") {
+        Ok(())
+    } else {
+        Err(ValidationError::new("url_scheme")
+            .with_message("avatar_url must be an http(s) link".into()))
+    }
+}
@@ -10,6 +10,7 @@ pub struct Web3UserInfo {
 pub struct UserInfoResponse {
     pub user_id: i64,
     pub username: String,
+    pub avatar_url: Option<String>,
     pub web3_user_info: Option<Web3UserInfo>,
 }
 
@@ -18,6 +19,7 @@ impl From<crate::domain::model::user::UserInfo> for UserInfoResponse {
         Self {
             user_id: ui.user.id,
             username: ui.user.username,
+            avatar_url: ui.user.avatar_url,
             web3_user_info: ui.web3_info.map(|w| Web3UserInfo {
                 chain_id: w.chain_id,
                 address: w.address,
@@ -1,8 +1,11 @@
+use common_core::AppError;
+use common_grpc::VerifyServiceToken;
 use common_proto::user::{
     RegisterType, RegisterUserReq, RegisterUserRes, UserInfoReq, UserInfoRes, Web3InfoReq,
     user_service_server::{UserService as UserServiceTrait, UserServiceServer},
 };
-use tonic::{Request, Response, Status};
+use tonic::{Request, Response, Status, service::interceptor::InterceptedService};
+use validator::Validate;
 
 use crate::{
     domain::bo::user_bo::{UserBo, UserInfoBo, Web3UserInfoBo},
@@ -19,8 +22,10 @@ impl UserGrpcService {
         Self { app_state }
     }
 
-    pub fn into_server(self) -> UserServiceServer<Self> {
-        UserServiceServer::new(self)
+    /// 只接受带有效服务间凭证（`x-service-token`）的调用
+    pub fn into_server(self) -> InterceptedService<UserServiceServer<Self>, VerifyServiceToken> {
+        let verifier = VerifyServiceToken::new(&self.app_state.app_config.identity);
+        UserServiceServer::with_interceptor(self, verifier)
     }
 }
 
@@ -36,8 +41,7 @@ impl UserServiceTrait for UserGrpcService {
             .app_state
             .user_service
             .get_user_info(req.user_id)
-            .await
-            .map_err(|e| Status::internal(format!("Failed to get user info: {}", e)))?;
+            .await?;
 
         match user_info {
             Some(user_info) => {
@@ -47,6 +51,7 @@ impl UserServiceTrait for UserGrpcService {
                     email: user_info.user.email,
                     created_at: user_info.user.created_at.to_rfc3339(),
                     updated_at: user_info.user.updated_at.to_rfc3339(),
+                    avatar_url: user_info.user.avatar_url,
                     web3_info: user_info.web3_info.map(|w| common_proto::user::Web3Info {
                         chain_id: w.chain_id,
                         address: w.address,
@@ -68,8 +73,7 @@ impl UserServiceTrait for UserGrpcService {
             .app_state
             .user_service
             .get_user_info_by_web3(req.chain_id, req.address)
-            .await
-            .map_err(|e| Status::internal(format!("Failed to get user info by web3: {}", e)))?;
+            .await?;
 
         match user_info {
             Some(user_info) => {
@@ -79,6 +83,7 @@ impl UserServiceTrait for UserGrpcService {
                     email: user_info.user.email,
                     created_at: user_info.user.created_at.to_rfc3339(),
                     updated_at: user_info.user.updated_at.to_rfc3339(),
+                    avatar_url: user_info.user.avatar_url,
                     web3_info: user_info.web3_info.map(|w| common_proto::user::Web3Info {
                         chain_id: w.chain_id,
                         address: w.address,
@@ -160,14 +165,16 @@ impl UserServiceTrait for UserGrpcService {
             },
             web3_info,
         };
+        user_info_bo
+            .validate()
+            .map_err(|e| AppError::validation(e.to_string()))?;
 
         // 3. 调用 Service
         let user_id = self
             .app_state
             .user_service
             .create_user(user_info_bo)
-            .await
-            .map_err(|e| Status::internal(format!("Failed to create user: {}", e)))?;
+            .await?;
 
         Ok(Response::new(RegisterUserRes { user_id }))
     }
@@ -1,6 +1,6 @@
 use crate::domain::model::user::User;
 use async_trait::async_trait;
-use common_core::AppError;
+use common_core::{AppError, error::DbResultExt};
 use sqlx::PgConnection;
 
 /// 用户数据访问层 trait
@@ -24,6 +24,13 @@ pub trait UserRepository: Send + Sync {
         email: &str,
     ) -> Result<Option<User>, AppError>;
     async fn inster(&self, executor: &mut PgConnection, user: &User) -> Result<(), AppError>;
+    /// 更新头像，返回是否有记录被更新
+    async fn update_avatar(
+        &self,
+        executor: &mut PgConnection,
+        id: i64,
+        avatar_url: &str,
+    ) -> Result<bool, AppError>;
 }
 
 pub struct UserRepositoryImpl;
@@ -46,7 +53,7 @@ impl UserRepository for UserRepositoryImpl {
             .bind(id)
             .fetch_optional(executor)
             .await
-            .map_err(|e| AppError::Db(format!("Failed to fetch user by id: {}", e)))
+            .db_context("Failed to fetch user by id")
     }
 
     async fn find_by_username(
@@ -58,7 +65,7 @@ impl UserRepository for UserRepositoryImpl {
             .bind(username)
             .fetch_optional(executor)
             .await
-            .map_err(|e| AppError::Db(format!("Failed to fetch user by username: {}", e)))
+            .db_context("Failed to fetch user by username")
     }
 
     async fn find_by_email(
@@ -70,23 +77,39 @@ impl UserRepository for UserRepositoryImpl {
             .bind(email)
             .fetch_optional(executor)
             .await
-            .map_err(|e| AppError::Db(format!("Failed to fetch user by email: {}", e)))
+            .db_context("Failed to fetch user by email")
     }
 
     async fn inster(&self, executor: &mut PgConnection, user: &User) -> Result<(), AppError> {
         sqlx::query(
-            "INSERT INTO users (id, username, email, password_hash, created_at, updated_at) 
-             VALUES ($1, $2, $3, $4, $5, $6)",
+            "INSERT INTO users (id, username, email, password_hash, avatar_url, created_at, updated_at) 
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
         )
         .bind(user.id)
         .bind(&user.username)
         .bind(&user.email)
         .bind(&user.password_hash)
+        .bind(&user.avatar_url)
         .bind(user.created_at)
         .bind(user.updated_at)
         .execute(executor)
-        .await
-        .map_err(|e| AppError::Db(format!("Failed to create user: {}", e)))?;
+        .await.db_context("Failed to create user")?;
         Ok(())
     }
+
+    async fn update_avatar(
+        &self,
+        executor: &mut PgConnection,
+        id: i64,
+        avatar_url: &str,
+    ) -> Result<bool, AppError> {
+        let result =
+            sqlx::query("UPDATE users SET avatar_url = $1, updated_at = now() WHERE id = $2")
+                .bind(avatar_url)
+                .bind(id)
+                .execute(executor)
+                .await
+                .db_context("Failed to update user avatar")?;
+        Ok(result.rows_affected() > 0)
+    }
 }
@@ -1,6 +1,6 @@
 use crate::domain::model::user::Web3UserInfo;
 use async_trait::async_trait;
-use common_core::AppError;
+use common_core::{AppError, error::DbResultExt};
 use sqlx::PgConnection;
 
 #[async_trait]
@@ -48,7 +48,7 @@ impl Web3UserRepository for Web3UserRepositoryImpl {
             .bind(user_id)
             .fetch_optional(executor)
             .await
-            .map_err(|e| AppError::Db(format!("Failed to fetch web3 info: {}", e)))
+            .db_context("Failed to fetch web3 info")
     }
 
     async fn insert(
@@ -68,7 +68,7 @@ impl Web3UserRepository for Web3UserRepositoryImpl {
         .bind(info.updated_at)
         .execute(executor)
         .await
-        .map_err(|e| AppError::Db(format!("Failed to save web3 info: {}", e)))?;
+        .db_context("Failed to save web3 info")?;
         Ok(())
     }
 
@@ -85,6 +85,6 @@ impl Web3UserRepository for Web3UserRepositoryImpl {
         .bind(address)
         .fetch_optional(executor)
         .await
-        .map_err(|e| AppError::Db(format!("Failed to fetch web3 info by address: {}", e)))
+        .db_context("Failed to fetch web3 info by address")
     }
 }
@@ -1,26 +1,55 @@
-use axum::{Json, Router, extract::State, http::HeaderMap, routing::get};
-use common_web::{domain::r::R, error::ApiError};
+use axum::{
+    Json, Router,
+    extract::State,
+    http::HeaderMap,
+    routing::{get, put},
+};
+use common_core::AppError;
+use common_web::{domain::r::R, error::ApiError, validation::ValidatedJson};
 
-use crate::{domain::response::user::UserInfoResponse, startup::AppState};
+use crate::{
+    domain::{request::user::UpdateAvatarRequest, response::user::UserInfoResponse},
+    startup::AppState,
+};
 
 pub fn router() -> Router<AppState> {
-    Router::new().route("/info", get(get_user_info))
+    Router::new()
+        .route("/info", get(get_user_info))
+        .route("/avatar", put(update_avatar))
 }
 
 async fn get_user_info(
     State(app_state): State<AppState>,
     headers: HeaderMap,
 ) -> Result<Json<R<UserInfoResponse>>, ApiError> {
-    let user_id = headers
-        .get("x-user-id")
-        .and_then(|h| h.to_str().ok())
-        .and_then(|s| s.parse::<i64>().ok())
-        .ok_or_else(|| ApiError(common_core::AppError::internal("User not authenticated")))?;
+    let user_id = get_user_id_from_header(&headers)?;
 
     let user_info_opt = app_state.user_service.get_user_info(user_id).await?;
     if let Some(ui) = user_info_opt {
         Ok(Json(R::ok(UserInfoResponse::from(ui))))
     } else {
-        Err(ApiError(common_core::AppError::internal("User not found")))
+        Err(ApiError(AppError::not_found("User not found")))
     }
 }
+
+/// 更新头像
+async fn update_avatar(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+    ValidatedJson(req): ValidatedJson<UpdateAvatarRequest>,
+) -> Result<Json<R<()>>, ApiError> {
+    let user_id = get_user_id_from_header(&headers)?;
+    app_state
+        .user_service
+        .update_avatar(user_id, req.avatar_url)
+        .await?;
+    Ok(Json(R::ok(())))
+}
+
+fn get_user_id_from_header(headers: &HeaderMap) -> Result<i64, ApiError> {
+    headers
+        .get("x-user-id")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|s| s.parse::<i64>().ok())
+        .ok_or_else(|| ApiError(AppError::unauthorized("User not authenticated")))
+}
@@ -4,7 +4,7 @@ use crate::repository::user_repository::{UserRepository, UserRepositoryImpl};
 use crate::repository::web3_user_info_repository::{Web3UserRepository, Web3UserRepositoryImpl};
 use async_trait::async_trait;
 use chrono::Utc;
-use common_core::AppError;
+use common_core::{AppError, error::DbResultExt};
 use common_redis::RedisClient;
 use snowflake::SnowflakeIdGenerator;
 use sqlx::PgPool;
@@ -26,6 +26,8 @@ pub trait UserService: Send + Sync {
     ) -> Result<Option<UserInfo>, AppError>;
 
     async fn create_user(&self, user_info_bo: UserInfoBo) -> Result<i64, AppError>;
+
+    async fn update_avatar(&self, user_id: i64, avatar_url: String) -> Result<(), AppError>;
 }
 
 pub struct UserServiceImpl {
@@ -39,11 +41,7 @@ pub struct UserServiceImpl {
 impl UserService for UserServiceImpl {
     /// 获取用户信息
     async fn get_user_info(&self, user_id: i64) -> Result<Option<UserInfo>, AppError> {
-        let mut conn = self
-            .db_pool
-            .acquire()
-            .await
-            .map_err(|e| AppError::Db(e.to_string()))?;
+        let mut conn = self.db_pool.acquire().await?;
 
         let user_opt = USER_REPO.find_by_id(&mut conn, user_id).await?;
 
@@ -61,11 +59,7 @@ impl UserService for UserServiceImpl {
         chain_id: i64,
         address: String,
     ) -> Result<Option<UserInfo>, AppError> {
-        let mut conn = self
-            .db_pool
-            .acquire()
-            .await
-            .map_err(|e| AppError::Db(e.to_string()))?;
+        let mut conn = self.db_pool.acquire().await?;
 
         let web3_info_opt = WEB3_REPO
             .find_by_address(&mut conn, chain_id, &address)
@@ -100,6 +94,7 @@ impl UserService for UserServiceImpl {
                 username: user_info_bo.user.username,
                 email: user_info_bo.user.email,
                 password_hash: user_info_bo.user.password_hash,
+                avatar_url: None,
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
             },
@@ -124,7 +119,7 @@ impl UserService for UserServiceImpl {
             .db_pool
             .begin()
             .await
-            .map_err(|e| AppError::Db(format!("Begin transaction failed: {}", e)))?;
+            .db_context("Begin transaction failed")?;
 
         // 4. 插入用户表
         USER_REPO.inster(&mut tx, &user_info.user).await?;
@@ -138,10 +133,21 @@ impl UserService for UserServiceImpl {
         }
 
         // 6. 提交事务
-        tx.commit()
-            .await
-            .map_err(|e| AppError::Db(format!("Commit transaction failed: {}", e)))?;
+        tx.commit().await.db_context("Commit transaction failed")?;
 
         Ok(user_id)
     }
+
+    /// 更新头像
+    async fn update_avatar(&self, user_id: i64, avatar_url: String) -> Result<(), AppError> {
+        let mut conn = self.db_pool.acquire().await?;
+
+        if !USER_REPO
+            .update_avatar(&mut conn, user_id, &avatar_url)
+            .await?
+        {
+            return Err(AppError::not_found("User not found"));
+        }
+        Ok(())
+    }
 }
@@ -1,49 +1,130 @@
-use axum::{routing::get, Router};
-use tokio::task::JoinHandle;
+use axum::{Router, middleware, routing::get};
+use common_core::AppError;
+use common_metrics::{GrpcMetricsLayer, http_metrics, metrics_handler};
+use common_proto::{
+    health::{HealthReporter, ServingStatus, health_reporter},
+    user::user_service_server::SERVICE_NAME,
+};
+use common_tracing::{grpc_server_span, http_trace, log_level_router};
+use common_web::{
+    health::HealthChecks,
+    identity::verify_identity,
+    request_id::{GrpcRequestIdLayer, request_id},
+    runner::Lifecycle,
+};
+use std::{sync::Arc, time::Duration};
+use tokio::time::MissedTickBehavior;
 
 use crate::grpc::user_grpc_service::UserGrpcService;
 use crate::routes::user_router;
 
 use super::AppState;
 
-/// 启动 HTTP 服务器
-pub fn start_http_server(app_state: AppState, bind_addr: String) -> JoinHandle<()> {
-    tokio::spawn(async move {
-        let app = Router::new()
-            .route("/health", get(|| async { "ok" }))
-            .merge(user_router::router())
-            .with_state(app_state);
-
-        let listener = tokio::net::TcpListener::bind(&bind_addr)
-            .await
-            .expect("Failed to bind HTTP server");
-
-        println!(
-            "HTTP server listening on {}",
-            listener.local_addr().unwrap()
-        );
-
-        axum::serve(listener, app)
-            .await
-            .expect("HTTP server error");
-    })
+/// gRPC 健康状态的刷新间隔
+const GRPC_HEALTH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 就绪检查：数据库和 Redis 都是关键依赖
+pub fn health_checks(app_state: &AppState, lifecycle: Lifecycle) -> HealthChecks {
+    let db_pool = app_state.db_pool.clone();
+    let redis_client = app_state.redis_client.clone();
+    HealthChecks::new(lifecycle, &app_state.app_config.server.health)
+        .critical("postgres", move || {
+            let db_pool = db_pool.clone();
+            async move { common_db::ping(&db_pool).await }
+        })
+        .critical("redis", move || {
+            let redis_client = redis_client.clone();
+            async move { redis_client.ping().await }
+        })
+}
+
+/// 启动 HTTP 服务器，收到停机通知后处理完进行中的请求再返回
+pub async fn start_http_server(
+    app_state: AppState,
+    bind_addr: String,
+    health: HealthChecks,
+    lifecycle: Lifecycle,
+) -> Result<(), AppError> {
+    let identity = Arc::new(app_state.app_config.identity.clone());
+    let app = Router::new()
+        // 存活 / 就绪探针
+        .merge(health.router())
+        .route("/metrics", get(metrics_handler))
+        .merge(user_router::router())
+        // 运行时调整日志级别
+        .merge(log_level_router(
+            app_state.app_config.logs.admin_token.as_deref(),
+        ))
+        // 只信任网关签名过的身份头
+        .layer(middleware::from_fn_with_state(identity, verify_identity))
+        // 请求指标
+        .layer(middleware::from_fn(http_metrics))
+        // 请求 ID（沿用网关传入的 X-Request-Id，记录到追踪 span）
+        .layer(middleware::from_fn(request_id))
+        // 链路追踪（最外层，接续网关传入的 traceparent）
+        .layer(middleware::from_fn(http_trace))
+        .with_state(app_state);
+
+    let listener = tokio::net::TcpListener::bind(&bind_addr).await?;
+    tracing::info!("HTTP server listening on {}", listener.local_addr()?);
+
+    axum::serve(listener, app)
+        .with_graceful_shutdown(lifecycle.shutdown())
+        .await?;
+    Ok(())
 }
 
-/// 启动 gRPC 服务器
-pub fn start_grpc_server(app_state: AppState, bind_addr: String) -> JoinHandle<()> {
-    tokio::spawn(async move {
-        let grpc_service = UserGrpcService::new(app_state);
+/// 启动 gRPC 服务器（附带标准健康检查服务），与 HTTP 服务器同时收到停机通知
+///
+/// 业务接口只接受带服务间凭证的调用，健康检查服务不需要凭证
+pub async fn start_grpc_server(
+    app_state: AppState,
+    bind_addr: String,
+    health: HealthChecks,
+    lifecycle: Lifecycle,
+) -> Result<(), AppError> {
+    let grpc_service = UserGrpcService::new(app_state);
 
-        let addr = bind_addr
-            .parse()
-            .expect("Failed to parse gRPC address");
+    let addr = bind_addr
+        .parse()
+        .map_err(|e| AppError::internal(format!("Invalid gRPC address {}: {}", bind_addr, e)))?;
 
-        println!("gRPC server listening on {}", addr);
+    tracing::info!("gRPC server listening on {}", addr);
+
+    let (reporter, health_service) = health_reporter();
+    let server = tonic::transport::Server::builder()
+        // 接续调用方在 metadata 中传入的 traceparent
+        .trace_fn(grpc_server_span)
+        .layer(GrpcMetricsLayer::server())
+        .layer(GrpcRequestIdLayer::server())
+        .add_service(health_service)
+        .add_service(grpc_service.into_server())
+        .serve_with_shutdown(addr, lifecycle.shutdown());
+
+    let (served, ()) = tokio::join!(server, report_grpc_health(reporter, health, lifecycle));
+    served.map_err(|e| AppError::internal(format!("gRPC server error: {}", e)))
+}
+
+/// 按就绪检查的结果刷新 gRPC 健康状态（整个服务器和 `user.UserService`），停机时置为 NOT_SERVING
+async fn report_grpc_health(reporter: HealthReporter, health: HealthChecks, lifecycle: Lifecycle) {
+    let mut ticker = tokio::time::interval(GRPC_HEALTH_INTERVAL);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = lifecycle.shutdown() => break,
+        }
+        let status = if health.report().await.is_ready() {
+            ServingStatus::Serving
+        } else {
+            ServingStatus::NotServing
+        };
+        set_grpc_health(&reporter, status).await;
+    }
+    set_grpc_health(&reporter, ServingStatus::NotServing).await;
+}
 
-        tonic::transport::Server::builder()
-            .add_service(grpc_service.into_server())
-            .serve(addr)
-            .await
-            .expect("gRPC server error");
-    })
+async fn set_grpc_health(reporter: &HealthReporter, status: ServingStatus) {
+    reporter.set_service_status("", status).await;
+    reporter.set_service_status(SERVICE_NAME, status).await;
 }
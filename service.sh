@@ -15,6 +15,14 @@ LOG_DIR="$PROJECT_DIR/logs"
 # 确保日志目录存在
 mkdir -p "$LOG_DIR"
 
+# 密钥（REDIS_PASSWORD、DATABASE_PASSWORD、JWT_SECRET、INTERNAL_IDENTITY_SECRET 等）不写在配置文件中，
+# 从 .env 读取后导出给各服务，未设置时服务启动失败
+if [ -f "$PROJECT_DIR/.env" ]; then
+    set -a
+    . "$PROJECT_DIR/.env"
+    set +a
+fi
+
 # 启动服务
 start_service() {
     local service=$1
@@ -1,48 +0,0 @@
-/*
- Navicat Premium Data Transfer
-
- Source Server         : local
- Source Server Type    : PostgreSQL
- Source Server Version : 170007 (170007)
- Source Host           : localhost:5432
- Source Catalog        : blog_v2
- Source Schema         : public
-
- Target Server Type    : PostgreSQL
- Target Server Version : 170007 (170007)
- File Encoding         : 65001
-
- Date: 13/01/2026 00:01:41
-*/
-
-
--- ----------------------------
--- Table structure for users
--- ----------------------------
-DROP TABLE IF EXISTS "public"."users";
-CREATE TABLE "public"."users" (
-  "id" int8 NOT NULL DEFAULT nextval('users_id_seq'::regclass),
-  "username" varchar(50) COLLATE "pg_catalog"."default" NOT NULL,
-  "email" varchar(255) COLLATE "pg_catalog"."default",
-  "password_hash" varchar(255) COLLATE "pg_catalog"."default",
-  "created_at" timestamptz(6) NOT NULL DEFAULT now(),
-  "updated_at" timestamptz(6) NOT NULL DEFAULT now()
-)
-;
-
--- ----------------------------
--- Records of users
--- ----------------------------
-INSERT INTO "public"."users" VALUES (1, 'gragon', NULL, NULL, '2026-01-06 10:50:14+08', '2026-01-06 10:50:14+08');
-INSERT INTO "public"."users" VALUES (7414486567504449536, '0xd2d6506637aa33a4efbcbcf6b559b86e5f9a28dc', NULL, NULL, '2026-01-07 10:02:32.192831+08', '2026-01-07 10:02:32.19284+08');
-
--- ----------------------------
--- Uniques structure for table users
--- ----------------------------
-ALTER TABLE "public"."users" ADD CONSTRAINT "users_username_key" UNIQUE ("username");
-ALTER TABLE "public"."users" ADD CONSTRAINT "users_email_key" UNIQUE ("email");
-
--- ----------------------------
--- Primary Key structure for table users
--- ----------------------------
-ALTER TABLE "public"."users" ADD CONSTRAINT "users_pkey" PRIMARY KEY ("id");